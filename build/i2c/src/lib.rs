@@ -159,8 +159,45 @@ struct I2cSensors {
 
     #[serde(default)]
     speed: usize,
+
+    /// thermal zone of any temperature sensors; this is only checked, and
+    /// isn't passed to the thermal task, which has no control loop (yet) to
+    /// use it
+    zone: Option<String>,
+
+    /// thresholds for any temperature sensors (see [`I2cThresholds`])
+    thresholds: Option<I2cThresholds>,
 }
 
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct I2cThresholds {
+    /// upper critical threshold; for thermal control, the temperature at
+    /// or above which we must power off
+    critical: Option<f32>,
+
+    /// upper non-critical threshold
+    noncritical: Option<f32>,
+
+    /// lower critical threshold
+    lower_critical: Option<f32>,
+
+    /// lower non-critical threshold
+    lower_noncritical: Option<f32>,
+
+    /// hysteresis for clearing any crossed threshold
+    hysteresis: Option<f32>,
+}
+
+//
+// The devices for which the thermal task has a temperature driver.  Devices
+// that have temperature sensors but are PMBus devices are assumed to be
+// monitored elsewhere (namely, by the power task); any other device with
+// temperature sensors must have its driver here.
+//
+const THERMAL_DRIVERS: &[&str] =
+    &["sbtsi", "spd5118", "tmp117", "tmp451", "tse2004av"];
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum Disposition {
     /// controller is an initiator
@@ -175,6 +212,9 @@ pub enum Disposition {
     /// devices are used, with some used as sensors
     Sensors,
 
+    /// devices are used, with temperature sensors used for thermal control
+    Thermal,
+
     /// devices are used, but only as validation
     Validation,
 }
@@ -204,6 +244,33 @@ impl std::fmt::Display for Sensor {
     }
 }
 
+///
+/// Returns each sensor of `devices` -- as its kind, its device, and its index
+/// among the device's sensors of that kind -- in order of sensor ID.  Sensor
+/// IDs are allocated by device, and by kind within each device.
+///
+fn sensors(devices: &[I2cDevice]) -> Vec<(Sensor, &I2cDevice, usize)> {
+    let mut sensors = vec![];
+
+    for d in devices {
+        if let Some(s) = &d.sensors {
+            for (kind, count) in [
+                (Sensor::Temperature, s.temperature),
+                (Sensor::Power, s.power),
+                (Sensor::Current, s.current),
+                (Sensor::Voltage, s.voltage),
+                (Sensor::Speed, s.speed),
+            ] {
+                for i in 0..count {
+                    sensors.push((kind, d, i));
+                }
+            }
+        }
+    }
+
+    sensors
+}
+
 struct ConfigGenerator {
     /// output that we're building
     output: String,
@@ -971,6 +1038,71 @@ impl ConfigGenerator {
         Ok(())
     }
 
+    fn emit_thresholds(
+        &mut self,
+        thresholds: &[(usize, I2cThresholds)],
+    ) -> Result<()> {
+        let mut values = vec![];
+        let mut hysteresis = vec![];
+
+        for (id, t) in thresholds {
+            for (threshold, value) in [
+                ("LowerCritical", t.lower_critical),
+                ("LowerNonCritical", t.lower_noncritical),
+                ("UpperNonCritical", t.noncritical),
+                ("UpperCritical", t.critical),
+            ] {
+                if let Some(value) = value {
+                    values.push((id, threshold, value));
+                }
+            }
+
+            if let Some(h) = t.hysteresis {
+                hysteresis.push((id, h));
+            }
+        }
+
+        write!(
+            &mut self.output,
+            r##"
+        #[allow(dead_code)]
+        pub const SENSOR_THRESHOLDS: [(SensorId, Threshold, f32); {}] = ["##,
+            values.len()
+        )?;
+
+        for (id, threshold, value) in &values {
+            write!(
+                &mut self.output,
+                r##"
+            (SensorId({}), Threshold::{}, {:?}),"##,
+                id, threshold, value
+            )?;
+        }
+
+        write!(
+            &mut self.output,
+            r##"
+        ];
+
+        #[allow(dead_code)]
+        pub const SENSOR_HYSTERESIS: [(SensorId, f32); {}] = ["##,
+            hysteresis.len()
+        )?;
+
+        for (id, value) in &hysteresis {
+            write!(
+                &mut self.output,
+                r##"
+            (SensorId({}), {:?}),"##,
+                id, value
+            )?;
+        }
+
+        writeln!(&mut self.output, "\n        ];")?;
+
+        Ok(())
+    }
+
     pub fn generate_sensors(&mut self) -> Result<()> {
         let mut bydevice = MultiMap::new();
         let mut byname = MultiMap::new();
@@ -978,12 +1110,9 @@ impl ConfigGenerator {
         let mut bybusname = MultiMap::new();
         let mut bykind = MultiMap::new();
 
-        let mut sensors = vec![];
-
-        let mut add_sensor = |kind, d: &I2cDevice, idx: usize| {
-            let id = sensors.len();
-            sensors.push(kind);
+        let mut thresholds = vec![];
 
+        let mut add_sensor = |id: usize, kind, d: &I2cDevice, idx: usize| {
             let name: Option<String> = if let Some(pmbus) = &d.pmbus {
                 if let Some(rails) = &pmbus.rails {
                     if idx < rails.len() {
@@ -1017,26 +1146,15 @@ impl ConfigGenerator {
             bykind.insert(kind, id);
         };
 
-        for d in &self.devices {
-            if let Some(s) = &d.sensors {
-                for i in 0..s.temperature {
-                    add_sensor(Sensor::Temperature, &d, i);
-                }
-
-                for i in 0..s.power {
-                    add_sensor(Sensor::Power, &d, i);
-                }
-
-                for i in 0..s.current {
-                    add_sensor(Sensor::Current, &d, i);
-                }
+        let all = sensors(&self.devices);
+        let nsensors = all.len();
 
-                for i in 0..s.voltage {
-                    add_sensor(Sensor::Voltage, &d, i);
-                }
+        for (id, (kind, d, i)) in all.into_iter().enumerate() {
+            add_sensor(id, kind, d, i);
 
-                for i in 0..s.speed {
-                    add_sensor(Sensor::Speed, &d, i);
+            if kind == Sensor::Temperature {
+                if let Some(t) = &d.sensors.as_ref().unwrap().thresholds {
+                    thresholds.push((id, t.clone()));
                 }
             }
         }
@@ -1045,14 +1163,16 @@ impl ConfigGenerator {
             &mut self.output,
             r##"
     pub mod sensors {{
-        use task_sensor_api::SensorId;
+        use task_sensor_api::{{SensorId, Threshold}};
 
         #[allow(dead_code)]
         pub const NUM_SENSORS: usize = {};
 "##,
-            sensors.len()
+            nsensors
         )?;
 
+        self.emit_thresholds(&thresholds)?;
+
         for ((device, kind), ids) in bydevice.iter_all() {
             self.emit_sensor(device, &format!("{}", kind), ids)?;
         }
@@ -1081,6 +1201,191 @@ impl ConfigGenerator {
         Ok(())
     }
 
+    pub fn generate_thermal(&mut self) -> Result<()> {
+        let mut entries = vec![];
+        let mut fans: Vec<(&I2cDevice, Vec<usize>)> = vec![];
+
+        for d in &self.devices {
+            let s = match &d.sensors {
+                Some(s) => s,
+                None => continue,
+            };
+
+            if s.zone.is_some() && (s.temperature == 0 || d.pmbus.is_some()) {
+                panic!(
+                    "device {} at address {:#x} has a thermal zone, \
+                    but no thermally controlled sensors",
+                    d.device, d.address
+                );
+            }
+        }
+
+        for (id, (kind, d, _)) in sensors(&self.devices).into_iter().enumerate()
+        {
+            match kind {
+                Sensor::Temperature if d.pmbus.is_none() => {
+                    if !THERMAL_DRIVERS.contains(&d.device.as_str()) {
+                        panic!(
+                            "device {} at address {:#x} has temperature \
+                            sensors, but no thermal driver",
+                            d.device, d.address
+                        );
+                    }
+
+                    let s = d.sensors.as_ref().unwrap();
+
+                    if s.temperature != 1 {
+                        panic!(
+                            "device {} at address {:#x} has {} temperature \
+                            sensors; expected exactly one",
+                            d.device, d.address, s.temperature
+                        );
+                    }
+
+                    entries.push((d, id));
+                }
+
+                //
+                // Fan controllers' speed sensors are in order of fan, so
+                // the fans of each controller are those of its sensors.
+                //
+                Sensor::Speed if d.device == "max31790" => {
+                    match fans.last_mut() {
+                        Some((last, ids)) if std::ptr::eq(*last, d) => {
+                            ids.push(id)
+                        }
+                        _ => fans.push((d, vec![id])),
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        write!(
+            &mut self.output,
+            r##"
+    pub mod thermal {{
+        use drv_i2c_api::{{I2cDevice, Controller, PortIndex}};
+        use task_sensor_api::SensorId;
+        use userlib::TaskId;
+
+        #[allow(dead_code)]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub enum Driver {{
+"##
+        )?;
+
+        for driver in THERMAL_DRIVERS {
+            writeln!(
+                &mut self.output,
+                "            {},",
+                driver.to_case(Case::UpperCamel)
+            )?;
+        }
+
+        write!(
+            &mut self.output,
+            r##"        }}
+
+        #[allow(dead_code)]
+        pub struct TemperatureSensor {{
+            pub driver: Driver,
+            pub device: I2cDevice,
+            pub id: SensorId,
+            pub critical: Option<f32>,
+        }}
+
+        #[allow(dead_code)]
+        pub const NUM_TEMPERATURE_SENSORS: usize = {};
+
+        #[allow(dead_code)]
+        pub fn temperature_sensors(
+            task: TaskId
+        ) -> [TemperatureSensor; NUM_TEMPERATURE_SENSORS] {{
+            ["##,
+            entries.len()
+        )?;
+
+        for (d, id) in &entries {
+            let s = d.sensors.as_ref().unwrap();
+            let critical = s.thresholds.as_ref().and_then(|t| t.critical);
+            let device = self.generate_device(d, 20);
+
+            write!(
+                &mut self.output,
+                r##"
+                TemperatureSensor {{
+                    driver: Driver::{driver},
+                    device: {device},
+                    id: SensorId({id}),
+                    critical: {critical},
+                }},"##,
+                driver = d.device.to_case(Case::UpperCamel),
+                device = device,
+                id = id,
+                critical = match critical {
+                    Some(c) => format!("Some({:?})", c),
+                    None => "None".to_string(),
+                },
+            )?;
+        }
+
+        write!(
+            &mut self.output,
+            r##"
+            ]
+        }}
+
+        /// A MAX31790 fan controller, and the speed sensors of its fans.
+        #[allow(dead_code)]
+        pub struct FanController {{
+            pub device: I2cDevice,
+            pub speed_sensors: &'static [SensorId],
+        }}
+
+        #[allow(dead_code)]
+        pub const NUM_FAN_CONTROLLERS: usize = {};
+
+        #[allow(dead_code)]
+        pub fn fan_controllers(
+            task: TaskId
+        ) -> [FanController; NUM_FAN_CONTROLLERS] {{
+            ["##,
+            fans.len()
+        )?;
+
+        for (d, ids) in &fans {
+            let device = self.generate_device(d, 20);
+            let ids = ids
+                .iter()
+                .map(|id| format!("SensorId({})", id))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            write!(
+                &mut self.output,
+                r##"
+                FanController {{
+                    device: {device},
+                    speed_sensors: &[{ids}],
+                }},"##,
+                device = device,
+                ids = ids,
+            )?;
+        }
+
+        writeln!(
+            &mut self.output,
+            r##"
+            ]
+        }}
+    }}"##
+        )?;
+
+        Ok(())
+    }
+
     pub fn generate_ports(&mut self) -> Result<()> {
         writeln!(
             &mut self.output,
@@ -1154,6 +1459,13 @@ pub fn codegen(disposition: Disposition) -> Result<()> {
             g.generate_sensors()?;
         }
 
+        Disposition::Thermal => {
+            g.generate_devices()?;
+            g.generate_pmbus()?;
+            g.generate_sensors()?;
+            g.generate_thermal()?;
+        }
+
         Disposition::Validation => {
             g.generate_validation()?;
         }
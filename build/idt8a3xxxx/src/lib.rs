@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Build-time support for loading the configuration of a Renesas 8A3XXXX
+//! clock generator.
+//!
+//! The configuration is taken from the Aardvark XML output of Renesas
+//! configuration software:  a sequence of `i2c_write` elements, each of
+//! which contains (in its text) the bytes to be written.  These writes are
+//! encoded into a compact payload, each write being its length in bytes
+//! followed by the bytes themselves.  The payload is written to
+//! `$OUT_DIR/idt8a3xxxx_payload.bin`, from which it can be included and
+//! iterated over at run time.
+
+use anyhow::{bail, Context, Result};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+///
+/// Reads the Aardvark XML file at `path` (relative to the crate being
+/// built), and writes the payload derived from it to the output directory.
+///
+pub fn codegen(path: &str) -> Result<()> {
+    let path = Path::new(path);
+    println!("cargo:rerun-if-changed={}", path.display());
+
+    let input = fs::read_to_string(path)
+        .with_context(|| format!("failed to read {}", path.display()))?;
+
+    let payload = payload(&input)
+        .with_context(|| format!("failed to parse {}", path.display()))?;
+
+    let out = PathBuf::from(env::var("OUT_DIR")?);
+    fs::write(out.join("idt8a3xxxx_payload.bin"), payload)?;
+
+    Ok(())
+}
+
+///
+/// Returns the value of attribute `name` in the tag `tag`, if any.
+///
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let pattern = format!(" {}=\"", name);
+    let start = tag.find(&pattern)? + pattern.len();
+    let len = tag[start..].find('"')?;
+
+    Some(&tag[start..start + len])
+}
+
+fn parse_number(val: &str) -> Result<u32> {
+    let parsed = match val.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => val.parse::<u32>(),
+    };
+
+    parsed.with_context(|| format!("invalid number \"{}\"", val))
+}
+
+///
+/// Derives a payload from Aardvark XML.  Elements that merely configure the
+/// Aardvark adapter are ignored; any element that we can't faithfully
+/// reproduce (e.g., a read or a sleep) is an error.
+///
+pub fn payload(input: &str) -> Result<Vec<u8>> {
+    const IGNORED: &[&str] =
+        &["?xml", "!--", "aardvark", "/aardvark", "configure", "i2c_bitrate"];
+
+    let mut payload = vec![];
+    let mut address = None;
+    let mut rest = input;
+
+    while let Some(start) = rest.find('<') {
+        let end = match rest[start..].find('>') {
+            Some(end) => start + end,
+            None => bail!("unterminated element"),
+        };
+
+        let tag = &rest[start + 1..end];
+        rest = &rest[end + 1..];
+
+        let name = tag
+            .split_whitespace()
+            .next()
+            .unwrap_or("")
+            .trim_end_matches('/');
+
+        if name.starts_with("!--") || IGNORED.contains(&name) {
+            continue;
+        }
+
+        if name != "i2c_write" {
+            bail!("unsupported element <{}>", name);
+        }
+
+        match (attribute(tag, "addr"), address) {
+            (None, _) => bail!("i2c_write is missing an address"),
+            (Some(addr), None) => address = Some(addr),
+            (Some(addr), Some(prev)) if addr != prev => {
+                bail!("i2c_write addresses {} and {} differ", prev, addr);
+            }
+            _ => {}
+        }
+
+        let radix = match attribute(tag, "radix") {
+            Some(radix) => parse_number(radix)?,
+            None => 16,
+        };
+
+        let close = match rest.find("</i2c_write>") {
+            Some(close) => close,
+            None => bail!("unterminated i2c_write"),
+        };
+
+        let bytes = rest[..close]
+            .split_whitespace()
+            .map(|b| {
+                u8::from_str_radix(b.trim_start_matches("0x"), radix)
+                    .with_context(|| format!("invalid byte \"{}\"", b))
+            })
+            .collect::<Result<Vec<u8>>>()?;
+
+        rest = &rest[close + "</i2c_write>".len()..];
+
+        if let Some(count) = attribute(tag, "count") {
+            if parse_number(count)? as usize != bytes.len() {
+                bail!("i2c_write count {} mismatches {:x?}", count, bytes);
+            }
+        }
+
+        if bytes.is_empty() || bytes.len() > u8::MAX as usize {
+            bail!("i2c_write of {} bytes is not supported", bytes.len());
+        }
+
+        payload.push(bytes.len() as u8);
+        payload.extend_from_slice(&bytes);
+    }
+
+    if payload.is_empty() {
+        bail!("no i2c_write elements found");
+    }
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes() {
+        let input = r##"<?xml version="1.0" encoding="UTF-8"?>
+<aardvark>
+    <configure i2c="1" spi="1" gpio="0" tpower="1" pullups="0" />
+    <i2c_bitrate khz="400" />
+    <i2c_write addr="0x58" count="5" radix="16">FC 00 C0 10 20</i2c_write>
+    <!-- a comment -->
+    <i2c_write addr="0x58" count="2" radix="16">6D 00</i2c_write>
+</aardvark>
+"##;
+
+        assert_eq!(
+            payload(input).unwrap(),
+            [5, 0xfc, 0x00, 0xc0, 0x10, 0x20, 2, 0x6d, 0x00]
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        let bad = [
+            r##"<i2c_write addr="0x58" count="3">FC 00</i2c_write>"##,
+            r##"<i2c_write addr="0x58">FC</i2c_write>
+                <i2c_write addr="0x5a">FC</i2c_write>"##,
+            r##"<i2c_write addr="0x58">FC</i2c_write><sleep ms="10" />"##,
+            r##"<aardvark></aardvark>"##,
+        ];
+
+        for input in bad {
+            assert!(payload(input).is_err(), "{}", input);
+        }
+    }
+}
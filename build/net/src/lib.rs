@@ -23,18 +23,77 @@ pub struct NetConfig {
     /// during the `net` build, so it must be present iff the `vlan` feature
     /// is turned on.
     pub vlan: Option<VLanConfig>,
+
+    /// IPv4 configuration, or None if we are IPv6 only.  (We always have a
+    /// link-local IPv6 address.)
+    pub ipv4: Option<Ipv4Config>,
+
+    /// Number of entries in the neighbor cache (ARP/NDP) of each interface
+    #[serde(default = "default_neighbors")]
+    pub neighbors: usize,
+
+    /// Number of distinct multicast groups that sockets can be members of at
+    /// once, including those joined at runtime
+    #[serde(default = "default_multicast_groups")]
+    pub multicast_groups: usize,
+
+    /// Packet capture configuration, which is only used (and then optional)
+    /// if the `pcap` feature is turned on.
+    pub pcap: Option<PcapConfig>,
+}
+
+fn default_neighbors() -> usize {
+    4
+}
+
+fn default_multicast_groups() -> usize {
+    4
 }
 
 /// TODO: this type really wants to be an enum, but the toml crate's enum
-/// handling is really, really fragile, and currently it would be an enum with a
-/// single variant anyway.
+/// handling is really, really fragile.
 #[derive(Deserialize)]
 pub struct SocketConfig {
+    /// Either "udp" or "tcp"
     pub kind: String,
     pub owner: TaskNote,
     pub port: u16,
     pub tx: BufSize,
     pub rx: BufSize,
+    /// Multicast groups that a UDP socket joins at startup
+    #[serde(default)]
+    pub multicast: Vec<std::net::IpAddr>,
+    /// Authenticated encryption for a UDP socket, which is only supported
+    /// with the `secure` feature
+    pub secure: Option<SecureConfig>,
+    /// Limit on the rate at which a UDP socket receives packets
+    pub rate: Option<RateLimit>,
+    /// Which packet a UDP socket drops when its receive queue is full:
+    /// either "newest" (the default) or "oldest"
+    pub drop: Option<String>,
+    /// Shared region (in the `net` task's and the owner's `uses`) in which a
+    /// UDP socket's received payloads are kept, so that its owner can read
+    /// them in place with `peek_packet` and `release_packet`
+    pub loan: Option<String>,
+}
+
+impl SocketConfig {
+    pub fn is_tcp(&self) -> bool {
+        self.kind == "tcp"
+    }
+
+    pub fn drops_oldest(&self) -> bool {
+        self.drop.as_deref() == Some("oldest")
+    }
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct RateLimit {
+    /// Packets per second
+    pub packets: u32,
+    /// Number of packets that can arrive at once after an idle period;
+    /// defaults to `packets`
+    pub burst: Option<u32>,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize)]
@@ -45,8 +104,76 @@ pub struct VLanConfig {
     pub count: usize,
 }
 
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct Ipv4Config {
+    /// Static address; mutually exclusive with `dhcp`
+    pub address: Option<std::net::Ipv4Addr>,
+    /// Prefix length of the static address's subnet
+    #[serde(default = "default_ipv4_prefix")]
+    pub prefix: u8,
+    /// Default gateway, if any, for a static address
+    pub gateway: Option<std::net::Ipv4Addr>,
+    /// Use DHCP to configure the address and gateway
+    #[serde(default)]
+    pub dhcp: bool,
+}
+
+fn default_ipv4_prefix() -> u8 {
+    24
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SecureConfig {
+    /// Name of the pre-shared key, which is provisioned per device in the
+    /// vital product data of the board's FRU ID EEPROM (as a `PSK0` record)
+    pub key: String,
+    /// Number of peers that can have sessions with the socket at once
+    #[serde(default = "default_secure_sessions")]
+    pub sessions: usize,
+}
+
+fn default_secure_sessions() -> usize {
+    1
+}
+
+#[derive(Copy, Clone, Debug, Deserialize)]
+pub struct PcapConfig {
+    /// Number of frames that the capture ring holds
+    #[serde(default = "default_pcap_frames")]
+    pub frames: usize,
+    /// Number of bytes of each frame that are captured
+    #[serde(default = "default_pcap_snaplen")]
+    pub snaplen: usize,
+    /// Only capture frames on this VLAN
+    pub vid: Option<u16>,
+    /// Only capture UDP and TCP frames to or from this port
+    pub port: Option<u16>,
+}
+
+impl Default for PcapConfig {
+    fn default() -> Self {
+        Self {
+            frames: default_pcap_frames(),
+            snaplen: default_pcap_snaplen(),
+            vid: None,
+            port: None,
+        }
+    }
+}
+
+fn default_pcap_frames() -> usize {
+    16
+}
+
+fn default_pcap_snaplen() -> usize {
+    128
+}
+
 #[derive(Deserialize)]
 pub struct BufSize {
+    /// Number of packets that can be buffered; TCP sockets are streams, and
+    /// ignore this.
+    #[serde(default)]
     pub packets: usize,
     pub bytes: usize,
 }
@@ -70,6 +197,203 @@ pub fn load_net_config() -> Result<NetConfig, Box<dyn std::error::Error>> {
         _ => (),
     }
 
+    if let Some(pcap) = &cfg.pcap {
+        if !cfg!(feature = "pcap") {
+            return Err(
+                "pcap is configured, but the pcap feature is off".into()
+            );
+        }
+        if pcap.frames == 0 {
+            return Err("pcap must capture at least one frame".into());
+        }
+        // We need the Ethernet header to be able to insert a VLAN tag.
+        if !(14..=1518).contains(&pcap.snaplen) {
+            return Err(format!("invalid pcap snaplen {}", pcap.snaplen).into());
+        }
+        if let Some(vid) = pcap.vid {
+            let in_range = cfg.vlan.map_or(false, |v| {
+                (v.start..v.start + v.count).contains(&(vid as usize))
+            });
+            if !in_range {
+                return Err(format!("pcap VID {} isn't a VLAN", vid).into());
+            }
+        }
+    }
+
+    if let Some(ipv4) = &cfg.ipv4 {
+        if cfg.vlan.is_some() {
+            return Err("IPv4 is not supported with VLANs".into());
+        }
+
+        match (ipv4.address.is_some(), ipv4.dhcp) {
+            (true, true) => {
+                return Err("IPv4 can't have both an address and DHCP".into());
+            }
+            (false, false) => {
+                return Err("IPv4 needs either an address or DHCP".into());
+            }
+            _ => (),
+        }
+
+        if ipv4.prefix > 32 {
+            return Err(format!("invalid IPv4 prefix {}", ipv4.prefix).into());
+        }
+    }
+
+    for (name, socket) in &cfg.sockets {
+        match socket.kind.as_str() {
+            "udp" => (),
+            "tcp" if cfg.vlan.is_some() => {
+                return Err(format!(
+                    "socket {}: TCP sockets are not supported with VLANs",
+                    name
+                )
+                .into());
+            }
+            "tcp" => (),
+            kind => {
+                return Err(format!(
+                    "socket {}: unsupported socket kind {:?}",
+                    name, kind
+                )
+                .into());
+            }
+        }
+
+        if let Some(secure) = &socket.secure {
+            if !cfg!(feature = "secure") {
+                return Err(format!(
+                    "socket {}: secure, but the secure feature is off",
+                    name
+                )
+                .into());
+            }
+            if socket.is_tcp() || cfg.vlan.is_some() {
+                return Err(format!(
+                    "socket {}: secure sockets must be UDP, without VLANs",
+                    name
+                )
+                .into());
+            }
+            if secure.sessions == 0 {
+                return Err(format!(
+                    "socket {}: secure sockets need at least one session",
+                    name
+                )
+                .into());
+            }
+            if secure.key.is_empty() || !secure.key.is_ascii() {
+                return Err(format!(
+                    "socket {}: key names must be non-empty ASCII",
+                    name
+                )
+                .into());
+            }
+        }
+
+        if socket.is_tcp() && (socket.rate.is_some() || socket.drop.is_some()) {
+            return Err(format!(
+                "socket {}: rate and drop only apply to UDP sockets",
+                name
+            )
+            .into());
+        }
+        if let Some(rate) = socket.rate {
+            if rate.packets == 0 || rate.burst == Some(0) {
+                return Err(format!(
+                    "socket {}: rate must allow at least one packet",
+                    name
+                )
+                .into());
+            }
+        }
+        match socket.drop.as_deref() {
+            None | Some("newest") | Some("oldest") => (),
+            Some(_) => {
+                return Err(format!(
+                    "socket {}: drop must be \"newest\" or \"oldest\"",
+                    name
+                )
+                .into());
+            }
+        }
+
+        if let Some(loan) = &socket.loan {
+            if socket.is_tcp() || cfg.vlan.is_some() {
+                return Err(format!(
+                    "socket {}: loans are only supported for UDP sockets, \
+                     without VLANs",
+                    name
+                )
+                .into());
+            }
+            // A secure socket's queued payloads are still sealed (they're
+            // opened into the `net` task's own memory), and dropping the
+            // oldest packet could pull a loaned packet out from under its
+            // owner.
+            if socket.secure.is_some() || socket.drops_oldest() {
+                return Err(format!(
+                    "socket {}: loaned sockets can't be secure or drop the \
+                     oldest packet",
+                    name
+                )
+                .into());
+            }
+            if loan.is_empty() {
+                return Err(
+                    format!("socket {}: loan names a region", name).into()
+                );
+            }
+        }
+
+        for group in &socket.multicast {
+            if !group.is_multicast() {
+                return Err(format!(
+                    "socket {}: {} is not a multicast group",
+                    name, group
+                )
+                .into());
+            }
+            if socket.is_tcp() {
+                return Err(format!(
+                    "socket {}: TCP sockets can't join multicast groups",
+                    name
+                )
+                .into());
+            }
+            if group.is_ipv4() && cfg.ipv4.is_none() {
+                return Err(format!(
+                    "socket {}: can't join IPv4 group {} without IPv4",
+                    name, group
+                )
+                .into());
+            }
+        }
+    }
+
+    // Multicast group membership is tracked as a bitmask of sockets.
+    if cfg.sockets.len() > 32 {
+        return Err("at most 32 sockets are supported".into());
+    }
+
+    if cfg.neighbors == 0 {
+        return Err("the neighbor cache must have at least one entry".into());
+    }
+
+    let groups = cfg
+        .sockets
+        .values()
+        .flat_map(|s| s.multicast.iter())
+        .collect::<std::collections::BTreeSet<_>>();
+    if groups.len() > cfg.multicast_groups {
+        return Err(format!(
+            "{} multicast groups are configured, but multicast_groups is {}",
+            groups.len(),
+            cfg.multicast_groups
+        )
+        .into());
+    }
+
     Ok(cfg)
 }
 
@@ -92,6 +416,183 @@ pub const VLAN_COUNT: usize = {};
     )
 }
 
+/// Generates the IPv4 configuration, which only the non-VLAN server uses (as
+/// IPv4 isn't supported with VLANs); nothing is generated for VLAN builds.
+pub fn generate_ipv4_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    if config.vlan.is_some() {
+        return Ok(());
+    }
+
+    let fmt = |a: Option<std::net::Ipv4Addr>| match a {
+        Some(a) => format!("Some({:?})", a.octets()),
+        None => "None".to_string(),
+    };
+
+    let ipv4 = config.ipv4;
+
+    writeln!(
+        out,
+        "
+pub(crate) const IPV4_ENABLED: bool = {};
+pub(crate) const IPV4_DHCP: bool = {};
+pub(crate) const IPV4_ADDRESS: Option<[u8; 4]> = {};
+pub(crate) const IPV4_PREFIX: u8 = {};
+pub(crate) const IPV4_GATEWAY: Option<[u8; 4]> = {};
+",
+        ipv4.is_some(),
+        ipv4.map(|c| c.dhcp).unwrap_or(false),
+        fmt(ipv4.and_then(|c| c.address)),
+        ipv4.map(|c| c.prefix).unwrap_or(0),
+        fmt(ipv4.and_then(|c| c.gateway)),
+    )
+}
+
+pub fn generate_neighbor_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    writeln!(
+        out,
+        "
+pub(crate) const NEIGHBORS: usize = {};
+",
+        config.neighbors
+    )
+}
+
+/// Generates the size of the multicast group table, and a table of the
+/// `(socket index, group)` pairs that are joined at startup.
+pub fn generate_multicast_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    let groups = config
+        .sockets
+        .values()
+        .enumerate()
+        .flat_map(|(i, s)| s.multicast.iter().map(move |g| (i, g)))
+        .collect::<Vec<_>>();
+
+    writeln!(
+        out,
+        "
+pub(crate) const MULTICAST_GROUPS: usize = {};
+pub(crate) const SOCKET_MULTICAST: [(usize, task_net_api::Address); {}] = [",
+        config.multicast_groups,
+        groups.len(),
+    )?;
+    for (i, group) in groups {
+        let addr = match group {
+            std::net::IpAddr::V4(a) => format!(
+                "task_net_api::Address::Ipv4(task_net_api::Ipv4Address({:?}))",
+                a.octets()
+            ),
+            std::net::IpAddr::V6(a) => format!(
+                "task_net_api::Address::Ipv6(task_net_api::Ipv6Address({:?}))",
+                a.octets()
+            ),
+        };
+        writeln!(out, "    ({}, {}),", i, addr)?;
+    }
+    writeln!(out, "];")
+}
+
+/// Generates the rate limit (packets per second and burst) and drop policy of
+/// each socket.
+pub fn generate_policy_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    let n = config.sockets.len();
+    writeln!(
+        out,
+        "pub(crate) const SOCKET_RATE_LIMITS: [Option<(u32, u32)>; {}] = [",
+        n
+    )?;
+    for socket in config.sockets.values() {
+        match socket.rate {
+            Some(rate) => writeln!(
+                out,
+                "    Some(({}, {})),",
+                rate.packets,
+                rate.burst.unwrap_or(rate.packets)
+            )?,
+            None => writeln!(out, "    None,")?,
+        }
+    }
+    writeln!(out, "];")?;
+
+    let oldest = config
+        .sockets
+        .values()
+        .map(SocketConfig::drops_oldest)
+        .collect::<Vec<_>>();
+    writeln!(
+        out,
+        "pub(crate) const SOCKET_DROP_OLDEST: [bool; {}] = {:?};",
+        n, oldest
+    )?;
+    writeln!(
+        out,
+        "pub(crate) const ANY_DROP_OLDEST: bool = {};",
+        oldest.contains(&true)
+    )
+}
+
+pub fn generate_pcap_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    let pcap = config.pcap.unwrap_or_default();
+    writeln!(
+        out,
+        "
+pub(crate) const PCAP_FRAMES: usize = {};
+pub(crate) const PCAP_SNAPLEN: usize = {};
+pub(crate) const PCAP_VID: Option<u16> = {:?};
+pub(crate) const PCAP_PORT: Option<u16> = {:?};
+",
+        pcap.frames, pcap.snaplen, pcap.vid, pcap.port,
+    )
+}
+
+/// Generates the name of the pre-shared key and number of sessions of each
+/// socket (`None` for plaintext sockets), along with the most sessions that
+/// any socket has.
+pub fn generate_secure_consts(
+    config: &NetConfig,
+    mut out: impl std::io::Write,
+) -> Result<(), std::io::Error> {
+    let sessions = config
+        .sockets
+        .values()
+        .filter_map(|s| s.secure.as_ref().map(|c| c.sessions))
+        .max()
+        .unwrap_or(0);
+    writeln!(
+        out,
+        "
+pub(crate) const SECURE_SESSIONS: usize = {};
+pub(crate) const SOCKET_SECURITY: [Option<(&str, usize)>; {}] = [",
+        sessions,
+        config.sockets.len(),
+    )?;
+    for socket in config.sockets.values() {
+        match &socket.secure {
+            Some(secure) => writeln!(
+                out,
+                "    Some(({:?}, {})),",
+                secure.key, secure.sessions
+            )?,
+            None => writeln!(out, "    None,")?,
+        }
+    }
+    writeln!(out, "];")
+}
+
 pub fn generate_socket_enum(
     config: &NetConfig,
     mut out: impl std::io::Write,
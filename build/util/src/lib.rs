@@ -4,6 +4,8 @@
 
 use anyhow::{anyhow, Result};
 use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::env;
 
 /// Exposes the CPU's M-profile architecture version. This isn't available in
@@ -60,6 +62,18 @@ pub fn task_config<T: DeserializeOwned>() -> Result<T> {
     toml_from_env("HUBRIS_TASK_CONFIG", section.as_deref())
 }
 
+/// A peripheral (or other region of memory) that a task uses.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Peripheral {
+    pub address: u32,
+    pub size: u32,
+}
+
+/// Pulls the peripherals that the task uses, by name.
+pub fn task_peripherals() -> Result<BTreeMap<String, Peripheral>> {
+    toml_from_env("HUBRIS_TASK_PERIPHERALS", None)
+}
+
 /// Parse the contents of an environment variable as toml. `section_name_pattern` is a string
 /// indicating what section of original toml file the variable should have come from to improve error reporting. `{task}` in the pattern is replaced with HUBRIS_TASK_NAME.
 fn toml_from_env<T: DeserializeOwned>(
@@ -8,7 +8,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::{bail, Result};
 use indexmap::IndexMap;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// A `RawConfig` represents an `app.toml` file that has been deserialized,
 /// but may not be ready for use.  In particular, we use the `chip` field
@@ -33,6 +33,8 @@ struct RawConfig {
     peripherals: IndexMap<String, Peripheral>,
     #[serde(default)]
     extratext: IndexMap<String, Peripheral>,
+    #[serde(default)]
+    shared_regions: IndexMap<String, Peripheral>,
     supervisor: Option<Supervisor>,
     #[serde(default)]
     config: Option<ordered_toml::Value>,
@@ -53,6 +55,7 @@ pub struct Config {
     pub tasks: IndexMap<String, Task>,
     pub peripherals: IndexMap<String, Peripheral>,
     pub extratext: IndexMap<String, Peripheral>,
+    pub shared_regions: IndexMap<String, Peripheral>,
     pub supervisor: Option<Supervisor>,
     pub config: Option<ordered_toml::Value>,
     pub buildhash: u64,
@@ -98,6 +101,7 @@ impl Config {
             tasks: toml.tasks,
             peripherals,
             extratext: toml.extratext,
+            shared_regions: toml.shared_regions,
             supervisor: toml.supervisor,
             config: toml.config,
             buildhash,
@@ -273,6 +277,25 @@ impl Config {
         out.env
             .insert("HUBRIS_TASK_NAME".to_string(), task_name.to_string());
 
+        // Expose the peripherals (and other regions) that the task uses, so
+        // that its build.rs can find their addresses without duplicating the
+        // chip's description of them.
+        let peripherals = task_toml
+            .uses
+            .iter()
+            .filter_map(|name| {
+                self.peripherals
+                    .get(name)
+                    .or_else(|| self.extratext.get(name))
+                    .or_else(|| self.shared_regions.get(name))
+                    .map(|p| (name, p))
+            })
+            .collect::<BTreeMap<_, _>>();
+        out.env.insert(
+            "HUBRIS_TASK_PERIPHERALS".to_string(),
+            toml::to_string(&peripherals).unwrap(),
+        );
+
         Ok(out)
     }
 }
@@ -357,7 +380,7 @@ pub struct Task {
     pub config: Option<ordered_toml::Value>,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub struct Peripheral {
     pub address: u32,
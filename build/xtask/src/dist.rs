@@ -375,6 +375,7 @@ Did you mean to run `cargo xtask dist`?"
         &toml.outputs,
         &entry_points,
         &toml.extratext,
+        &toml.shared_regions,
     )?;
     let kconfig = ron::ser::to_string(&kconfig)?;
 
@@ -1298,6 +1299,7 @@ fn make_descriptors(
     outputs: &IndexMap<String, Output>,
     entry_points: &HashMap<String, u32>,
     extra_text: &IndexMap<String, Peripheral>,
+    shared_regions: &IndexMap<String, Peripheral>,
 ) -> Result<KernelConfig> {
     // Generate the three record sections concurrently.
     let mut regions = vec![];
@@ -1378,6 +1380,41 @@ fn make_descriptors(
         });
     }
 
+    for (name, p) in shared_regions.iter() {
+        if power_of_two_required && !p.size.is_power_of_two() {
+            panic!("Memory region for shared region '{}' is required to be a power of two, but has size {}", name, p.size);
+        }
+
+        // Shared regions are carved out of memory that isn't otherwise
+        // allocated, so they mustn't overlap any of the outputs.
+        let end = p.address as u64 + p.size as u64;
+        for (output_name, out) in outputs {
+            let out_end = out.address as u64 + out.size as u64;
+            if (p.address as u64) < out_end && (out.address as u64) < end {
+                bail!(
+                    "shared region `{}` overlaps output `{}`",
+                    name,
+                    output_name
+                );
+            }
+        }
+
+        peripheral_index.insert(name, regions.len());
+
+        // Shared regions are normal memory, which the tasks that use them
+        // can both read and write (unlike peripherals, which are device
+        // memory, where unaligned accesses fault).
+        let attributes =
+            abi::RegionAttributes::READ | abi::RegionAttributes::WRITE;
+
+        regions.push(abi::RegionDesc {
+            base: p.address,
+            size: p.size,
+            attributes,
+            reserved_zero: 0,
+        });
+    }
+
     // The remaining regions are allocated to tasks on a first-come first-serve
     // basis.
     for (i, (name, task)) in tasks.iter().enumerate() {
@@ -0,0 +1,79 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Client API for the AT24CSW080 EEPROM server
+//!
+//! Besides reading and writing the EEPROM, this allows for access to the
+//! 32-byte security register (the first 16 bytes of which are a read-only
+//! serial number) and to the EEPROM's write protection.  Vital product data
+//! stored in the EEPROM can be parsed with the `vpd` crate.
+
+#![no_std]
+
+use derive_idol_err::IdolError;
+use userlib::*;
+use zerocopy::{AsBytes, FromBytes};
+
+/// Number of bytes stored in the EEPROM
+pub const EEPROM_SIZE: usize = 1024;
+
+/// Number of bytes in the security register
+pub const SECURITY_REGISTER_SIZE: usize = 32;
+
+/// Offset of the user-programmable bytes in the security register
+pub const SECURITY_REGISTER_USER: usize = 16;
+
+/// The `EepromError` is a simple `enum` that copies the more detailed
+/// `drv_i2c_devices::at24csw080::Error` type, discarding extra data
+/// so this can be sent in Idol messages.
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, IdolError)]
+#[repr(u32)]
+pub enum EepromError {
+    I2cError = 1,
+    InvalidAddress,
+    InvalidEndAddress,
+    InvalidObjectSize,
+    MisalignedPage,
+    InvalidPageSize,
+    InvalidSecurityRegisterReadByte,
+    InvalidSecurityRegisterWriteByte,
+}
+
+/// A range of the EEPROM that can be write-protected.
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, AsBytes)]
+#[repr(u8)]
+pub enum WriteProtectBlock {
+    Upper256Bytes = 0,
+    Upper512Bytes = 1,
+    Upper768Bytes = 2,
+    AllMemory = 3,
+}
+
+///
+/// The state of the EEPROM's write protection, as returned by
+/// `Eeprom::write_protect`.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct WriteProtectStatus {
+    /// Nonzero if write protection is enabled
+    pub enabled: u8,
+    /// `WriteProtectBlock` that is protected, if enabled
+    pub block: u8,
+    /// Nonzero if write protection is permanently locked
+    pub locked: u8,
+    pub reserved: u8,
+}
+
+impl WriteProtectStatus {
+    pub fn block(&self) -> Option<WriteProtectBlock> {
+        if self.enabled != 0 {
+            WriteProtectBlock::from_u8(self.block)
+        } else {
+            None
+        }
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/client_stub.rs"));
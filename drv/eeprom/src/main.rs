@@ -4,67 +4,64 @@
 
 //! Driver task for the AT24CSW080 EEPROM
 //!
-//! This is an _extremely minimal_ driver, meant to be used from Humility
-//! for extremely basic bring-up testing.
-//!
-//! When using the EEPROM in production, it will be owned by a more
-//! featureful task which will use the I2C device directly from
-//! `i2c_config::devices::at24csw080_*`.
+//! This provides bulk reads and writes of the EEPROM (using page writes where
+//! possible), access to the security register, and control over the EEPROM's
+//! write protection.  Parsing the contents of the EEPROM (e.g. as vital
+//! product data) is left to clients; see the `vpd` crate.
 
 #![no_std]
 #![no_main]
 
-use derive_idol_err::IdolError;
-use drv_i2c_devices::at24csw080::*;
-use idol_runtime::RequestError;
+use drv_eeprom_api::{
+    EepromError, WriteProtectBlock, WriteProtectStatus, EEPROM_SIZE,
+    SECURITY_REGISTER_SIZE, SECURITY_REGISTER_USER,
+};
+use drv_i2c_devices::at24csw080::{self, At24csw080, Error};
+use idol_runtime::{ClientError, Leased, LenLimit, RequestError, R, W};
 use userlib::*;
 
 include!(concat!(env!("OUT_DIR"), "/i2c_config.rs"));
 task_slot!(I2C, i2c_driver);
 
-/// The `EepromError` is a simple `enum` that copies the more detailed
-/// `drv_i2c_devices::at24csw080::Error` type, discarding extra data
-/// so this can be sent in Idol messages.
-#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, IdolError)]
-#[repr(u32)]
-pub enum EepromError {
-    I2cError = 1,
-    InvalidAddress,
-    InvalidEndAddress,
-    InvalidObjectSize,
-    MisalignedPage,
-    InvalidPageSize,
-    InvalidSecurityRegisterReadByte,
-    InvalidSecurityRegisterWriteByte,
-}
+/// Size of the chunks in which we move data to and from leases; this is the
+/// EEPROM's page size, which keeps our stack usage small.
+const CHUNK_SIZE: usize = 16;
 
-impl From<Error> for EepromError {
-    fn from(err: Error) -> Self {
-        match err {
-            Error::I2cError(_) => Self::I2cError,
-            Error::InvalidAddress(_) => Self::InvalidAddress,
-            Error::InvalidEndAddress(_) => Self::InvalidEndAddress,
-            Error::InvalidObjectSize(_) => Self::InvalidObjectSize,
-            Error::MisalignedPage(_) => Self::MisalignedPage,
-            Error::InvalidPageSize(_) => Self::InvalidPageSize,
-            Error::InvalidSecurityRegisterReadByte(_) => {
-                Self::InvalidSecurityRegisterReadByte
-            }
-            Error::InvalidSecurityRegisterWriteByte(_) => {
-                Self::InvalidSecurityRegisterWriteByte
-            }
+fn eeprom_error(err: Error) -> EepromError {
+    match err {
+        Error::I2cError(_) => EepromError::I2cError,
+        Error::InvalidAddress(_) => EepromError::InvalidAddress,
+        Error::InvalidEndAddress(_) => EepromError::InvalidEndAddress,
+        Error::InvalidObjectSize(_) => EepromError::InvalidObjectSize,
+        Error::MisalignedPage(_) => EepromError::MisalignedPage,
+        Error::InvalidPageSize(_) => EepromError::InvalidPageSize,
+        Error::InvalidSecurityRegisterReadByte(_) => {
+            EepromError::InvalidSecurityRegisterReadByte
+        }
+        Error::InvalidSecurityRegisterWriteByte(_) => {
+            EepromError::InvalidSecurityRegisterWriteByte
         }
     }
 }
 
+///
+/// Checks that `len` bytes starting at `addr` are within the EEPROM, so that
+/// we can fail a request before any of it has been performed.
+///
+fn check_range(addr: u16, len: usize) -> Result<(), EepromError> {
+    if addr as usize >= EEPROM_SIZE {
+        Err(EepromError::InvalidAddress)
+    } else if addr as usize + len > EEPROM_SIZE {
+        Err(EepromError::InvalidEndAddress)
+    } else {
+        Ok(())
+    }
+}
+
 struct EepromServer {
     dev: At24csw080,
 }
 
-/// The simplest possible EEPROM implementation.
-///
-/// This is _very inefficient_; writing byte-by-byte incurs a 5ms pause after
-/// each byte, which could be reduced by writing 16-byte pages.
 impl idl::InOrderEepromImpl for EepromServer {
     fn read_byte(
         &mut self,
@@ -73,7 +70,7 @@ impl idl::InOrderEepromImpl for EepromServer {
     ) -> Result<u8, RequestError<EepromError>> {
         self.dev
             .read::<u8>(addr)
-            .map_err(|e| EepromError::from(e).into())
+            .map_err(|e| eeprom_error(e).into())
     }
 
     fn write_byte(
@@ -84,7 +81,172 @@ impl idl::InOrderEepromImpl for EepromServer {
     ) -> Result<(), RequestError<EepromError>> {
         self.dev
             .write_byte(addr, value)
-            .map_err(|e| EepromError::from(e).into())
+            .map_err(|e| eeprom_error(e).into())
+    }
+
+    fn read(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        addr: u16,
+        data: LenLimit<Leased<W, [u8]>, EEPROM_SIZE>,
+    ) -> Result<(), RequestError<EepromError>> {
+        check_range(addr, data.len())?;
+
+        let mut buf = [0u8; CHUNK_SIZE];
+        let mut offset = 0;
+
+        while offset < data.len() {
+            let n = usize::min(CHUNK_SIZE, data.len() - offset);
+
+            self.dev
+                .read_buffer(addr + offset as u16, &mut buf[..n])
+                .map_err(eeprom_error)?;
+
+            data.write_range(offset..offset + n, &buf[..n])
+                .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+            offset += n;
+        }
+
+        Ok(())
+    }
+
+    fn write(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        addr: u16,
+        data: LenLimit<Leased<R, [u8]>, EEPROM_SIZE>,
+    ) -> Result<(), RequestError<EepromError>> {
+        check_range(addr, data.len())?;
+
+        let mut buf = [0u8; CHUNK_SIZE];
+        let mut offset = 0;
+
+        while offset < data.len() {
+            //
+            // Break our chunks on page boundaries, so that everything past
+            // the first (partial) page is written with a page write.
+            //
+            let a = addr as usize + offset;
+            let n =
+                usize::min(CHUNK_SIZE - (a % CHUNK_SIZE), data.len() - offset);
+
+            data.read_range(offset..offset + n, &mut buf[..n])
+                .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+            self.dev
+                .write_buffer(a as u16, &buf[..n])
+                .map_err(eeprom_error)?;
+
+            offset += n;
+        }
+
+        Ok(())
+    }
+
+    fn read_security_register(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<[u8; SECURITY_REGISTER_SIZE], RequestError<EepromError>> {
+        let mut out = [0u8; SECURITY_REGISTER_SIZE];
+
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = self
+                .dev
+                .read_security_register_byte(i as u8)
+                .map_err(eeprom_error)?;
+        }
+
+        Ok(out)
+    }
+
+    fn write_security_register(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        offset: u8,
+        data: LenLimit<Leased<R, [u8]>, 16>,
+    ) -> Result<(), RequestError<EepromError>> {
+        let start = offset as usize;
+
+        if start < SECURITY_REGISTER_USER
+            || start + data.len() > SECURITY_REGISTER_SIZE
+        {
+            return Err(EepromError::InvalidSecurityRegisterWriteByte.into());
+        }
+
+        let mut buf = [0u8; CHUNK_SIZE];
+
+        data.read_range(0..data.len(), &mut buf[..data.len()])
+            .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+        for (i, &byte) in buf[..data.len()].iter().enumerate() {
+            self.dev
+                .write_security_register_byte((start + i) as u8, byte)
+                .map_err(eeprom_error)?;
+        }
+
+        Ok(())
+    }
+
+    fn is_security_register_locked(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<u8, RequestError<EepromError>> {
+        let locked = self
+            .dev
+            .is_security_register_locked()
+            .map_err(eeprom_error)?;
+
+        Ok(locked as u8)
+    }
+
+    fn write_protect(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<WriteProtectStatus, RequestError<EepromError>> {
+        let state =
+            self.dev.read_eeprom_write_protect().map_err(eeprom_error)?;
+
+        Ok(WriteProtectStatus {
+            enabled: state.block.is_some() as u8,
+            block: state.block.and_then(|b| b.to_u8()).unwrap_or(0),
+            locked: state.locked as u8,
+            reserved: 0,
+        })
+    }
+
+    fn enable_write_protect(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        block: WriteProtectBlock,
+    ) -> Result<(), RequestError<EepromError>> {
+        let block = match block {
+            WriteProtectBlock::Upper256Bytes => {
+                at24csw080::WriteProtectBlock::Upper256Bytes
+            }
+            WriteProtectBlock::Upper512Bytes => {
+                at24csw080::WriteProtectBlock::Upper512Bytes
+            }
+            WriteProtectBlock::Upper768Bytes => {
+                at24csw080::WriteProtectBlock::Upper768Bytes
+            }
+            WriteProtectBlock::AllMemory => {
+                at24csw080::WriteProtectBlock::AllMemory
+            }
+        };
+
+        self.dev
+            .enable_eeprom_write_protection(block)
+            .map_err(|e| eeprom_error(e).into())
+    }
+
+    fn disable_write_protect(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<(), RequestError<EepromError>> {
+        self.dev
+            .disable_eeprom_write_protection()
+            .map_err(|e| eeprom_error(e).into())
     }
 }
 
@@ -101,7 +263,7 @@ fn main() -> ! {
 }
 
 mod idl {
-    use super::EepromError;
+    use super::{EepromError, WriteProtectBlock, WriteProtectStatus};
 
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
@@ -9,7 +9,7 @@
 use derive_idol_err::IdolError;
 use drv_hash_api::SHA256_SZ;
 use userlib::*;
-use zerocopy::AsBytes;
+use zerocopy::{AsBytes, FromBytes};
 
 /// Errors that can be produced from the host flash server API.
 ///
@@ -25,6 +25,62 @@ pub enum HfError {
     HashNotConfigured = 6,
     NoDevSelect = 7,
     DevSelectFailed = 8,
+    /// The range is misaligned, or extends beyond the end of flash
+    BadRange = 9,
+    /// The range does not have the expected hash
+    VerifyFailed = 10,
+    /// The operation requires the SP to have access to flash
+    HostOwnsFlash = 11,
+    /// The slot doesn't contain an image
+    SlotEmpty = 12,
+    /// The range includes the active slot, which is write protected
+    SlotProtected = 13,
+    /// An erase is in progress; the flash can't be used until it's done
+    Busy = 14,
+}
+
+/// Size of a sector, the unit of `sector_erase` and `erase_range`
+pub const SECTOR_SIZE: u32 = 64 * 1024;
+
+/// Size of a page, the largest unit that can be programmed at once
+pub const PAGE_SIZE: u32 = 256;
+
+/// A long-running operation on a range of flash
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq)]
+pub enum HfBulkOp {
+    Erase = 1,
+    Program = 2,
+    Verify = 3,
+    Hash = 4,
+}
+
+///
+/// The progress of the current or most recent bulk operation, as returned by
+/// `HostFlash::progress`.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct HfProgress {
+    /// `HfBulkOp` being performed, or 0 if there has been none
+    pub op: u32,
+    /// address at which the operation started
+    pub address: u32,
+    /// number of bytes processed
+    pub done: u32,
+    /// total number of bytes to process
+    pub total: u32,
+    /// `HfError` that stopped an erase before it was done, or 0 if none
+    pub error: u32,
+}
+
+impl HfProgress {
+    pub fn op(&self) -> Option<HfBulkOp> {
+        HfBulkOp::from_u32(self.op)
+    }
+
+    pub fn error(&self) -> Option<HfError> {
+        HfError::from_u32(self.error)
+    }
 }
 
 /// Controls whether the SP or host CPU has access to flash
@@ -44,4 +100,47 @@ pub enum HfDevSelect {
     Flash1 = 1,
 }
 
+/// The state of a host image slot.  Each slot is a flash device.
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, AsBytes)]
+#[repr(u8)]
+pub enum HfSlotState {
+    /// The slot has no (known) image
+    Empty = 0,
+    /// The slot holds a new image, to be tried on the next host boot
+    Pending = 1,
+    /// The slot holds the image from which the host boots
+    Active = 2,
+    /// The slot holds an image that is neither pending nor active
+    Inactive = 3,
+    /// The slot's image failed to boot too many times
+    Failed = 4,
+}
+
+///
+/// Information about a host image slot, as returned by
+/// `HostFlash::slot_info`.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct HfSlotInfo {
+    /// `HfSlotState` of the slot
+    pub state: u32,
+    /// Version of the image, as supplied when it was marked pending
+    pub version: u32,
+    /// Consecutive failed host boots from this slot since the SP booted
+    pub boot_failures: u32,
+    /// Nonzero if the host has successfully booted from this slot
+    pub last_known_good: u32,
+    /// Nonzero if the slot is write protected
+    pub protected: u32,
+    /// SHA-256 hash of the image, as supplied when it was marked pending
+    pub hash: [u8; SHA256_SZ],
+}
+
+impl HfSlotInfo {
+    pub fn state(&self) -> Option<HfSlotState> {
+        HfSlotState::from_u32(self.state)
+    }
+}
+
 include!(concat!(env!("OUT_DIR"), "/client_stub.rs"));
@@ -6,17 +6,38 @@
 //!
 //! This server is responsible for managing access to the host flash; it embeds
 //! the QSPI flash driver.
+//!
+//! In addition to page- and sector-sized operations, the server offers bulk
+//! operations on ranges of flash (`erase_range`, `program`, `verify` and
+//! `hash`) to allow an entire host image to be written in a handful of IPCs.
+//! These can take a long time; their progress is recorded in our ring buffer
+//! as they run, and can be retrieved via `progress` (e.g., to determine where
+//! a failed operation should be resumed).
+//!
+//! Erasing a range takes longest of all, so `erase_range` only starts the
+//! erase:  it proceeds in the background a sector at a time, with our timer
+//! telling us when to check on it, and we continue to serve requests while it
+//! runs -- `progress` among them.  Until it is done, requests that need the
+//! flash itself (or the mux) fail with `HfError::Busy`.
+//!
+//! On boards with two flash devices, the server also manages A/B slots for
+//! the host image (see the `slot` module), selecting the device from which
+//! the host boots when the mux is handed to it.
 
 #![no_std]
 #![no_main]
 
 mod bsp;
+mod slot;
 
+use ringbuf::*;
 use userlib::*;
 
 use drv_stm32h7_qspi::Qspi;
 use drv_stm32xx_sys_api as sys_api;
-use idol_runtime::{ClientError, Leased, LenLimit, RequestError, R, W};
+use idol_runtime::{
+    ClientError, Leased, LenLimit, NotificationHandler, RequestError, R, W,
+};
 
 #[cfg(feature = "h743")]
 use stm32h7::stm32h743 as device;
@@ -31,7 +52,11 @@ use stm32h7::stm32h753 as device;
 use drv_hash_api as hash_api;
 use drv_hash_api::SHA256_SZ;
 
-use drv_gimlet_hf_api::{HfDevSelect, HfError, HfMuxState};
+use drv_gimlet_hf_api::{
+    HfBulkOp, HfDevSelect, HfError, HfMuxState, HfProgress, HfSlotInfo,
+    HfSlotState, PAGE_SIZE, SECTOR_SIZE,
+};
+use slot::{SlotRecord, Slots, SLOTS, SLOT_MAGIC};
 
 task_slot!(SYS, sys);
 #[cfg(feature = "hash")]
@@ -39,6 +64,41 @@ task_slot!(HASH, hash_driver);
 
 const QSPI_IRQ: u32 = 1;
 
+/// Notification mask for our timer, which we use to check on an erase.
+const TIMER_MASK: u32 = 1 << 1;
+
+///
+/// Interval (in milliseconds) at which we check whether the sector being
+/// erased is done.  Sector erases take hundreds of milliseconds.
+///
+const ERASE_POLL_INTERVAL: u64 = 10;
+
+///
+/// Interval (in bytes) at which we record the progress of bulk operations.
+///
+const PROGRESS_INTERVAL: u32 = SECTOR_SIZE;
+
+///
+/// Block protect and top/bottom bits in the Status register, on both of the
+/// parts that we support.
+///
+const BLOCK_PROTECT: u8 = 0b0111_1100;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Trace {
+    Begin(HfBulkOp, u32, u32),
+    Progress(u32),
+    Done(HfBulkOp),
+    Failed(HfBulkOp, HfError),
+    SlotState(HfDevSelect, HfSlotState),
+    SlotSyncFailed(HfDevSelect, HfError),
+    BootSlot(HfDevSelect),
+    BootFailed(HfDevSelect, u32),
+    None,
+}
+
+ringbuf!(Trace, 16, Trace::None);
+
 struct Config {
     pub sp_host_mux_select: sys_api::PinSet,
     pub reset: sys_api::PinSet,
@@ -95,31 +155,29 @@ fn main() -> ! {
     // TODO: If different flash parts are used on the same board name,
     // then hard-coding commands, capacity, and clocks will get us into
     // trouble. Someday we will need more flexability here.
-    let capacity = {
-        let mut idbuf = [0; 20];
-        qspi.read_id(&mut idbuf);
-
-        match idbuf[0] {
-            0x00 => None, // Invalid
-            0xef => {
-                // Winbond
-                if idbuf[1] != 0x40 {
-                    None
-                } else {
-                    Some(idbuf[2])
-                }
+    let mut idbuf = [0; 20];
+    qspi.read_id(&mut idbuf);
+
+    let capacity = match idbuf[0] {
+        0x00 => None, // Invalid
+        0xef => {
+            // Winbond
+            if idbuf[1] != 0x40 {
+                None
+            } else {
+                Some(idbuf[2])
             }
-            0x20 => {
-                if !matches!(idbuf[1], 0xBA | 0xBB) {
-                    // 1.8v or 3.3v
-                    None
-                } else {
-                    // TODO: Stash, or read on demand, Micron Unique ID for measurement?
-                    Some(idbuf[2])
-                }
+        }
+        0x20 => {
+            if !matches!(idbuf[1], 0xBA | 0xBB) {
+                // 1.8v or 3.3v
+                None
+            } else {
+                // TODO: Stash, or read on demand, Micron Unique ID for measurement?
+                Some(idbuf[2])
             }
-            _ => None, // Unknown
         }
+        _ => None, // Unknown
     };
 
     if capacity.is_none() {
@@ -139,13 +197,32 @@ fn main() -> ! {
         dev_state: HfDevSelect::Flash0,
         mux_select_pin: cfg.sp_host_mux_select,
         dev_select_pin: cfg.flash_dev_select,
+        capacity,
+        slot_protect: slot_protection(idbuf[0], capacity),
+        progress: HfProgress::default(),
+        slots: None,
+        booting: HfDevSelect::Flash0,
+        erase: None,
     };
 
+    server.load_slots();
+
     loop {
-        idol_runtime::dispatch(&mut buffer, &mut server);
+        idol_runtime::dispatch_n(&mut buffer, &mut server);
     }
 }
 
+///
+/// An erase proceeding in the background:  the operation that started it,
+/// the sector being erased, and the end of the range.
+///
+#[derive(Copy, Clone)]
+struct Erase {
+    op: HfBulkOp,
+    sector: u32,
+    end: u32,
+}
+
 struct ServerImpl {
     qspi: Qspi,
     block: [u8; 256],
@@ -157,6 +234,341 @@ struct ServerImpl {
     /// Selects between QSPI flash chips 1 and 2 (if present)
     dev_state: HfDevSelect,
     dev_select_pin: Option<sys_api::PinSet>,
+
+    /// Log2 of the size of the flash, in bytes
+    capacity: u8,
+
+    /// Status register bits that write protect a slot
+    slot_protect: u8,
+
+    /// Progress of the current or most recent bulk operation
+    progress: HfProgress,
+
+    /// Host image slots, if we have more than one flash device
+    slots: Option<Slots>,
+
+    /// Slot selected when the mux was last handed to the host
+    booting: HfDevSelect,
+
+    /// Erase in progress, if any
+    erase: Option<Erase>,
+}
+
+impl ServerImpl {
+    ///
+    /// Checks that `len` bytes starting at `addr` are within the flash, and
+    /// that both are multiples of `align`.
+    ///
+    fn check_range(
+        &self,
+        addr: u32,
+        len: u32,
+        align: u32,
+    ) -> Result<(), HfError> {
+        let end = addr.checked_add(len).ok_or(HfError::BadRange)?;
+
+        if addr % align != 0 || len % align != 0 {
+            return Err(HfError::BadRange);
+        }
+
+        if end as u64 > 1u64 << self.capacity {
+            return Err(HfError::BadRange);
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Returns the address of the sector holding slot metadata.
+    ///
+    fn slot_sector(&self) -> u32 {
+        ((1u64 << self.capacity) - SECTOR_SIZE as u64) as u32
+    }
+
+    ///
+    /// Returns the size of a slot, which starts at the bottom of its device.
+    ///
+    fn slot_len(&self) -> u32 {
+        (1u64 << (self.capacity - 1)) as u32
+    }
+
+    fn slots(&mut self) -> Result<&mut Slots, HfError> {
+        self.slots.as_mut().ok_or(HfError::NoDevSelect)
+    }
+
+    ///
+    /// Checks that there is no erase in progress, which would leave the
+    /// flash unable to do anything else -- and which we mustn't pull the
+    /// flash out from under.
+    ///
+    fn check_idle(&self) -> Result<(), HfError> {
+        match self.erase {
+            Some(_) => Err(HfError::Busy),
+            None => Ok(()),
+        }
+    }
+
+    ///
+    /// Runs `f` with `dev` selected, reselecting the current device
+    /// afterwards.
+    ///
+    fn with_dev<T>(
+        &mut self,
+        dev: HfDevSelect,
+        f: impl FnOnce(&Qspi) -> Result<T, HfError>,
+    ) -> Result<T, HfError> {
+        let pin = self.dev_select_pin.ok_or(HfError::NoDevSelect)?;
+
+        if self.mux_state != HfMuxState::SP {
+            return Err(HfError::HostOwnsFlash);
+        }
+
+        self.check_idle()?;
+
+        select_dev(pin, dev)?;
+        let rval = f(&self.qspi);
+        select_dev(pin, self.dev_state)?;
+
+        rval
+    }
+
+    ///
+    /// Reads the slot records from each device, and makes sure that the
+    /// active slot is write protected.
+    ///
+    fn load_slots(&mut self) {
+        if self.dev_select_pin.is_none() {
+            return;
+        }
+
+        let sector = self.slot_sector();
+        let mut slots = Slots::default();
+
+        for &dev in &SLOTS {
+            let mut record = SlotRecord::default();
+
+            let _ = self.with_dev(dev, |qspi| {
+                qspi.read_memory(sector, record.as_bytes_mut());
+                Ok(())
+            });
+
+            if record.magic == SLOT_MAGIC {
+                slots.get_mut(dev).record = record;
+            }
+
+            ringbuf_entry!(Trace::SlotState(dev, slots.get(dev).state()));
+        }
+
+        self.slots = Some(slots);
+        let _ = self.sync_slots();
+    }
+
+    ///
+    /// Writes any slot records that have changed, and sets write protection
+    /// such that only the active slot is protected.
+    ///
+    fn sync_slots(&mut self) -> Result<(), HfError> {
+        let sector = self.slot_sector();
+
+        for &dev in &SLOTS {
+            let slot = *self.slots()?.get(dev);
+
+            let protect = match slot.state() {
+                HfSlotState::Active => self.slot_protect,
+                _ => 0,
+            };
+
+            //
+            // The slot record lies outside of the slot, so we needn't lift
+            // the slot's protection to write it.
+            //
+            let rval = self.with_dev(dev, |qspi| {
+                set_protection(qspi, protect)?;
+
+                if slot.dirty {
+                    set_and_check_write_enable(qspi)?;
+                    qspi.sector_erase(sector);
+                    poll_for_write_complete(qspi);
+
+                    set_and_check_write_enable(qspi)?;
+                    qspi.page_program(sector, slot.record.as_bytes());
+                    poll_for_write_complete(qspi);
+                }
+
+                Ok(())
+            });
+
+            if let Err(err) = rval {
+                ringbuf_entry!(Trace::SlotSyncFailed(dev, err));
+                return Err(err);
+            }
+
+            self.slots()?.get_mut(dev).dirty = false;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Checks that `len` bytes starting at `addr` on the selected device may
+    /// be written, invalidating the image in the device's slot if the range
+    /// includes it.
+    ///
+    fn begin_write(&mut self, addr: u32, len: u32) -> Result<(), HfError> {
+        self.check_idle()?;
+
+        let dev = self.dev_state;
+        let sector = self.slot_sector();
+        let slot_len = self.slot_len();
+
+        let slots = match self.slots.as_mut() {
+            Some(slots) => slots,
+            None => return Ok(()),
+        };
+
+        if addr.saturating_add(len) > sector {
+            return Err(HfError::BadRange);
+        }
+
+        if addr >= slot_len {
+            return Ok(());
+        }
+
+        let slot = slots.get_mut(dev);
+
+        if slot.state() == HfSlotState::Active {
+            return Err(HfError::SlotProtected);
+        }
+
+        if slot.state() != HfSlotState::Empty {
+            slot.invalidate();
+            ringbuf_entry!(Trace::SlotState(dev, slot.state()));
+            self.sync_slots()?;
+        }
+
+        Ok(())
+    }
+
+    fn begin(&mut self, op: HfBulkOp, addr: u32, len: u32) {
+        ringbuf_entry!(Trace::Begin(op, addr, len));
+
+        self.progress = HfProgress {
+            op: op as u32,
+            address: addr,
+            done: 0,
+            total: len,
+            error: 0,
+        };
+    }
+
+    fn advance(&mut self, nbytes: u32) {
+        let before = self.progress.done / PROGRESS_INTERVAL;
+        self.progress.done += nbytes;
+
+        if self.progress.done / PROGRESS_INTERVAL != before {
+            ringbuf_entry!(Trace::Progress(self.progress.done));
+        }
+    }
+
+    fn done(&mut self, op: HfBulkOp) {
+        ringbuf_entry!(Trace::Done(op));
+    }
+
+    ///
+    /// Starts erasing `len` bytes starting at `addr` (both of which must be
+    /// sector-aligned) in the background.
+    ///
+    fn start_erase(&mut self, op: HfBulkOp, addr: u32, len: u32) {
+        self.begin(op, addr, len);
+        self.erase = Some(Erase {
+            op,
+            sector: addr,
+            end: addr + len,
+        });
+        self.erase_sector();
+    }
+
+    ///
+    /// Starts erasing the next sector of the erase in progress, or finishes
+    /// the erase if there are none left.
+    ///
+    fn erase_sector(&mut self) {
+        let erase = match self.erase {
+            Some(erase) if erase.sector < erase.end => erase,
+            Some(erase) => {
+                self.erase = None;
+                self.done(erase.op);
+                return;
+            }
+            None => return,
+        };
+
+        if let Err(err) = set_and_check_write_enable(&self.qspi) {
+            ringbuf_entry!(Trace::Failed(erase.op, err));
+            self.progress.error = err as u32;
+            self.erase = None;
+            return;
+        }
+
+        self.qspi.sector_erase(erase.sector);
+
+        let deadline = sys_get_timer().now + ERASE_POLL_INTERVAL;
+        sys_set_timer(Some(deadline), TIMER_MASK);
+    }
+
+    ///
+    /// Computes the SHA-256 hash of `len` bytes starting at `addr`, recording
+    /// progress against `op`.
+    ///
+    #[cfg(feature = "hash")]
+    fn hash_range(
+        &mut self,
+        op: HfBulkOp,
+        addr: u32,
+        len: u32,
+    ) -> Result<[u8; SHA256_SZ], HfError> {
+        self.check_range(addr, len, 1)
+            .map_err(|_| HfError::HashBadRange)?;
+        self.check_idle()?;
+
+        let hash_driver = hash_api::Hash::from(HASH.get_task_id());
+        hash_driver.init_sha256().map_err(|_| HfError::HashError)?;
+
+        self.begin(op, addr, len);
+
+        let end = addr + len;
+        let mut addr = addr;
+
+        while addr < end {
+            let size = u32::min(self.block.len() as u32, end - addr);
+            let block = &mut self.block[..size as usize];
+
+            self.qspi.read_memory(addr, block);
+            hash_driver
+                .update(size, block)
+                .map_err(|_| HfError::HashError)?;
+
+            addr += size;
+            self.advance(size);
+        }
+
+        let sum = hash_driver
+            .finalize_sha256()
+            .map_err(|_| HfError::HashError)?;
+
+        self.done(op);
+        Ok(sum)
+    }
+
+    #[cfg(not(feature = "hash"))]
+    fn hash_range(
+        &mut self,
+        _op: HfBulkOp,
+        _addr: u32,
+        _len: u32,
+    ) -> Result<[u8; SHA256_SZ], HfError> {
+        Err(HfError::HashNotConfigured)
+    }
 }
 
 impl idl::InOrderHostFlashImpl for ServerImpl {
@@ -180,6 +592,20 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
         &mut self,
         _: &RecvMessage,
     ) -> Result<(), RequestError<HfError>> {
+        self.check_idle()?;
+
+        //
+        // If we have slots, a bulk erase would take the slot record with it,
+        // so we erase the sectors before it instead -- which takes as long
+        // as any erase_range, and proceeds in the background in the same way.
+        //
+        if self.slots.is_some() {
+            let len = self.slot_sector();
+            self.begin_write(0, len)?;
+            self.start_erase(HfBulkOp::Erase, 0, len);
+            return Ok(());
+        }
+
         set_and_check_write_enable(&self.qspi)?;
         self.qspi.bulk_erase();
         poll_for_write_complete(&self.qspi);
@@ -196,6 +622,8 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
         data.read_range(0..data.len(), &mut self.block[..data.len()])
             .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
 
+        self.begin_write(addr, data.len() as u32)?;
+
         // Now we can't fail.
 
         set_and_check_write_enable(&self.qspi)?;
@@ -210,6 +638,7 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
         addr: u32,
         dest: LenLimit<Leased<W, [u8]>, 256>,
     ) -> Result<(), RequestError<HfError>> {
+        self.check_idle()?;
         self.qspi.read_memory(addr, &mut self.block[..dest.len()]);
 
         dest.write_range(0..dest.len(), &self.block[..dest.len()])
@@ -223,6 +652,7 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
         _: &RecvMessage,
         addr: u32,
     ) -> Result<(), RequestError<HfError>> {
+        self.begin_write(addr - addr % SECTOR_SIZE, SECTOR_SIZE)?;
         set_and_check_write_enable(&self.qspi)?;
         self.qspi.sector_erase(addr);
         poll_for_write_complete(&self.qspi);
@@ -241,6 +671,29 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
         _: &RecvMessage,
         state: HfMuxState,
     ) -> Result<(), RequestError<HfError>> {
+        self.check_idle()?;
+
+        //
+        // Before handing the flash to the host, we make sure that our slot
+        // records are written and the active slot protected, and select the
+        // device from which the host should boot.
+        //
+        if state == HfMuxState::HostCPU && self.mux_state == HfMuxState::SP {
+            if let Some(slots) = self.slots {
+                self.sync_slots()?;
+
+                let dev = slots.boot_slot();
+                ringbuf_entry!(Trace::BootSlot(dev));
+
+                if let Some(pin) = self.dev_select_pin {
+                    select_dev(pin, dev)?;
+                    self.dev_state = dev;
+                }
+
+                self.booting = dev;
+            }
+        }
+
         let sys = sys_api::Sys::from(SYS.get_task_id());
 
         let rv = match state {
@@ -252,6 +705,15 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
             Err(_) => Err(HfError::MuxFailed.into()),
             Ok(_) => {
                 self.mux_state = state;
+
+                //
+                // If we've been told the outcome of a boot while the host
+                // had the flash, we can now record it.
+                //
+                if state == HfMuxState::SP && self.slots.is_some() {
+                    let _ = self.sync_slots();
+                }
+
                 Ok(())
             }
         }
@@ -271,92 +733,282 @@ impl idl::InOrderHostFlashImpl for ServerImpl {
     ) -> Result<(), RequestError<HfError>> {
         // Return early if the dev select pin is missing
         let dev_select_pin = self.dev_select_pin.ok_or(HfError::NoDevSelect)?;
+        self.check_idle()?;
 
-        let sys = sys_api::Sys::from(SYS.get_task_id());
-        let rv = match state {
-            HfDevSelect::Flash0 => sys.gpio_reset(dev_select_pin),
-            HfDevSelect::Flash1 => sys.gpio_set(dev_select_pin),
-        };
+        select_dev(dev_select_pin, state)?;
+        self.dev_state = state;
+        Ok(())
+    }
 
-        match rv {
-            Err(_) => Err(HfError::DevSelectFailed.into()),
-            Ok(_) => {
-                self.dev_state = state;
-                Ok(())
-            }
+    fn erase_range(
+        &mut self,
+        _: &RecvMessage,
+        addr: u32,
+        len: u32,
+    ) -> Result<(), RequestError<HfError>> {
+        self.check_range(addr, len, SECTOR_SIZE)?;
+        self.begin_write(addr, len)?;
+        self.start_erase(HfBulkOp::Erase, addr, len);
+        Ok(())
+    }
+
+    fn program(
+        &mut self,
+        _: &RecvMessage,
+        addr: u32,
+        data: Leased<R, [u8]>,
+    ) -> Result<(), RequestError<HfError>> {
+        let len = data.len() as u32;
+
+        self.check_range(addr, len, 1)?;
+        self.begin_write(addr, len)?;
+        self.begin(HfBulkOp::Program, addr, len);
+
+        let mut offset = 0;
+
+        while offset < len {
+            //
+            // Each write must be confined to a single page.
+            //
+            let page = addr + offset;
+            let size = u32::min(PAGE_SIZE - page % PAGE_SIZE, len - offset);
+            let (start, end) = (offset as usize, (offset + size) as usize);
+            let block = &mut self.block[..size as usize];
+
+            data.read_range(start..end, block)
+                .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+            set_and_check_write_enable(&self.qspi)?;
+            self.qspi.page_program(page, block);
+            poll_for_write_complete(&self.qspi);
+
+            offset += size;
+            self.advance(size);
         }
+
+        self.done(HfBulkOp::Program);
+        Ok(())
     }
 
-    cfg_if::cfg_if! {
-        if #[cfg(feature = "hash")] {
-            fn hash(
-                &mut self,
-                _: &RecvMessage,
-                addr: u32,
-                len: u32,
-            ) -> Result<[u8; SHA256_SZ], RequestError<HfError>> {
-                let hash_driver = hash_api::Hash::from(HASH.get_task_id());
-                if let Err(_) = hash_driver.init_sha256() {
-                    return Err(HfError::HashError.into());
-                }
-                let begin = addr as usize;
-                // TODO: Begin may be an address beyond physical end of
-                // flash part and may wrap around.
-                let end = match begin.checked_add(len as usize) {
-                    Some(end) => {
-                        // Check end > maximum 4-byte address.
-                        // TODO: End may be beyond physical end of flash part.
-                        //       Use that limit rather than maximum 4-byte address.
-                        if end > u32::MAX as usize {
-                            return Err(HfError::HashBadRange.into());
-                        } else {
-                            end
-                        }
-                    },
-                    None => {
-                        return Err(HfError::HashBadRange.into());
-                    },
-                };
-                // If we knew the flash part size, we'd check against those limits.
-                for addr in (begin..end).step_by(self.block.len()) {
-                    let size = if self.block.len() < (end - addr) {
-                        self.block.len()
-                    } else {
-                        end - addr
-                    };
-                    self.qspi.read_memory(addr as u32, &mut self.block[..size]);
-                    if let Err(_) = hash_driver.update(
-                        size as u32, &self.block[..size]) {
-                        return Err(HfError::HashError.into());
-                    }
-                }
-                match hash_driver.finalize_sha256() {
-                    Ok(sum) => Ok(sum),
-                    Err(_) => Err(HfError::HashError.into()),   // XXX losing info
-                }
-            }
-        } else {
-            fn hash(
-                &mut self,
-                _: &RecvMessage,
-                _addr: u32,
-                _len: u32,
-            ) -> Result<[u8; SHA256_SZ], RequestError<HfError>> {
-                Err(HfError::HashNotConfigured.into())
-            }
+    fn hash(
+        &mut self,
+        _: &RecvMessage,
+        addr: u32,
+        len: u32,
+    ) -> Result<[u8; SHA256_SZ], RequestError<HfError>> {
+        Ok(self.hash_range(HfBulkOp::Hash, addr, len)?)
+    }
+
+    fn verify(
+        &mut self,
+        _: &RecvMessage,
+        addr: u32,
+        len: u32,
+        hash: [u8; SHA256_SZ],
+    ) -> Result<(), RequestError<HfError>> {
+        if self.hash_range(HfBulkOp::Verify, addr, len)? != hash {
+            return Err(HfError::VerifyFailed.into());
+        }
+
+        Ok(())
+    }
+
+    fn progress(
+        &mut self,
+        _: &RecvMessage,
+    ) -> Result<HfProgress, RequestError<HfError>> {
+        Ok(self.progress)
+    }
+
+    fn slot_info(
+        &mut self,
+        _: &RecvMessage,
+        dev: HfDevSelect,
+    ) -> Result<HfSlotInfo, RequestError<HfError>> {
+        let slot = *self.slots()?.get(dev);
+        let state = slot.state();
+
+        //
+        // If the host has the flash, we report the protection that we set
+        // before handing it over.
+        //
+        let protected = self
+            .with_dev(dev, |qspi| Ok(qspi.read_status() & BLOCK_PROTECT != 0))
+            .unwrap_or(state == HfSlotState::Active);
+
+        Ok(HfSlotInfo {
+            state: state as u32,
+            version: slot.record.version,
+            boot_failures: slot.boot_failures,
+            last_known_good: slot.record.last_known_good,
+            protected: protected as u32,
+            hash: slot.record.hash,
+        })
+    }
+
+    fn mark_pending(
+        &mut self,
+        _: &RecvMessage,
+        dev: HfDevSelect,
+        version: u32,
+        hash: [u8; SHA256_SZ],
+    ) -> Result<(), RequestError<HfError>> {
+        if self.mux_state != HfMuxState::SP {
+            return Err(HfError::HostOwnsFlash.into());
+        }
+
+        self.slots()?.mark_pending(dev, version, hash)?;
+        ringbuf_entry!(Trace::SlotState(dev, HfSlotState::Pending));
+        self.sync_slots()?;
+        Ok(())
+    }
+
+    fn mark_active(
+        &mut self,
+        _: &RecvMessage,
+        dev: HfDevSelect,
+    ) -> Result<(), RequestError<HfError>> {
+        if self.mux_state != HfMuxState::SP {
+            return Err(HfError::HostOwnsFlash.into());
+        }
+
+        self.slots()?.mark_active(dev)?;
+        ringbuf_entry!(Trace::SlotState(dev, HfSlotState::Active));
+        self.sync_slots()?;
+        Ok(())
+    }
+
+    fn boot_slot(
+        &mut self,
+        _: &RecvMessage,
+    ) -> Result<HfDevSelect, RequestError<HfError>> {
+        Ok(self.slots()?.boot_slot())
+    }
+
+    fn report_boot_success(
+        &mut self,
+        _: &RecvMessage,
+    ) -> Result<(), RequestError<HfError>> {
+        let dev = self.booting;
+        self.slots()?.boot_succeeded(dev);
+
+        //
+        // If the flash is otherwise engaged, the outcome will be recorded
+        // when we next sync our slots.
+        //
+        if self.mux_state == HfMuxState::SP && self.erase.is_none() {
+            self.sync_slots()?;
         }
+
+        Ok(())
     }
+
+    fn report_boot_failure(
+        &mut self,
+        _: &RecvMessage,
+    ) -> Result<(), RequestError<HfError>> {
+        let dev = self.booting;
+        let slots = self.slots()?;
+        slots.boot_failed(dev);
+
+        let failures = slots.get(dev).boot_failures;
+        ringbuf_entry!(Trace::BootFailed(dev, failures));
+
+        for &dev in &SLOTS {
+            ringbuf_entry!(Trace::SlotState(dev, slots.get(dev).state()));
+        }
+
+        if self.mux_state == HfMuxState::SP && self.erase.is_none() {
+            self.sync_slots()?;
+        }
+
+        Ok(())
+    }
+}
+
+impl NotificationHandler for ServerImpl {
+    fn current_notification_mask(&self) -> u32 {
+        TIMER_MASK
+    }
+
+    fn handle_notification(&mut self, _bits: u32) {
+        let erase = match self.erase {
+            Some(erase) => erase,
+            None => return,
+        };
+
+        //
+        // If the sector is still being erased, check again later.
+        //
+        if self.qspi.read_status() & 1 != 0 {
+            let deadline = sys_get_timer().now + ERASE_POLL_INTERVAL;
+            sys_set_timer(Some(deadline), TIMER_MASK);
+            return;
+        }
+
+        self.advance(SECTOR_SIZE);
+        self.erase = Some(Erase {
+            sector: erase.sector + SECTOR_SIZE,
+            ..erase
+        });
+        self.erase_sector();
+    }
+}
+
+fn select_dev(pin: sys_api::PinSet, dev: HfDevSelect) -> Result<(), HfError> {
+    let sys = sys_api::Sys::from(SYS.get_task_id());
+
+    let rv = match dev {
+        HfDevSelect::Flash0 => sys.gpio_reset(pin),
+        HfDevSelect::Flash1 => sys.gpio_set(pin),
+    };
+
+    rv.map_err(|_| HfError::DevSelectFailed)
+}
+
+///
+/// Returns the Status register bits that protect a slot -- the lower half
+/// -- of a part from `manufacturer` of 2^`capacity` bytes.  Both of the parts
+/// that we support protect 2^(n - 1) sectors from the bottom (if TB is set)
+/// when the BP bits hold n; they differ in where they put TB and BP3.
+///
+fn slot_protection(manufacturer: u8, capacity: u8) -> u8 {
+    let n = capacity - 16;
+    let bp = (n & 0b111) << 2;
+    let bp3 = (n >> 3) & 1;
+
+    match manufacturer {
+        // Winbond: TB, BP3, BP2, BP1, BP0
+        0xef => 1 << 6 | bp3 << 5 | bp,
+        // Micron: BP3, TB, BP2, BP1, BP0
+        _ => bp3 << 6 | 1 << 5 | bp,
+    }
+}
+
+///
+/// Sets the block protect bits of the selected device to `protect`, which
+/// is either 0 or the bits that protect a slot.
+///
+fn set_protection(qspi: &Qspi, protect: u8) -> Result<(), HfError> {
+    let status = qspi.read_status();
+    let new = (status & !BLOCK_PROTECT) | protect;
+
+    if new != status {
+        set_and_check_write_enable(qspi)?;
+        qspi.write_status(new);
+        poll_for_write_complete(qspi);
+    }
+
+    Ok(())
 }
 
-fn set_and_check_write_enable(
-    qspi: &Qspi,
-) -> Result<(), RequestError<HfError>> {
+fn set_and_check_write_enable(qspi: &Qspi) -> Result<(), HfError> {
     qspi.write_enable();
     let status = qspi.read_status();
 
     if status & 0b10 == 0 {
         // oh oh
-        return Err(HfError::WriteEnableFailed.into());
+        return Err(HfError::WriteEnableFailed);
     }
     Ok(())
 }
@@ -372,7 +1024,7 @@ fn poll_for_write_complete(qspi: &Qspi) {
 }
 
 mod idl {
-    use super::{HfDevSelect, HfError, HfMuxState};
+    use super::{HfDevSelect, HfError, HfMuxState, HfProgress, HfSlotInfo};
 
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
@@ -0,0 +1,212 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Host image slots.
+//!
+//! On boards with two host flash devices, each device has a slot that can
+//! hold a host image:  the lower half of the device, which is as much as the
+//! block protect bits can protect without also protecting the last sector.
+//! At most one slot is active (and write protected); a
+//! new image is written to the other slot and marked pending, whereupon the
+//! host will boot from it until it is either marked active or fails to boot
+//! `MAX_BOOT_FAILURES` times in a row.  If the active slot fails to boot
+//! `MAX_BOOT_FAILURES` times in a row and the other slot holds an image that
+//! has booted in the past, we fall back to it.
+//!
+//! The state of each slot is kept in a `SlotRecord` in the last sector of
+//! its device; counts of boot failures are kept only in memory.  The rest of
+//! the upper half of the device is free for other uses (e.g. the sequencer's
+//! FPGA image), and remains writable when the slot is active.
+//!
+//! The outcome of each host boot is reported to us (via `report_boot_success`
+//! or `report_boot_failure`) by whatever observes the host's progress through
+//! boot -- not by the sequencer, which only knows whether the rails came up.
+//!
+
+use drv_gimlet_hf_api::{HfDevSelect, HfError, HfSlotState};
+use drv_hash_api::SHA256_SZ;
+use userlib::FromPrimitive;
+use zerocopy::{AsBytes, FromBytes};
+
+/// Number of consecutive failed boots after which we give up on a slot.
+pub const MAX_BOOT_FAILURES: u32 = 3;
+
+/// Magic number identifying a valid `SlotRecord`.
+pub const SLOT_MAGIC: u32 = 0x5107_ab01;
+
+///
+/// The persistent state of a slot.
+///
+#[derive(Copy, Clone, Default, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct SlotRecord {
+    pub magic: u32,
+    pub state: u32,
+    pub version: u32,
+    pub last_known_good: u32,
+    pub hash: [u8; SHA256_SZ],
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct Slot {
+    pub record: SlotRecord,
+    pub boot_failures: u32,
+
+    /// true if `record` has changed since it was last written
+    pub dirty: bool,
+}
+
+impl Slot {
+    pub fn state(&self) -> HfSlotState {
+        if self.record.magic != SLOT_MAGIC {
+            return HfSlotState::Empty;
+        }
+
+        HfSlotState::from_u32(self.record.state).unwrap_or(HfSlotState::Empty)
+    }
+
+    fn set_state(&mut self, state: HfSlotState) {
+        if self.state() != state {
+            self.record.magic = SLOT_MAGIC;
+            self.record.state = state as u32;
+            self.dirty = true;
+        }
+    }
+
+    ///
+    /// Forgets the slot's image, e.g. because it is being overwritten.
+    ///
+    pub fn invalidate(&mut self) {
+        if self.state() != HfSlotState::Empty {
+            self.record = SlotRecord::default();
+            self.dirty = true;
+        }
+
+        self.boot_failures = 0;
+    }
+}
+
+pub const SLOTS: [HfDevSelect; 2] = [HfDevSelect::Flash0, HfDevSelect::Flash1];
+
+#[derive(Copy, Clone, Default)]
+pub struct Slots([Slot; 2]);
+
+impl Slots {
+    pub fn get(&self, dev: HfDevSelect) -> &Slot {
+        &self.0[dev as usize]
+    }
+
+    pub fn get_mut(&mut self, dev: HfDevSelect) -> &mut Slot {
+        &mut self.0[dev as usize]
+    }
+
+    fn other(dev: HfDevSelect) -> HfDevSelect {
+        match dev {
+            HfDevSelect::Flash0 => HfDevSelect::Flash1,
+            HfDevSelect::Flash1 => HfDevSelect::Flash0,
+        }
+    }
+
+    ///
+    /// Returns the slot from which the host should next boot: a pending
+    /// slot if there is one, or the active slot if there is one.  (Absent
+    /// either, we boot from the first device, as we always have.)
+    ///
+    pub fn boot_slot(&self) -> HfDevSelect {
+        for &state in &[HfSlotState::Pending, HfSlotState::Active] {
+            for &dev in &SLOTS {
+                if self.get(dev).state() == state {
+                    return dev;
+                }
+            }
+        }
+
+        HfDevSelect::Flash0
+    }
+
+    pub fn mark_pending(
+        &mut self,
+        dev: HfDevSelect,
+        version: u32,
+        hash: [u8; SHA256_SZ],
+    ) -> Result<(), HfError> {
+        let slot = self.get_mut(dev);
+
+        if slot.state() == HfSlotState::Active {
+            return Err(HfError::SlotProtected);
+        }
+
+        slot.record = SlotRecord {
+            magic: SLOT_MAGIC,
+            state: HfSlotState::Pending as u32,
+            version,
+            last_known_good: 0,
+            hash,
+        };
+        slot.boot_failures = 0;
+        slot.dirty = true;
+
+        Ok(())
+    }
+
+    pub fn mark_active(&mut self, dev: HfDevSelect) -> Result<(), HfError> {
+        if self.get(dev).state() == HfSlotState::Empty {
+            return Err(HfError::SlotEmpty);
+        }
+
+        let other = self.get_mut(Self::other(dev));
+
+        if other.state() == HfSlotState::Active {
+            other.set_state(HfSlotState::Inactive);
+        }
+
+        let slot = self.get_mut(dev);
+        slot.set_state(HfSlotState::Active);
+        slot.boot_failures = 0;
+
+        Ok(())
+    }
+
+    pub fn boot_succeeded(&mut self, dev: HfDevSelect) {
+        let slot = self.get_mut(dev);
+        slot.boot_failures = 0;
+
+        if slot.state() != HfSlotState::Empty
+            && slot.record.last_known_good == 0
+        {
+            slot.record.last_known_good = 1;
+            slot.dirty = true;
+        }
+    }
+
+    pub fn boot_failed(&mut self, dev: HfDevSelect) {
+        let slot = self.get_mut(dev);
+        slot.boot_failures += 1;
+
+        if slot.boot_failures < MAX_BOOT_FAILURES {
+            return;
+        }
+
+        match slot.state() {
+            HfSlotState::Pending => {
+                slot.set_state(HfSlotState::Failed);
+            }
+
+            HfSlotState::Active => {
+                let other = Self::other(dev);
+                let fallback = self.get(other);
+
+                if fallback.state() == HfSlotState::Inactive
+                    && fallback.record.last_known_good != 0
+                {
+                    self.get_mut(dev).set_state(HfSlotState::Failed);
+                    self.get_mut(other).set_state(HfSlotState::Active);
+                    self.get_mut(other).boot_failures = 0;
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
@@ -8,17 +8,72 @@
 
 use derive_idol_err::IdolError;
 use userlib::*;
-use zerocopy::AsBytes;
+use zerocopy::{AsBytes, FromBytes};
 
-#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, IdolError)]
+pub use power_history::{PowerReason, PowerTransition, ThermalTrip};
+
+#[derive(
+    Copy, Clone, Debug, FromPrimitive, ToPrimitive, PartialEq, IdolError,
+)]
 pub enum SeqError {
     IllegalTransition = 1,
     MuxToHostCPUFailed = 2,
     MuxToSPFailed = 3,
     ClockConfigFailed = 4,
+    /// SPI communication with the sequencer FPGA failed
+    SequencerFpgaFailed = 5,
+    /// A1 and A0 groups A and B failed to come up in time
+    GroupABTimeout = 6,
+    /// VDD_VCORE and VDDCR_SOC could not be enabled
+    VcoreSocFailed = 7,
+    /// A0 group C failed to come up in time
+    GroupCTimeout = 8,
+    /// The requested transition (or thermal trip) is not in the history
+    BadHistoryIndex = 9,
+}
+
+/// Size of an FPGA image hash (SHA-256) in bytes.
+pub const FPGA_HASH_SZ: usize = 32;
+
+///
+/// Identification of the image loaded into the sequencer FPGA, as returned by
+/// `Sequencer::fpga_image_info`.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct FpgaImageInfo {
+    /// SHA-256 hash of the image, verified as the image was loaded -- or all
+    /// zeros if the image was not loaded (and verified) by this boot
+    pub hash: [u8; FPGA_HASH_SZ],
+    /// Version, as read back from the loaded design
+    pub version: u32,
+    /// Short commit hash of the design, as read back from the loaded design
+    pub sha: u32,
+    /// `FpgaImageSource` from which the image was loaded
+    pub source: u32,
+}
+
+impl FpgaImageInfo {
+    pub fn source(&self) -> Option<FpgaImageSource> {
+        FpgaImageSource::from_u32(self.source)
+    }
+}
+
+/// Where the image loaded into the sequencer FPGA came from
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq)]
+pub enum FpgaImageSource {
+    /// The image embedded in the SP image
+    Embedded = 1,
+    /// A signed image in host flash
+    HostFlash = 2,
+    /// The image that was already loaded, which we left in place; we don't
+    /// know where it came from
+    Retained = 3,
 }
 
-#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, AsBytes)]
+#[derive(
+    Copy, Clone, Debug, FromPrimitive, ToPrimitive, PartialEq, AsBytes,
+)]
 #[repr(u8)]
 pub enum PowerState {
     A2 = 1,
@@ -3,6 +3,7 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use serde::Deserialize;
+use sha2::{Digest, Sha256};
 use std::fmt::Write;
 use std::{env, fs, path::PathBuf};
 
@@ -10,6 +11,20 @@ use std::{env, fs, path::PathBuf};
 struct Config {
     fpga_image: String,
     register_defs: String,
+    clock_config: String,
+    fpga_update: Option<FpgaUpdate>,
+}
+
+///
+/// Configuration for loading a signed FPGA image from host flash: the
+/// partition's offset and size, and a file containing the SEC1-encoded
+/// public key with which images must be signed.
+///
+#[derive(serde::Deserialize)]
+struct FpgaUpdate {
+    offset: u32,
+    size: u32,
+    key: String,
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -36,6 +51,39 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         compressed_path.display()
     );
 
+    //
+    // We hash the uncompressed image, as that is what the FPGA sees.
+    //
+    let hash_path = out.join(fpga_image_path.with_extension("sha256"));
+    fs::write(&hash_path, Sha256::digest(&fpga_image))?;
+
+    println!(
+        "cargo:rustc-env=GIMLET_FPGA_IMAGE_HASH_PATH={}",
+        hash_path.display()
+    );
+
+    //
+    // Loading an image from host flash (and the P-256 code to check its
+    // signature) is only built in with the `fpga-update` feature, which
+    // needs somewhere to load the image from.
+    //
+    match (
+        env::var_os("CARGO_FEATURE_FPGA_UPDATE").is_some(),
+        &config.fpga_update,
+    ) {
+        (true, Some(update)) => {
+            let update_out = out.join("fpga_update.rs");
+            fs::write(&update_out, fpga_update(update)?)?;
+        }
+        (true, None) => {
+            panic!("fpga-update feature requires fpga_update config");
+        }
+        (false, Some(_)) => {
+            panic!("fpga_update config requires fpga-update feature");
+        }
+        (false, None) => {}
+    }
+
     let disposition = build_i2c::Disposition::Devices;
 
     if let Err(e) = build_i2c::codegen(disposition) {
@@ -43,6 +91,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
+    build_idt8a3xxxx::codegen(&config.clock_config)?;
+
     let regs_in = PathBuf::from(config.register_defs);
     let regs_out = out.join(regs_in.with_extension("rs"));
     fs::write(&regs_out, regs(regs_in)?)?;
@@ -180,6 +230,43 @@ pub mod Reg {{
     Ok(output)
 }
 
+fn fpga_update(
+    update: &FpgaUpdate,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut output = String::new();
+
+    //
+    // This must be at least the size of the image header (112 bytes), and
+    // should have room for an image besides.
+    //
+    if update.size <= 112 {
+        panic!("fpga_update partition is too small");
+    }
+
+    println!("cargo:rerun-if-changed={}", update.key);
+    let key = fs::read(&update.key)?;
+
+    //
+    // We expect a SEC1-encoded P-256 key, compressed or uncompressed.
+    //
+    match (key.len(), key.first()) {
+        (33, Some(0x02)) | (33, Some(0x03)) | (65, Some(0x04)) => {}
+        _ => panic!("{} is not a SEC1-encoded P-256 key", update.key),
+    }
+
+    writeln!(
+        &mut output,
+        r##"pub const FPGA_UPDATE: FpgaUpdate = FpgaUpdate {{
+    offset: {:#x},
+    size: {:#x},
+    key: &{:?},
+}};"##,
+        update.offset, update.size, key
+    )?;
+
+    Ok(output)
+}
+
 fn compress(input: &[u8]) -> Vec<u8> {
     let mut output = vec![];
     gnarle::compress(input, |chunk| {
@@ -0,0 +1,215 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Sequencer FPGA images.
+//!
+//! By default, the sequencer FPGA is loaded with the image embedded in our
+//! own image at build time.  If we are built with the `fpga-update` feature
+//! (which requires `fpga_update` to be set in our task configuration), we
+//! first look for a signed image in a partition of host
+//! flash, allowing the FPGA to be updated in the field without updating the
+//! SP.  If there is no valid image in the partition (or if loading it fails),
+//! we fall back to the embedded image.
+//!
+//! An image in host flash consists of an `FpgaImageHeader` followed by the
+//! `gnarle`-compressed bitstream.  The header contains the SHA-256 hash of
+//! the uncompressed bitstream -- which is checked as the bitstream is loaded
+//! -- and an ECDSA P-256 signature over the fields that precede it, made with
+//! the private half of the key named by `fpga_update.key`.
+//!
+
+use drv_gimlet_hf_api as hf_api;
+use drv_gimlet_seq_api::FpgaImageSource;
+use drv_ice40_spi_program as ice40;
+#[cfg(feature = "fpga-update")]
+use p256::ecdsa::signature::{Signature as _, Verifier};
+#[cfg(feature = "fpga-update")]
+use p256::ecdsa::{Signature, VerifyingKey};
+#[cfg(feature = "fpga-update")]
+use zerocopy::{AsBytes, FromBytes};
+
+/// Magic number at the start of an image in host flash.
+#[cfg(feature = "fpga-update")]
+pub const FPGA_IMAGE_MAGIC: u32 = 0x1de_f9a0;
+
+/// Version of the image header format.
+#[cfg(feature = "fpga-update")]
+pub const FPGA_IMAGE_VERSION: u32 = 1;
+
+/// Size of an ECDSA P-256 signature (r followed by s) in bytes.
+#[cfg(feature = "fpga-update")]
+pub const SIGNATURE_SZ: usize = 64;
+
+///
+/// The header of an image in host flash.  All fields are little-endian.
+///
+#[cfg(feature = "fpga-update")]
+#[derive(Copy, Clone, Default, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct FpgaImageHeader {
+    pub magic: u32,
+    pub version: u32,
+    /// length of the compressed bitstream that follows the header
+    pub len: u32,
+    pub reserved: u32,
+    /// SHA-256 hash of the uncompressed bitstream
+    pub hash: ice40::BitstreamHash,
+    /// signature over all of the above fields
+    pub signature: [u8; SIGNATURE_SZ],
+}
+
+///
+/// The location of the partition in host flash, and the key with which
+/// images therein must be signed.
+///
+#[cfg(feature = "fpga-update")]
+#[derive(Copy, Clone)]
+pub struct FpgaUpdate {
+    /// offset of the partition in host flash
+    pub offset: u32,
+    /// size of the partition
+    pub size: u32,
+    /// SEC1-encoded public key
+    pub key: &'static [u8],
+}
+
+#[cfg(feature = "fpga-update")]
+include!(concat!(env!("OUT_DIR"), "/fpga_update.rs"));
+
+static COMPRESSED_BITSTREAM: &[u8] =
+    include_bytes!(env!("GIMLET_FPGA_IMAGE_PATH"));
+
+///
+/// SHA-256 hash of the uncompressed embedded bitstream, computed at build
+/// time.
+///
+const BITSTREAM_HASH: ice40::BitstreamHash =
+    *include_bytes!(env!("GIMLET_FPGA_IMAGE_HASH_PATH"));
+
+/// Reasons that we rejected the image in host flash.
+#[cfg(feature = "fpga-update")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ImageError {
+    HostFlash(hf_api::HfError),
+    BadMagic(u32),
+    BadVersion(u32),
+    BadLength(u32),
+    BadKey,
+    BadSignature,
+}
+
+#[cfg(feature = "fpga-update")]
+impl From<hf_api::HfError> for ImageError {
+    fn from(err: hf_api::HfError) -> Self {
+        Self::HostFlash(err)
+    }
+}
+
+pub enum Image {
+    Embedded,
+    #[cfg(feature = "fpga-update")]
+    HostFlash {
+        offset: u32,
+        header: FpgaImageHeader,
+    },
+}
+
+impl Image {
+    ///
+    /// Reads and validates the header of the image in host flash, returning
+    /// the image if it is correctly signed.  This doesn't check the bitstream
+    /// itself; that is done as it is loaded.
+    ///
+    #[cfg(feature = "fpga-update")]
+    pub fn from_host_flash(
+        hf: &hf_api::HostFlash,
+        update: &FpgaUpdate,
+    ) -> Result<Self, ImageError> {
+        let mut header = FpgaImageHeader::default();
+        hf.read(update.offset, header.as_bytes_mut())?;
+
+        if header.magic != FPGA_IMAGE_MAGIC {
+            return Err(ImageError::BadMagic(header.magic));
+        }
+
+        if header.version != FPGA_IMAGE_VERSION {
+            return Err(ImageError::BadVersion(header.version));
+        }
+
+        let max = update.size as usize - core::mem::size_of_val(&header);
+
+        if header.len == 0 || header.len as usize > max {
+            return Err(ImageError::BadLength(header.len));
+        }
+
+        let key = VerifyingKey::from_sec1_bytes(update.key)
+            .map_err(|_| ImageError::BadKey)?;
+
+        let signature = Signature::from_bytes(&header.signature)
+            .map_err(|_| ImageError::BadSignature)?;
+
+        let signed = header.as_bytes().len() - SIGNATURE_SZ;
+
+        key.verify(&header.as_bytes()[..signed], &signature)
+            .map_err(|_| ImageError::BadSignature)?;
+
+        Ok(Self::HostFlash {
+            offset: update.offset,
+            header,
+        })
+    }
+
+    pub fn source(&self) -> FpgaImageSource {
+        match self {
+            Self::Embedded => FpgaImageSource::Embedded,
+            #[cfg(feature = "fpga-update")]
+            Self::HostFlash { .. } => FpgaImageSource::HostFlash,
+        }
+    }
+
+    /// Returns the hash of the uncompressed bitstream.
+    pub fn hash(&self) -> &ice40::BitstreamHash {
+        match self {
+            Self::Embedded => &BITSTREAM_HASH,
+            #[cfg(feature = "fpga-update")]
+            Self::HostFlash { header, .. } => &header.hash,
+        }
+    }
+
+    /// Returns the length of the compressed bitstream.
+    pub fn compressed_len(&self) -> usize {
+        match self {
+            Self::Embedded => COMPRESSED_BITSTREAM.len(),
+            #[cfg(feature = "fpga-update")]
+            Self::HostFlash { header, .. } => header.len as usize,
+        }
+    }
+
+    ///
+    /// Reads the compressed bitstream starting at `offset`, returning as much
+    /// of it as is convenient -- which may be all of it, or may be no more
+    /// than fits in `buf`.
+    ///
+    #[cfg_attr(not(feature = "fpga-update"), allow(unused_variables))]
+    pub fn read<'a>(
+        &self,
+        hf: &hf_api::HostFlash,
+        offset: usize,
+        buf: &'a mut [u8],
+    ) -> Result<&'a [u8], hf_api::HfError> {
+        match self {
+            Self::Embedded => Ok(&COMPRESSED_BITSTREAM[offset..]),
+            #[cfg(feature = "fpga-update")]
+            Self::HostFlash {
+                offset: base,
+                header,
+            } => {
+                let start = core::mem::size_of_val(header) + offset;
+                let len = usize::min(header.len as usize - offset, buf.len());
+                hf.read(*base + start as u32, &mut buf[..len])?;
+                Ok(&buf[..len])
+            }
+        }
+    }
+}
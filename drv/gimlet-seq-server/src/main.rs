@@ -3,21 +3,35 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Server for managing the Gimlet sequencing process.
+//!
+//! Transitions between power states are performed as a series of steps,
+//! each of which can fail -- and any step that waits on the sequencer FPGA
+//! has a deadline.  If a transition fails, we roll back to A2.  Each
+//! transition (and its outcome) is recorded in the `TRANSITIONS` ring
+//! buffer, which is kept separate from our tracing to assure that it isn't
+//! lost amid the noise of polling.
 
 #![no_std]
 #![no_main]
 
+mod fpga_image;
 mod seq_spi;
 
+use core::mem::MaybeUninit;
 use ringbuf::*;
 use userlib::*;
 
 use drv_gimlet_hf_api as hf_api;
-use drv_gimlet_seq_api::{PowerState, SeqError};
+use drv_gimlet_seq_api::{
+    FpgaImageInfo, FpgaImageSource, PowerReason, PowerState, PowerTransition,
+    SeqError, ThermalTrip, FPGA_HASH_SZ,
+};
+use drv_i2c_api::ResponseCode;
 use drv_ice40_spi_program as ice40;
 use drv_spi_api as spi_api;
 use drv_stm32xx_sys_api as sys_api;
 use idol_runtime::RequestError;
+use power_history::History;
 use seq_spi::{Addr, Reg};
 
 task_slot!(SYS, sys);
@@ -34,11 +48,15 @@ enum Trace {
     Ice40Rails(bool, bool),
     Reprogram(bool),
     Programmed,
-    Programming,
+    Programming(FpgaImageSource),
+    ProgramFailed(ProgramError),
+    #[cfg(feature = "fpga-update")]
+    ImageRejected(fpga_image::ImageError),
     Ice40PowerGoodV1P2(bool),
     Ice40PowerGoodV3P3(bool),
     RailsOff,
     Ident(u16),
+    Image(u32, u32),
     A1Status(u8),
     A2,
     A1Power(u8, u8),
@@ -54,6 +72,65 @@ enum Trace {
 
 ringbuf!(Trace, 64, Trace::None);
 
+///
+/// The steps of a power state transition.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    MuxToHostCPU,
+    A1A0Enable,
+    GroupAB,
+    VcoreSocOn,
+    GroupC,
+    A0Disable,
+    VcoreSocOff,
+    MuxToSP,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Transition {
+    Begin {
+        from: PowerState,
+        to: PowerState,
+        now: u64,
+    },
+    Done {
+        state: PowerState,
+        now: u64,
+    },
+    Failed {
+        step: Step,
+        err: SeqError,
+        ifr: u8,
+        now: u64,
+    },
+    RolledBack {
+        now: u64,
+    },
+    None,
+}
+
+ringbuf!(TRANSITIONS, Transition, 16, Transition::None);
+
+///
+/// The history of power state transitions, which is left alone when this
+/// task starts, so that it survives our restarts (and those of the SP).
+///
+#[link_section = ".uninit.power_history"]
+static mut HISTORY: MaybeUninit<History> = MaybeUninit::uninit();
+
+///
+/// Time (in milliseconds) that we allow for A1 and A0 groups A and B to come
+/// up once enabled.
+///
+const GROUP_AB_TIMEOUT: u64 = 1000;
+
+///
+/// Time (in milliseconds) that we allow for A0 group C to come up once
+/// VDD_VCORE and VDDCR_SOC are enabled.
+///
+const GROUP_C_TIMEOUT: u64 = 1000;
+
 #[export_name = "main"]
 fn main() -> ! {
     let spi = spi_api::Spi::from(SPI.get_task_id());
@@ -214,8 +291,16 @@ fn main() -> ! {
 
     ringbuf_entry!(Trace::Reprogram(reprogram));
 
+    let hf = hf_api::HostFlash::from(HF.get_task_id());
+
+    // The image that we loaded (and whose hash we checked as we did so), if
+    // we programmed the FPGA.
+    let mut loaded = None;
+
     // We only want to reset and reprogram the FPGA when absolutely required.
     if reprogram {
+        let mut image = fpga_image::Image::Embedded;
+
         if let Some(pin) = GLOBAL_RESET {
             // Assert the design reset signal (not the same as the FPGA
             // programming logic reset signal). We do this during reprogramming
@@ -223,20 +308,35 @@ fn main() -> ! {
             sys.gpio_reset(pin).unwrap();
         }
 
+        // If we have been configured to look for an image in host flash and
+        // there is a valid one there, we prefer it to our embedded image.
+        #[cfg(feature = "fpga-update")]
+        match fpga_image::Image::from_host_flash(&hf, &fpga_image::FPGA_UPDATE)
+        {
+            Ok(flash) => image = flash,
+            Err(e) => ringbuf_entry!(Trace::ImageRejected(e)),
+        }
+
         // Reprogramming will continue until morale improves -- to a point.
         loop {
             let prog = spi.device(ICE40_SPI_DEVICE);
-            ringbuf_entry!(Trace::Programming);
-            match reprogram_fpga(&prog, &sys, &ICE40_CONFIG) {
+            ringbuf_entry!(Trace::Programming(image.source()));
+            match reprogram_fpga(&prog, &sys, &ICE40_CONFIG, &hf, &image) {
                 Ok(()) => {
                     // yay
                     break;
                 }
-                Err(_) => {
+                Err(e) => {
+                    ringbuf_entry!(Trace::ProgramFailed(e));
+
                     // Try and put state back to something reasonable.  We
                     // don't know if we're still locked, so ignore the
                     // complaint if we're not.
                     let _ = prog.release();
+
+                    // If the image from host flash didn't load, we fall back
+                    // to the embedded image rather than retrying it.
+                    image = fpga_image::Image::Embedded;
                 }
             }
         }
@@ -246,16 +346,33 @@ fn main() -> ! {
             // active low.
             sys.gpio_set(pin).unwrap();
         }
+
+        loaded = Some(image);
     }
 
     ringbuf_entry!(Trace::Programmed);
 
-    vcore_soc_off();
+    vcore_soc_off().unwrap();
     ringbuf_entry!(Trace::RailsOff);
 
     let ident = seq.read_ident().unwrap();
     ringbuf_entry!(Trace::Ident(ident));
 
+    //
+    // We can only vouch for the hash of an image that we loaded ourselves;
+    // if we left the FPGA as we found it, we report an all-zero hash.
+    //
+    let image = FpgaImageInfo {
+        hash: loaded.as_ref().map_or([0; FPGA_HASH_SZ], |i| *i.hash()),
+        version: seq.read_version().unwrap(),
+        sha: seq.read_sha().unwrap(),
+        source: loaded
+            .as_ref()
+            .map_or(FpgaImageSource::Retained, |i| i.source())
+            as u32,
+    };
+    ringbuf_entry!(Trace::Image(image.version, image.sha));
+
     loop {
         let mut status = [0u8];
 
@@ -296,6 +413,8 @@ fn main() -> ! {
     let mut server = ServerImpl {
         state: PowerState::A2,
         seq,
+        history: History::resume(unsafe { &mut HISTORY }),
+        image,
     };
 
     loop {
@@ -306,104 +425,300 @@ fn main() -> ! {
 struct ServerImpl {
     state: PowerState,
     seq: seq_spi::SequencerFpga,
+    history: &'static mut History,
+    image: FpgaImageInfo,
 }
 
-impl idl::InOrderSequencerImpl for ServerImpl {
-    fn get_state(
-        &mut self,
-        rm: &RecvMessage,
-    ) -> Result<PowerState, RequestError<SeqError>> {
-        ringbuf_entry!(Trace::GetState(rm.sender));
-        Ok(self.state)
+impl ServerImpl {
+    ///
+    /// Polls the A1 and A0 state machine status registers until `done`
+    /// returns true, failing with `err` if that doesn't happen within
+    /// `timeout` milliseconds.
+    ///
+    fn wait_for(
+        &self,
+        timeout: u64,
+        err: SeqError,
+        done: impl Fn(&[u8; 2]) -> bool,
+    ) -> Result<(), SeqError> {
+        let deadline = sys_get_timer().now + timeout;
+
+        loop {
+            let mut power = [0u8, 0u8];
+
+            self.seq
+                .read_bytes(Addr::A1SMSTATUS, &mut power)
+                .map_err(|_| SeqError::SequencerFpgaFailed)?;
+
+            if done(&power) {
+                return Ok(());
+            }
+
+            if sys_get_timer().now >= deadline {
+                return Err(err);
+            }
+
+            hl::sleep_for(1);
+        }
     }
 
-    fn set_state(
+    fn power_on(&mut self) -> Result<(), (Step, SeqError)> {
+        //
+        // First, set our mux state to be the HostCPU
+        //
+        let hf = hf_api::HostFlash::from(HF.get_task_id());
+
+        hf.set_mux(hf_api::HfMuxState::HostCPU)
+            .map_err(|_| (Step::MuxToHostCPU, SeqError::MuxToHostCPUFailed))?;
+
+        //
+        // We are going to pass through A1 on the way to A0.
+        //
+        let a1a0 = Reg::PWRCTRL::A1PWREN | Reg::PWRCTRL::A0A_EN;
+        self.seq
+            .write_bytes(Addr::PWRCTRL, &[a1a0])
+            .map_err(|_| (Step::A1A0Enable, SeqError::SequencerFpgaFailed))?;
+
+        self.wait_for(GROUP_AB_TIMEOUT, SeqError::GroupABTimeout, |power| {
+            ringbuf_entry!(Trace::A1Power(power[0], power[1]));
+            power[1] == 0x7
+        })
+        .map_err(|err| (Step::GroupAB, err))?;
+
+        //
+        // And power up!
+        //
+        vcore_soc_on()
+            .map_err(|_| (Step::VcoreSocOn, SeqError::VcoreSocFailed))?;
+        ringbuf_entry!(Trace::RailsOn);
+
+        //
+        // Now wait for the end of Group C.
+        //
+        self.wait_for(GROUP_C_TIMEOUT, SeqError::GroupCTimeout, |power| {
+            ringbuf_entry!(Trace::A0Power(power[1]));
+            power[1] == 0xc
+        })
+        .map_err(|err| (Step::GroupC, err))?;
+
+        //
+        // Finally, enable transmission to the SP3's UART
+        //
+        uart_sp_to_sp3_enable();
+        ringbuf_entry!(Trace::UartEnabled);
+
+        Ok(())
+    }
+
+    ///
+    /// Powers off to A2.  Because this is also used to roll back a failed
+    /// transition, we attempt every step even if an earlier one fails,
+    /// returning the first failure.
+    ///
+    fn power_off(&mut self) -> Result<(), (Step, SeqError)> {
+        let mut rval = Ok(());
+
+        let mut fail = |step, err| {
+            if rval.is_ok() {
+                rval = Err((step, err));
+            }
+        };
+
+        let a1a0 = Reg::PWRCTRL::A0C_DIS;
+
+        if self.seq.write_bytes(Addr::PWRCTRL, &[a1a0]).is_err() {
+            fail(Step::A0Disable, SeqError::SequencerFpgaFailed);
+        }
+
+        if vcore_soc_off().is_err() {
+            fail(Step::VcoreSocOff, SeqError::VcoreSocFailed);
+        }
+
+        let hf = hf_api::HostFlash::from(HF.get_task_id());
+
+        if hf.set_mux(hf_api::HfMuxState::SP).is_err() {
+            fail(Step::MuxToSP, SeqError::MuxToSPFailed);
+        }
+
+        if rval.is_ok() {
+            ringbuf_entry!(Trace::A2);
+        }
+
+        rval
+    }
+
+    ///
+    /// Performs a transition to `state` on behalf of `task`, recording it
+    /// (and any rollback) in our history.
+    ///
+    fn transition(
         &mut self,
-        _: &RecvMessage,
+        task: TaskId,
         state: PowerState,
-    ) -> Result<(), RequestError<SeqError>> {
+        reason: PowerReason,
+    ) -> Result<(), SeqError> {
         ringbuf_entry!(Trace::SetState(self.state, state));
 
-        match (self.state, state) {
-            (PowerState::A2, PowerState::A0) => {
-                //
-                // First, set our mux state to be the HostCPU
-                //
-                let hf = hf_api::HostFlash::from(HF.get_task_id());
-
-                if let Err(_) = hf.set_mux(hf_api::HfMuxState::HostCPU) {
-                    return Err(SeqError::MuxToHostCPUFailed.into());
-                }
+        let power_on = match (self.state, state) {
+            (PowerState::A2, PowerState::A0) => true,
+            (PowerState::A0, PowerState::A2) => false,
+            _ => {
+                let err = SeqError::IllegalTransition;
+                self.history
+                    .record(task, self.state, state, reason, Err(err));
+                return Err(err);
+            }
+        };
 
-                //
-                // We are going to pass through A1 on the way to A0.
-                //
-                let a1a0 = Reg::PWRCTRL::A1PWREN | Reg::PWRCTRL::A0A_EN;
-                self.seq.write_bytes(Addr::PWRCTRL, &[a1a0]).unwrap();
+        ringbuf_entry!(
+            TRANSITIONS,
+            Transition::Begin {
+                from: self.state,
+                to: state,
+                now: sys_get_timer().now,
+            }
+        );
 
-                loop {
-                    let mut power = [0u8, 0u8];
+        let rval = if power_on {
+            self.power_on()
+        } else {
+            self.power_off()
+        };
 
-                    self.seq.read_bytes(Addr::A1SMSTATUS, &mut power).unwrap();
-                    ringbuf_entry!(Trace::A1Power(power[0], power[1]));
+        match rval {
+            Ok(()) => {
+                self.history.record(task, self.state, state, reason, Ok(()));
+                self.state = state;
 
-                    if power[1] == 0x7 {
-                        break;
+                ringbuf_entry!(
+                    TRANSITIONS,
+                    Transition::Done {
+                        state,
+                        now: sys_get_timer().now,
                     }
+                );
 
-                    hl::sleep_for(1);
-                }
+                Ok(())
+            }
 
-                //
-                // And power up!
-                //
-                vcore_soc_on();
-                ringbuf_entry!(Trace::RailsOn);
+            Err((step, err)) => {
+                let mut ifr = [0u8];
+                let _ = self.seq.read_bytes(Addr::IFR, &mut ifr);
+
+                ringbuf_entry!(
+                    TRANSITIONS,
+                    Transition::Failed {
+                        step,
+                        err,
+                        ifr: ifr[0],
+                        now: sys_get_timer().now,
+                    }
+                );
 
                 //
-                // Now wait for the end of Group C.
+                // Whatever state we were in and wherever we failed, we now
+                // make every effort to get to A2.  (If we failed powering
+                // off, this will retry each of its steps.)
                 //
-                loop {
-                    let mut power = [0u8];
+                let from = self.state;
+                self.history.record(task, from, state, reason, Err(err));
 
-                    self.seq.read_bytes(Addr::A0SMSTATUS, &mut power).unwrap();
-                    ringbuf_entry!(Trace::A0Power(power[0]));
+                let rollback = self.power_off().map_err(|(_, err)| err);
+                self.state = PowerState::A2;
 
-                    if power[0] == 0xc {
-                        break;
+                self.history.record(
+                    task,
+                    from,
+                    PowerState::A2,
+                    PowerReason::FaultRollback,
+                    rollback,
+                );
+
+                ringbuf_entry!(
+                    TRANSITIONS,
+                    Transition::RolledBack {
+                        now: sys_get_timer().now,
                     }
+                );
+                ringbuf_entry!(Trace::A2);
 
-                    hl::sleep_for(1);
-                }
+                Err(err)
+            }
+        }
+    }
+}
 
-                //
-                // Finally, enable transmission to the SP3's UART
-                //
-                uart_sp_to_sp3_enable();
-                ringbuf_entry!(Trace::UartEnabled);
+impl idl::InOrderSequencerImpl for ServerImpl {
+    fn get_state(
+        &mut self,
+        rm: &RecvMessage,
+    ) -> Result<PowerState, RequestError<SeqError>> {
+        ringbuf_entry!(Trace::GetState(rm.sender));
+        Ok(self.state)
+    }
 
-                self.state = PowerState::A0;
-                Ok(())
-            }
+    fn set_state(
+        &mut self,
+        rm: &RecvMessage,
+        state: PowerState,
+    ) -> Result<(), RequestError<SeqError>> {
+        self.transition(rm.sender, state, PowerReason::Requested)?;
+        Ok(())
+    }
 
-            (PowerState::A0, PowerState::A2) => {
-                let hf = hf_api::HostFlash::from(HF.get_task_id());
-                let a1a0 = Reg::PWRCTRL::A0C_DIS;
+    fn set_state_with_reason(
+        &mut self,
+        rm: &RecvMessage,
+        state: PowerState,
+        reason: PowerReason,
+    ) -> Result<(), RequestError<SeqError>> {
+        self.transition(rm.sender, state, reason)?;
+        Ok(())
+    }
 
-                self.seq.write_bytes(Addr::PWRCTRL, &[a1a0]).unwrap();
-                vcore_soc_off();
+    fn transition_history(
+        &mut self,
+        _: &RecvMessage,
+        index: u8,
+    ) -> Result<PowerTransition, RequestError<SeqError>> {
+        Ok(self.history.get(index).ok_or(SeqError::BadHistoryIndex)?)
+    }
 
-                if let Err(_) = hf.set_mux(hf_api::HfMuxState::SP) {
-                    return Err(SeqError::MuxToSPFailed.into());
-                }
+    ///
+    /// Drops to A2 (if we aren't already there) because a temperature sensor
+    /// has tripped, recording the trip -- and what we did about it -- in our
+    /// history, so that it survives us and the SP being restarted.
+    ///
+    fn thermal_trip(
+        &mut self,
+        rm: &RecvMessage,
+        sensor: u32,
+        reading: f32,
+        critical: f32,
+    ) -> Result<(), RequestError<SeqError>> {
+        let state = self.state;
 
-                self.state = PowerState::A2;
-                ringbuf_entry!(Trace::A2);
-                Ok(())
-            }
+        let rval = if state == PowerState::A2 {
+            Ok(())
+        } else {
+            self.transition(rm.sender, PowerState::A2, PowerReason::ThermalTrip)
+        };
 
-            _ => Err(RequestError::Runtime(SeqError::IllegalTransition)),
-        }
+        self.history
+            .record_trip(sensor, reading, critical, state, rval);
+        rval?;
+        Ok(())
+    }
+
+    fn thermal_trip_history(
+        &mut self,
+        _: &RecvMessage,
+        index: u8,
+    ) -> Result<ThermalTrip, RequestError<SeqError>> {
+        Ok(self
+            .history
+            .get_trip(index)
+            .ok_or(SeqError::BadHistoryIndex)?)
     }
 
     fn fans_on(
@@ -411,7 +726,9 @@ impl idl::InOrderSequencerImpl for ServerImpl {
         _: &RecvMessage,
     ) -> Result<(), RequestError<SeqError>> {
         let on = Reg::EARLY_POWER_CTRL::FANPWREN;
-        self.seq.set_bytes(Addr::EARLY_POWER_CTRL, &[on]).unwrap();
+        self.seq
+            .set_bytes(Addr::EARLY_POWER_CTRL, &[on])
+            .map_err(|_| SeqError::SequencerFpgaFailed)?;
         Ok(())
     }
 
@@ -422,10 +739,17 @@ impl idl::InOrderSequencerImpl for ServerImpl {
         let off = Reg::EARLY_POWER_CTRL::FANPWREN;
         self.seq
             .clear_bytes(Addr::EARLY_POWER_CTRL, &[off])
-            .unwrap();
+            .map_err(|_| SeqError::SequencerFpgaFailed)?;
         Ok(())
     }
 
+    fn fpga_image_info(
+        &mut self,
+        _: &RecvMessage,
+    ) -> Result<FpgaImageInfo, RequestError<SeqError>> {
+        Ok(self.image)
+    }
+
     //
     // By the time we are hanging out the shingle, the clock config is loaded.
     //
@@ -437,30 +761,74 @@ impl idl::InOrderSequencerImpl for ServerImpl {
     }
 }
 
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ProgramError {
+    Ice40(ice40::Ice40Error),
+    HostFlash(hf_api::HfError),
+}
+
+impl From<ice40::Ice40Error> for ProgramError {
+    fn from(err: ice40::Ice40Error) -> Self {
+        Self::Ice40(err)
+    }
+}
+
+impl From<spi_api::SpiError> for ProgramError {
+    fn from(err: spi_api::SpiError) -> Self {
+        Self::Ice40(err.into())
+    }
+}
+
+impl From<hf_api::HfError> for ProgramError {
+    fn from(err: hf_api::HfError) -> Self {
+        Self::HostFlash(err)
+    }
+}
+
 fn reprogram_fpga(
     spi: &spi_api::SpiDevice,
     sys: &sys_api::Sys,
     config: &ice40::Config,
-) -> Result<(), ice40::Ice40Error> {
+    hf: &hf_api::HostFlash,
+    image: &fpga_image::Image,
+) -> Result<(), ProgramError> {
     ice40::begin_bitstream_load(&spi, &sys, &config)?;
 
-    // We've got the bitstream in Flash, so we can technically just send it in
-    // one transaction, but we'll want chunking later -- so let's make sure
-    // chunking works.
-    let mut bitstream = COMPRESSED_BITSTREAM;
+    // The embedded image is read all at once, but an image in host flash is
+    // read (and decompressed) a block at a time.
+    let mut block = [0; 256];
+    let mut offset = 0;
+    let mut bitstream: &[u8] = &[];
     let mut decompressor = gnarle::Decompressor::default();
     let mut chunk = [0; 256];
-    while !bitstream.is_empty() || !decompressor.is_idle() {
+    let mut verifier = ice40::Verifier::default();
+
+    loop {
+        if bitstream.is_empty() && offset < image.compressed_len() {
+            bitstream = image.read(hf, offset, &mut block)?;
+            offset += bitstream.len();
+        }
+
+        if bitstream.is_empty() && decompressor.is_idle() {
+            break;
+        }
+
         let out =
             gnarle::decompress(&mut decompressor, &mut bitstream, &mut chunk);
+        verifier.update(out);
         ice40::continue_bitstream_load(&spi, out)?;
     }
 
-    ice40::finish_bitstream_load(&spi, &sys, &config)
-}
+    ice40::finish_verified_bitstream_load(
+        &spi,
+        &sys,
+        &config,
+        verifier,
+        image.hash(),
+    )?;
 
-static COMPRESSED_BITSTREAM: &[u8] =
-    include_bytes!(env!("GIMLET_FPGA_IMAGE_PATH"));
+    Ok(())
+}
 
 cfg_if::cfg_if! {
     if #[cfg(any(target_board = "gimlet-a", target_board = "gimlet-b"))] {
@@ -544,7 +912,12 @@ cfg_if::cfg_if! {
         // Gimlet provides external pullups.
         const PGS_PULL: sys_api::Pull = sys_api::Pull::None;
 
-        fn vcore_soc_off() {
+        ///
+        /// Turns off VDD_VCORE and VDDCR_SOC.  As part of powering off, we
+        /// attempt both even if the first fails, returning the first
+        /// failure.
+        ///
+        fn vcore_soc_off() -> Result<(), ResponseCode> {
             use drv_i2c_devices::raa229618::Raa229618;
             let i2c = I2C.get_task_id();
 
@@ -554,11 +927,14 @@ cfg_if::cfg_if! {
             let (device, rail) = i2c_config::pmbus::vddcr_soc(i2c);
             let mut vddcr_soc = Raa229618::new(&device, rail);
 
-            vdd_vcore.turn_off().unwrap();
-            vddcr_soc.turn_off().unwrap();
+            let vcore = vdd_vcore.turn_off();
+            let soc = vddcr_soc.turn_off();
+            vcore?;
+            soc?;
+            Ok(())
         }
 
-        fn vcore_soc_on() {
+        fn vcore_soc_on() -> Result<(), ResponseCode> {
             use drv_i2c_devices::raa229618::Raa229618;
             let i2c = I2C.get_task_id();
 
@@ -568,8 +944,9 @@ cfg_if::cfg_if! {
             let (device, rail) = i2c_config::pmbus::vddcr_soc(i2c);
             let mut vddcr_soc = Raa229618::new(&device, rail);
 
-            vdd_vcore.turn_on().unwrap();
-            vddcr_soc.turn_on().unwrap();
+            vdd_vcore.turn_on()?;
+            vddcr_soc.turn_on()?;
+            Ok(())
         }
     } else {
         compiler_error!("unsupported target board");
@@ -577,7 +954,10 @@ cfg_if::cfg_if! {
 }
 
 mod idl {
-    use super::{PowerState, SeqError};
+    use super::{
+        FpgaImageInfo, FpgaImageSource, PowerReason, PowerState,
+        PowerTransition, SeqError, ThermalTrip,
+    };
 
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
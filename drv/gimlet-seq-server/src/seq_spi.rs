@@ -39,6 +39,21 @@ impl SequencerFpga {
         Ok(ident)
     }
 
+    /// Reads the VER0:3 registers as a big-endian 32-bit integer.
+    pub fn read_version(&self) -> Result<u32, spi_api::SpiError> {
+        let mut version = [0u8; 4];
+        self.read_bytes(Addr::VER0, &mut version)?;
+        Ok(u32::from_be_bytes(version))
+    }
+
+    /// Reads the SHA0:3 registers (the short commit hash of the design) as a
+    /// big-endian 32-bit integer.
+    pub fn read_sha(&self) -> Result<u32, spi_api::SpiError> {
+        let mut sha = [0u8; 4];
+        self.read_bytes(Addr::SHA0, &mut sha)?;
+        Ok(u32::from_be_bytes(sha))
+    }
+
     /// Check for a valid identifier, deliberately eating any SPI errors.
     pub fn valid_ident(&self) -> bool {
         if let Ok(ident) = self.read_ident() {
@@ -31,6 +31,7 @@ use userlib::*;
 pub enum Op {
     WriteRead = 1,
     WriteReadBlock = 2,
+    GetStats = 3,
 }
 
 /// The response code returned from the I2C controller (or from the kernel in
@@ -184,6 +185,41 @@ pub struct I2cDevice {
 
 type I2cMessage = (u8, Controller, PortIndex, Option<(Mux, Segment)>);
 
+///
+/// Counters of bus events, as kept by the I2C server for each controller,
+/// for each port on a controller and for each mux on a port.  All counters
+/// saturate rather than wrap.
+///
+#[derive(Copy, Clone, Debug, Default, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct I2cStats {
+    /// device NACKed either its address or a register
+    pub nacks: u32,
+    /// misplaced start or stop condition
+    pub bus_errors: u32,
+    /// arbitration lost to another controller
+    pub arbitration_lost: u32,
+    /// controller failed to become idle
+    pub timeouts: u32,
+    /// SCL held low by a target for longer than the SCL timeout
+    pub clock_stretch: u32,
+    /// controller (and any mux on the bus) reset
+    pub resets: u32,
+    /// SDA found held low and bus cleared by clocking SCL
+    pub bus_clears: u32,
+}
+
+///
+/// The scope of a request for [`I2cStats`]:  all of a controller, one of its
+/// ports, or one mux on a port.
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StatsScope {
+    Controller(Controller),
+    Port(Controller, PortIndex),
+    Mux(Controller, PortIndex, Mux),
+}
+
 pub trait Marshal<T> {
     fn marshal(&self) -> T;
     fn unmarshal(val: &T) -> Result<Self, ResponseCode>
@@ -224,6 +260,60 @@ impl Marshal<[u8; 4]> for I2cMessage {
     }
 }
 
+impl Marshal<[u8; 4]> for StatsScope {
+    fn marshal(&self) -> [u8; 4] {
+        match *self {
+            StatsScope::Controller(controller) => [0, controller as u8, 0, 0],
+            StatsScope::Port(controller, port) => {
+                [1, controller as u8, port.0, 0]
+            }
+            StatsScope::Mux(controller, port, mux) => {
+                [2, controller as u8, port.0, mux as u8]
+            }
+        }
+    }
+    fn unmarshal(val: &[u8; 4]) -> Result<Self, ResponseCode> {
+        let controller =
+            Controller::from_u8(val[1]).ok_or(ResponseCode::BadController)?;
+
+        match val[0] {
+            0 => Ok(StatsScope::Controller(controller)),
+            1 => Ok(StatsScope::Port(controller, PortIndex(val[2]))),
+            2 => Ok(StatsScope::Mux(
+                controller,
+                PortIndex(val[2]),
+                Mux::from_u8(val[3]).ok_or(ResponseCode::BadMux)?,
+            )),
+            _ => Err(ResponseCode::BadArg),
+        }
+    }
+}
+
+///
+/// Retrieves the bus statistics kept by the I2C server `task` for the
+/// specified scope.
+///
+pub fn stats(
+    task: TaskId,
+    scope: StatsScope,
+) -> Result<I2cStats, ResponseCode> {
+    let mut stats = I2cStats::default();
+
+    let (code, _) = sys_send(
+        task,
+        Op::GetStats as u16,
+        &Marshal::marshal(&scope),
+        stats.as_bytes_mut(),
+        &[],
+    );
+
+    if code != 0 {
+        Err(ResponseCode::from_u32(code).ok_or(ResponseCode::BadResponse)?)
+    } else {
+        Ok(stats)
+    }
+}
+
 impl core::fmt::Display for I2cDevice {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let addr = self.address;
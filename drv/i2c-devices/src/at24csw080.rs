@@ -10,7 +10,7 @@ use userlib::{hl::sleep_for, FromPrimitive, ToPrimitive};
 use zerocopy::{AsBytes, FromBytes};
 
 /// Number of bytes stored in the EEPROM
-const EEPROM_SIZE: u16 = 1024;
+pub const EEPROM_SIZE: u16 = 1024;
 
 /// Wait time after performing a write
 const WRITE_TIME_MS: u64 = 5;
@@ -123,6 +123,44 @@ impl At24csw080 {
             .map_err(Into::into)
     }
 
+    /// Reads a buffer from the EEPROM at the specified address.
+    ///
+    /// Because the high bits of the address are part of the I2C device
+    /// address, a read that crosses a 256-byte boundary is split into
+    /// multiple I2C transactions.
+    ///
+    /// `addr` and `addr + buf.len()` must be <= `EEPROM_SIZE`; otherwise,
+    /// this function returns an error
+    pub fn read_buffer(
+        &self,
+        mut addr: u16,
+        mut buf: &mut [u8],
+    ) -> Result<(), Error> {
+        // Address validation
+        if addr >= EEPROM_SIZE {
+            return Err(Error::InvalidAddress(addr));
+        }
+        let end_addr = addr
+            .checked_add(
+                buf.len()
+                    .try_into()
+                    .map_err(|_| Error::InvalidObjectSize(buf.len()))?,
+            )
+            .unwrap_or(u16::MAX);
+        if end_addr > EEPROM_SIZE {
+            return Err(Error::InvalidEndAddress(end_addr));
+        }
+
+        while !buf.is_empty() {
+            let n = usize::min(buf.len(), 256 - (addr & 0xff) as usize);
+            let (chunk, rest) = buf.split_at_mut(n);
+            self.device.eeprom(addr).read_reg_into(addr as u8, chunk)?;
+            buf = rest;
+            addr += n as u16;
+        }
+        Ok(())
+    }
+
     /// Writes a single byte to the EEPROM at the given address
     ///
     /// On success, sleeps for 5 ms (the EEPROM's write cycle time) before
@@ -176,7 +214,11 @@ impl At24csw080 {
     ///
     /// `addr` and `addr + buf.len()` must be < `EEPROM_SIZE`; otherwise, this
     /// function returns an error
-    fn write_buffer(&self, mut addr: u16, mut buf: &[u8]) -> Result<(), Error> {
+    pub fn write_buffer(
+        &self,
+        mut addr: u16,
+        mut buf: &[u8],
+    ) -> Result<(), Error> {
         // Address validation
         if addr >= EEPROM_SIZE {
             return Err(Error::InvalidAddress(addr));
@@ -17,6 +17,7 @@
 //! - [`pct2075`]: PCT2075 temperature sensor
 //! - [`raa229618`]: RAA229618 power controller
 //! - [`sbtsi`]: AMD SB-TSI temperature sensor
+//! - [`spd5118`]: SPD5118 DDR5 SPD hub with temperature sensor
 //! - [`tmp116`]: TMP116 temperature sensor
 //! - [`tmp451`]: TMP451 temperature sensor
 //! - [`tps546b24a`]: TPS546B24A buck converter
@@ -164,6 +165,7 @@ pub mod mcp9808;
 pub mod pct2075;
 pub mod raa229618;
 pub mod sbtsi;
+pub mod spd5118;
 pub mod tmp117;
 pub mod tmp451;
 pub mod tps546b24a;
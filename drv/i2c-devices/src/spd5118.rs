@@ -0,0 +1,196 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Driver for the SPD5118 SPD hub with temperature sensor, as found on DDR5
+//! DIMMs.
+//!
+//! The hub is accessed in its I2C legacy mode with 1-byte addressing: the
+//! high bit of the address byte selects between the hub's registers (MR0
+//! through MR127) and its 1 KB of non-volatile memory, which is accessed as
+//! eight 128-byte pages; the page is selected by the low three bits of MR11.
+
+use crate::TempSensor;
+use drv_i2c_api::*;
+use userlib::units::*;
+
+#[allow(dead_code)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Register {
+    DeviceTypeMsb = 0,
+    DeviceTypeLsb = 1,
+    DeviceRevision = 2,
+    VendorId0 = 3,
+    VendorId1 = 4,
+    DeviceCapability = 5,
+    LegacyModeConfiguration = 11,
+    DeviceConfiguration = 18,
+    TsConfiguration = 26,
+    TsTemperatureLow = 49,
+    TsTemperatureHigh = 50,
+    DeviceStatus = 52,
+}
+
+/// Size of the hub's non-volatile memory
+pub const NVM_SIZE: usize = 1024;
+
+/// Size of each page of non-volatile memory
+pub const PAGE_SIZE: usize = 128;
+
+/// Number of registers
+pub const NUM_REGISTERS: usize = 128;
+
+/// Device type, as found in MR0 and MR1
+pub const DEVICE_TYPE: [u8; 2] = [0x51, 0x18];
+
+/// Bit in the address byte that selects memory rather than registers
+pub const MEMORY: u8 = 1 << 7;
+
+/// Mask of the page bits in MR11
+pub const PAGE_MASK: u8 = 0b111;
+
+#[derive(Debug)]
+pub enum Error {
+    BadRegisterRead { reg: Register, code: ResponseCode },
+    BadRegisterWrite { reg: Register, code: ResponseCode },
+    BadMemoryRead { offset: u16, code: ResponseCode },
+    InvalidOffset(u16),
+}
+
+impl From<Error> for ResponseCode {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::BadRegisterRead { code, .. } => code,
+            Error::BadRegisterWrite { code, .. } => code,
+            Error::BadMemoryRead { code, .. } => code,
+            Error::InvalidOffset(_) => ResponseCode::BadArg,
+        }
+    }
+}
+
+pub struct Spd5118 {
+    device: I2cDevice,
+}
+
+impl core::fmt::Display for Spd5118 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SPD5118: {}", &self.device)
+    }
+}
+
+impl Spd5118 {
+    pub fn new(device: &I2cDevice) -> Self {
+        Self { device: *device }
+    }
+
+    pub fn read_reg(&self, reg: Register) -> Result<u8, Error> {
+        self.device
+            .read_reg::<u8, u8>(reg as u8)
+            .map_err(|code| Error::BadRegisterRead { reg, code })
+    }
+
+    pub fn write_reg(&self, reg: Register, val: u8) -> Result<(), Error> {
+        self.device
+            .write(&[reg as u8, val])
+            .map_err(|code| Error::BadRegisterWrite { reg, code })
+    }
+
+    ///
+    /// Reads registers starting at MR0 into `buf`, which must be no larger
+    /// than `NUM_REGISTERS`.
+    ///
+    pub fn read_registers(&self, buf: &mut [u8]) -> Result<(), Error> {
+        if buf.len() > NUM_REGISTERS {
+            return Err(Error::InvalidOffset(buf.len() as u16));
+        }
+
+        self.device
+            .read_reg_into::<u8>(0, buf)
+            .map(|_| ())
+            .map_err(|code| Error::BadRegisterRead {
+                reg: Register::DeviceTypeMsb,
+                code,
+            })
+    }
+
+    /// Selects the page of non-volatile memory visible at `MEMORY`.
+    pub fn set_page(&self, page: u8) -> Result<(), Error> {
+        let reg = Register::LegacyModeConfiguration;
+        let val = self.read_reg(reg)?;
+        self.write_reg(reg, (val & !PAGE_MASK) | (page & PAGE_MASK))
+    }
+
+    ///
+    /// Reads non-volatile memory starting at `offset` into `buf`, selecting
+    /// pages as needed.  `offset` and `offset + buf.len()` must be no larger
+    /// than `NVM_SIZE`.  This leaves the last page read selected.
+    ///
+    pub fn read_memory(
+        &self,
+        offset: u16,
+        buf: &mut [u8],
+    ) -> Result<(), Error> {
+        let mut offset = offset as usize;
+
+        if offset + buf.len() > NVM_SIZE {
+            return Err(Error::InvalidOffset((offset + buf.len()) as u16));
+        }
+
+        let mut buf = buf;
+
+        while !buf.is_empty() {
+            let page = offset / PAGE_SIZE;
+            let within = offset % PAGE_SIZE;
+            let n = usize::min(buf.len(), PAGE_SIZE - within);
+            let (chunk, rest) = buf.split_at_mut(n);
+
+            self.set_page(page as u8)?;
+
+            self.device
+                .read_reg_into::<u8>(MEMORY | within as u8, chunk)
+                .map_err(|code| Error::BadMemoryRead {
+                    offset: offset as u16,
+                    code,
+                })?;
+
+            offset += n;
+            buf = rest;
+        }
+
+        Ok(())
+    }
+
+    ///
+    /// Reads the raw contents of the temperature sensor's registers (MR49
+    /// and MR50) in a single transaction, that they are coherent.
+    ///
+    pub fn read_temperature_registers(&self) -> Result<[u8; 2], Error> {
+        let reg = Register::TsTemperatureLow;
+        self.device
+            .read_reg::<u8, [u8; 2]>(reg as u8)
+            .map_err(|code| Error::BadRegisterRead { reg, code })
+    }
+}
+
+impl TempSensor<Error> for Spd5118 {
+    fn read_temperature(&mut self) -> Result<Celsius, Error> {
+        let t = self.read_temperature_registers()?;
+
+        // The temperature is a 13-bit two's complement value (of which the
+        // low two bits are always zero) in units of 1/16th of a degree.
+        //
+        // We shift it so that the sign bit is in the right place, cast it
+        // to an i16 to make it signed, then scale it into a float.
+        let t = (u16::from_le_bytes(t) << 3) as i16;
+        Ok(Celsius(f32::from(t) * 0.0078125f32))
+    }
+}
+
+impl crate::Validate<Error> for Spd5118 {
+    fn validate(device: &drv_i2c_api::I2cDevice) -> Result<bool, Error> {
+        let dev = Spd5118::new(device);
+        let msb = dev.read_reg(Register::DeviceTypeMsb)?;
+        let lsb = dev.read_reg(Register::DeviceTypeLsb)?;
+        Ok([msb, lsb] == DEVICE_TYPE)
+    }
+}
@@ -0,0 +1,53 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Client API for the I2C target server
+//!
+//! The I2C target server operates an I2C controller as a target, allowing
+//! tasks to register virtual targets at addresses on the bus.  Each virtual
+//! target presents [`NUM_REGISTERS`] byte-wide registers, accessed in the
+//! manner of an EEPROM:  the first byte of a write by an initiator sets the
+//! register pointer, subsequent bytes are written to successive registers,
+//! and reads return successive registers starting at the pointer.  The
+//! owning task sets the registers that an initiator reads, and is notified
+//! when an initiator writes them.
+
+#![no_std]
+
+use derive_idol_err::IdolError;
+use userlib::*;
+use zerocopy::{AsBytes, FromBytes};
+
+/// Number of registers in each virtual target
+pub const NUM_REGISTERS: usize = 256;
+
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, IdolError)]
+pub enum I2cTargetError {
+    /// Address is reserved or otherwise invalid
+    BadAddress = 1,
+    /// Address already has a virtual target
+    AddressInUse = 2,
+    /// No more virtual targets can be registered
+    TargetsFull = 3,
+    /// Address does not have a virtual target
+    NotRegistered = 4,
+    /// Virtual target is owned by another task
+    NotYours = 5,
+    /// Registers exceed those of the virtual target
+    BadOffset = 6,
+}
+
+///
+/// The registers written by an initiator's most recent write to a virtual
+/// target:  `len` registers (saturating at 255), starting at `offset`.  A
+/// write that consists only of the register pointer has a `len` of 0.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct TargetWrite {
+    pub offset: u8,
+    pub len: u8,
+}
+
+include!(concat!(env!("OUT_DIR"), "/client_stub.rs"));
@@ -20,6 +20,11 @@
 //! 5. Call `finish_bitstream_load` once to complete the process and check the
 //!    result.
 //!
+//! To check the integrity of the bitstream as it is loaded, pass each chunk
+//! to a `Verifier` as well, and call `finish_verified_bitstream_load` with the
+//! expected hash instead of `finish_bitstream_load`.  (The iCE40 offers no
+//! way to read back its configuration, so this is the best we can do.)
+//!
 //! If any of the operations fail, the intention is that you restart the process
 //! from `begin_bitstream_load` -- it should handle the reset and clean up from
 //! the earlier failure. However, this is only _somewhat_ tested.
@@ -28,8 +33,15 @@
 
 use drv_spi_api::{self as spi_api, SpiDevice};
 use drv_stm32xx_sys_api::{self as sys_api, Sys};
+use sha2::{Digest, Sha256};
 use userlib::hl;
 
+/// Size of a bitstream hash (SHA-256) in bytes.
+pub const HASH_SZ: usize = 32;
+
+/// SHA-256 hash of an (uncompressed) bitstream.
+pub type BitstreamHash = [u8; HASH_SZ];
+
 /// Wiring configuration for the iCE40 FPGA.
 pub struct Config {
     /// Pin set where CRESETB goes -- should only have one bit set.
@@ -40,6 +52,7 @@ pub struct Config {
 
 /// Things that we can _notice_ going wrong when programming -- the FPGA doesn't
 /// actually give us a lot of feedback.
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Ice40Error {
     /// We attempted to put the chip into programming mode, but its CDONE pin
     /// did not go low to confirm.
@@ -48,6 +61,9 @@ pub enum Ice40Error {
     /// high. This may be a sign that you're sending a bitstream for a smaller
     /// FPGA.
     ConfigDidNotComplete,
+    /// The bitstream we sent did not have the expected hash; the FPGA has
+    /// been left in reset.
+    BitstreamCorrupt,
     /// Communications over SPI failed (reason attached).
     Spi(spi_api::SpiError),
 }
@@ -187,3 +203,42 @@ pub fn finish_bitstream_load(
 
     Ok(())
 }
+
+/// Computes the hash of a bitstream as it is sent to the FPGA.
+#[derive(Default)]
+pub struct Verifier(Sha256);
+
+impl Verifier {
+    /// Adds a chunk of the bitstream -- as passed to `continue_bitstream_load`
+    /// -- to the hash.
+    pub fn update(&mut self, data: &[u8]) {
+        self.0.update(data);
+    }
+
+    /// Returns the hash of the bitstream thus far.
+    pub fn finalize(self) -> BitstreamHash {
+        self.0.finalize().into()
+    }
+}
+
+/// Like `finish_bitstream_load`, but first checks that the bitstream sent
+/// has the `expected` hash.  If it doesn't, the FPGA is held in reset (lest
+/// it run a corrupt design) and the SPI controller is unlocked.
+pub fn finish_verified_bitstream_load(
+    spi: &SpiDevice,
+    sys: &Sys,
+    config: &Config,
+    verifier: Verifier,
+    expected: &BitstreamHash,
+) -> Result<(), Ice40Error> {
+    if verifier.finalize() != *expected {
+        // Assert reset (active low), and leave it that way.
+        sys.gpio_reset(config.creset).unwrap();
+        spi.lock(spi_api::CsState::NotAsserted)?;
+        spi.release().unwrap();
+
+        return Err(Ice40Error::BitstreamCorrupt);
+    }
+
+    finish_bitstream_load(spi, sys, config)
+}
@@ -10,13 +10,21 @@ use derive_idol_err::IdolError;
 use userlib::*;
 use zerocopy::AsBytes;
 
-#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, IdolError)]
+pub use power_history::{PowerReason, PowerTransition};
+
+#[derive(
+    Copy, Clone, Debug, FromPrimitive, ToPrimitive, PartialEq, IdolError,
+)]
 pub enum SeqError {
     IllegalTransition = 1,
     ClockConfigFailed = 2,
+    /// The requested transition is not in the history
+    BadHistoryIndex = 3,
 }
 
-#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, AsBytes)]
+#[derive(
+    Copy, Clone, Debug, FromPrimitive, ToPrimitive, PartialEq, AsBytes,
+)]
 #[repr(u8)]
 pub enum PowerState {
     A2 = 1,
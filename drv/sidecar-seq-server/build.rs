@@ -2,9 +2,16 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+#[derive(serde::Deserialize)]
+struct Config {
+    clock_config: String,
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     build_util::expose_target_board();
 
+    let config = build_util::task_config::<Config>()?;
+
     let disposition = build_i2c::Disposition::Devices;
 
     if let Err(e) = build_i2c::codegen(disposition) {
@@ -12,6 +19,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         std::process::exit(1);
     }
 
+    build_idt8a3xxxx::codegen(&config.clock_config)?;
+
     idol::server::build_server_support(
         "../../idl/sidecar-seq.idol",
         "server_stub.rs",
@@ -7,12 +7,14 @@
 #![no_std]
 #![no_main]
 
+use core::mem::MaybeUninit;
 use ringbuf::*;
 use userlib::*;
 
 use drv_i2c_api::{I2cDevice, ResponseCode};
-use drv_sidecar_seq_api::{PowerState, SeqError};
+use drv_sidecar_seq_api::{PowerReason, PowerState, PowerTransition, SeqError};
 use idol_runtime::{NotificationHandler, RequestError};
+use power_history::History;
 
 task_slot!(SYS, sys);
 task_slot!(I2C, i2c_driver);
@@ -37,11 +39,19 @@ enum Trace {
 
 ringbuf!(Trace, 64, Trace::None);
 
+///
+/// The history of power state transitions, which is left alone when this
+/// task starts, so that it survives our restarts (and those of the SP).
+///
+#[link_section = ".uninit.power_history"]
+static mut HISTORY: MaybeUninit<History> = MaybeUninit::uninit();
+
 const TIMER_MASK: u32 = 1 << 0;
 const TIMER_INTERVAL: u64 = 1000;
 
 struct ServerImpl {
     state: PowerState,
+    history: &'static mut History,
     clockgen: I2cDevice,
     led: drv_stm32xx_sys_api::PinSet,
     led_on: bool,
@@ -91,6 +101,25 @@ impl ServerImpl {
             self.led_on();
         }
     }
+
+    ///
+    /// Performs a transition to `state` on behalf of `task`, recording it in
+    /// our history.
+    ///
+    fn transition(
+        &mut self,
+        task: TaskId,
+        state: PowerState,
+        reason: PowerReason,
+    ) -> Result<(), SeqError> {
+        ringbuf_entry!(Trace::SetState(self.state, state));
+
+        // No transitions are implemented yet.
+        let rval = Err(SeqError::IllegalTransition);
+
+        self.history.record(task, self.state, state, reason, rval);
+        rval
+    }
 }
 
 impl idl::InOrderSequencerImpl for ServerImpl {
@@ -104,14 +133,29 @@ impl idl::InOrderSequencerImpl for ServerImpl {
 
     fn set_state(
         &mut self,
-        _: &RecvMessage,
+        rm: &RecvMessage,
         state: PowerState,
     ) -> Result<(), RequestError<SeqError>> {
-        ringbuf_entry!(Trace::SetState(self.state, state));
+        self.transition(rm.sender, state, PowerReason::Requested)?;
+        Ok(())
+    }
 
-        match (self.state, state) {
-            _ => Err(RequestError::Runtime(SeqError::IllegalTransition)),
-        }
+    fn set_state_with_reason(
+        &mut self,
+        rm: &RecvMessage,
+        state: PowerState,
+        reason: PowerReason,
+    ) -> Result<(), RequestError<SeqError>> {
+        self.transition(rm.sender, state, reason)?;
+        Ok(())
+    }
+
+    fn transition_history(
+        &mut self,
+        _: &RecvMessage,
+        index: u8,
+    ) -> Result<PowerTransition, RequestError<SeqError>> {
+        Ok(self.history.get(index).ok_or(SeqError::BadHistoryIndex)?)
     }
 
     fn load_clock_config(
@@ -178,6 +222,7 @@ fn main() -> ! {
 
     let mut server = ServerImpl {
         state: PowerState::A2,
+        history: History::resume(unsafe { &mut HISTORY }),
         clockgen: devices::idt8a34001(task)[0],
         led: drv_stm32xx_sys_api::Port::C.pin(3),
         led_on: false,
@@ -194,7 +239,7 @@ fn main() -> ! {
 }
 
 mod idl {
-    use super::{PowerState, SeqError};
+    use super::{PowerReason, PowerState, PowerTransition, SeqError};
 
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
@@ -12,6 +12,7 @@
 
 #![no_std]
 
+use core::cell::Cell;
 use core::convert::TryFrom;
 
 #[cfg(feature = "h743")]
@@ -36,6 +37,54 @@ pub struct Ethernet {
     tx_ring: crate::ring::TxRing,
     /// Control of the RX ring.
     rx_ring: crate::ring::RxRing,
+    /// Running counters, which are reported by `stats`.
+    counters: Counters,
+}
+
+/// Running counters kept by the driver. All of these wrap around.
+#[derive(Default)]
+struct Counters {
+    tx_packets: Cell<u32>,
+    rx_packets: Cell<u32>,
+    tx_ring_full: Cell<u32>,
+    /// Whether the TX ring was full the last time we looked, so that
+    /// `tx_ring_full` counts each time it fills up, rather than each time
+    /// smoltcp polls it while it stays full.
+    tx_ring_was_full: Cell<bool>,
+    /// Accumulated from the DMA missed frame counter, which clears on read.
+    rx_missed: Cell<u32>,
+}
+
+/// Snapshot of driver counters and DMA ring occupancy, as returned by
+/// `Ethernet::stats`. The counters wrap around.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Packets handed to the DMA for transmission
+    pub tx_packets: u32,
+    /// Packets received from the DMA
+    pub rx_packets: u32,
+    /// Number of times that the TX ring filled up: this counts transitions
+    /// into the ring being full, not the polls that find it still full (or
+    /// the transmits that are deferred while it is)
+    pub tx_ring_full: u32,
+    /// Received packets that were dropped from the RX ring (because they had
+    /// errors or, with VLANs, an unknown VLAN tag)
+    pub rx_dropped: u32,
+    /// Received packets that the DMA dropped because the RX ring was full
+    pub rx_missed: u32,
+    /// Number of TX descriptors, and number of those which are waiting to be
+    /// transmitted
+    pub tx_ring_len: u16,
+    pub tx_ring_pending: u16,
+    /// Number of RX descriptors, and number of those which contain received
+    /// packets that have not yet been read
+    pub rx_ring_len: u16,
+    pub rx_ring_ready: u16,
+}
+
+/// Increments a wrapping counter.
+fn bump(c: &Cell<u32>) {
+    c.set(c.get().wrapping_add(1));
 }
 
 /// As the name implies, this spins until a predicate becomes true, in a crappy
@@ -225,13 +274,47 @@ impl Ethernet {
             dma,
             tx_ring,
             rx_ring,
+            counters: Counters::default(),
         }
     }
 
     // This function is identical in the VLAN and non-VLAN cases, so it lives
     // in the main impl block
     pub fn can_send(&self) -> bool {
-        self.tx_ring.is_next_free()
+        let free = self.tx_ring.is_next_free();
+        let was_full = self.counters.tx_ring_was_full.replace(!free);
+        if !free && !was_full {
+            bump(&self.counters.tx_ring_full);
+        }
+        free
+    }
+
+    /// Returns a snapshot of our counters and of the occupancy of the DMA
+    /// rings.
+    pub fn stats(&self) -> Stats {
+        // The missed frame counter clears on read, so we accumulate it. If it
+        // has overflowed, we've lost track of how many were missed, but it's
+        // at least the maximum value of the counter.
+        let mfcr = self.dma.dmacmfcr.read();
+        let missed = if mfcr.mfco().bit() {
+            0x7FF
+        } else {
+            mfcr.mfc().bits() as u32
+        };
+        let c = &self.counters;
+        c.rx_missed.set(c.rx_missed.get().wrapping_add(missed));
+
+        Stats {
+            tx_packets: c.tx_packets.get(),
+            rx_packets: c.rx_packets.get(),
+            tx_ring_full: c.tx_ring_full.get(),
+            rx_dropped: self.rx_ring.dropped(),
+            rx_missed: c.rx_missed.get(),
+            tx_ring_len: self.tx_ring.len() as u16,
+            tx_ring_pending: self.tx_ring.pending() as u16,
+            rx_ring_len: self.rx_ring.len() as u16,
+            rx_ring_ready: self.rx_ring.ready() as u16,
+        }
     }
 
     /// Pokes at the controller interrupt status registers to handle and clear
@@ -389,6 +472,7 @@ impl Ethernet {
     ) -> Option<R> {
         let result = self.tx_ring.try_with_next(len, fillout)?;
         self.tx_notify();
+        bump(&self.counters.tx_packets);
         Some(result)
     }
 
@@ -409,6 +493,7 @@ impl Ethernet {
     pub fn recv<R>(&self, readout: impl FnOnce(&mut [u8]) -> R) -> R {
         let result = self.rx_ring.with_next(readout);
         self.rx_notify();
+        bump(&self.counters.rx_packets);
         result
     }
 }
@@ -426,6 +511,7 @@ impl Ethernet {
     ) -> R {
         let result = self.rx_ring.vlan_with_next(vid, readout);
         self.rx_notify();
+        bump(&self.counters.rx_packets);
         result
     }
 
@@ -459,6 +545,7 @@ impl Ethernet {
     ) -> Option<R> {
         let result = self.tx_ring.vlan_try_with_next(len, vid, fillout)?;
         self.tx_notify();
+        bump(&self.counters.tx_packets);
         Some(result)
     }
 }
@@ -167,6 +167,28 @@ impl TxRing {
     pub fn tail_ptr(&self) -> *const TxDesc {
         self.storage.as_ptr_range().end
     }
+
+    /// Returns the number of descriptors that are currently owned by the
+    /// hardware, i.e. packets that have been queued but not yet transmitted.
+    /// This counts in the same units as `len`.
+    pub fn pending(&self) -> usize {
+        let owned = |tdes: &[AtomicU32; 4]| {
+            (tdes[3].load(Ordering::Relaxed) & (1 << TDES3_OWN_BIT) != 0)
+                as usize
+        };
+        self.storage
+            .iter()
+            .map(|d| {
+                #[cfg(not(feature = "vlan"))]
+                let n = owned(&d.tdes);
+
+                #[cfg(feature = "vlan")]
+                let n = owned(&d.tdes[0]) + owned(&d.tdes[1]);
+
+                n
+            })
+            .sum()
+    }
 }
 
 #[cfg(not(feature = "vlan"))]
@@ -401,6 +423,10 @@ pub struct RxRing {
     /// received packet. This must be in the range `0..storage.len()` at all
     /// times.
     next: Cell<usize>,
+    /// Count of received packets that we have dropped from the ring, either
+    /// because the hardware flagged them as erroneous or (with VLANs) because
+    /// they were not tagged with any of our VLANs.
+    dropped: Cell<u32>,
 }
 
 impl RxRing {
@@ -434,6 +460,7 @@ impl RxRing {
             storage,
             buffers,
             next: Cell::new(0),
+            dropped: Cell::new(0),
         }
     }
 
@@ -457,6 +484,23 @@ impl RxRing {
         self.storage.len()
     }
 
+    /// Returns the number of descriptors that have been released by the
+    /// hardware, i.e. received packets that are waiting for us to read them.
+    pub fn ready(&self) -> usize {
+        self.storage
+            .iter()
+            .filter(|d| {
+                d.rdes[3].load(Ordering::Relaxed) & (1 << RDES3_OWN_BIT) == 0
+            })
+            .count()
+    }
+
+    /// Returns the number of received packets that have been dropped from the
+    /// ring (this wraps around).
+    pub fn dropped(&self) -> u32 {
+        self.dropped.get()
+    }
+
     /// Programs the words in `d` to prepare to receive into `buffer` and sets
     /// `d` accessible to hardware. The final write to make it accessible is
     /// performed with Release ordering to get a barrier.
@@ -507,6 +551,7 @@ impl RxRing {
             } else {
                 self.next.get() + 1
             });
+            self.dropped.set(self.dropped.get().wrapping_add(1));
             any_dropped = true;
         }
     }
@@ -650,6 +695,7 @@ impl RxRing {
             } else {
                 self.next.get() + 1
             });
+            self.dropped.set(self.dropped.get().wrapping_add(1));
             any_dropped = true;
         }
     }
@@ -3,13 +3,19 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! A driver for the STM32H7 I2C interface
+//!
+//! In addition to performing I2C operations, the server keeps counters of bus
+//! events (NACKs, bus errors, arbitration losses, timeouts and clock
+//! stretching violations, along with the resets and bus clears performed in
+//! response to them) for each controller, each port and each mux.  These can
+//! be retrieved via [`drv_i2c_api::stats`].
 
 #![no_std]
 #![no_main]
 
 use drv_i2c_api::*;
 use drv_stm32h7_i2c::*;
-use drv_stm32xx_sys_api::{OutputType, Pull, Speed, Sys};
+use drv_stm32xx_sys_api::{OutputType, PinSet, Pull, Speed, Sys};
 
 use fixedmap::*;
 use ringbuf::*;
@@ -105,15 +111,132 @@ fn configure_mux(
 
 ringbuf!(Option<ResponseCode>, 16, None);
 
+///
+/// Bus statistics, kept for each controller, each port and each mux.  All
+/// of these are populated when we start, allowing a query for a
+/// nonexistent controller, port or mux to be failed.
+///
+struct Stats {
+    controllers: FixedMap<Controller, I2cStats, 8>,
+    ports: FixedMap<(Controller, PortIndex), I2cStats, 16>,
+    muxes: FixedMap<(Controller, PortIndex, Mux), I2cStats, 16>,
+}
+
+impl Stats {
+    fn new(
+        controllers: &[I2cController],
+        pins: &[I2cPin],
+        muxes: &[I2cMux],
+    ) -> Self {
+        let mut stats = Self {
+            controllers: FixedMap::new(),
+            ports: FixedMap::new(),
+            muxes: FixedMap::new(),
+        };
+
+        let zero = I2cStats::default();
+
+        for controller in controllers {
+            stats.controllers.insert(controller.controller, zero);
+        }
+
+        for pin in pins {
+            stats.ports.insert((pin.controller, pin.port), zero);
+        }
+
+        for mux in muxes {
+            stats.muxes.insert((mux.controller, mux.port, mux.id), zero);
+        }
+
+        stats
+    }
+
+    ///
+    /// Applies `func` to the statistics for the specified controller, for
+    /// the specified port on it, and (if specified) for the mux on that port.
+    ///
+    fn update(
+        &mut self,
+        controller: Controller,
+        port: PortIndex,
+        mux: Option<Mux>,
+        func: impl Fn(&mut I2cStats),
+    ) {
+        if let Some(mut stats) = self.controllers.get(controller) {
+            func(&mut stats);
+            self.controllers.insert(controller, stats);
+        }
+
+        if let Some(mut stats) = self.ports.get((controller, port)) {
+            func(&mut stats);
+            self.ports.insert((controller, port), stats);
+        }
+
+        if let Some(mux) = mux {
+            if let Some(mut stats) = self.muxes.get((controller, port, mux)) {
+                func(&mut stats);
+                self.muxes.insert((controller, port, mux), stats);
+            }
+        }
+    }
+
+    fn get(&self, scope: StatsScope) -> Result<I2cStats, ResponseCode> {
+        match scope {
+            StatsScope::Controller(controller) => self
+                .controllers
+                .get(controller)
+                .ok_or(ResponseCode::BadController),
+            StatsScope::Port(controller, port) => self
+                .ports
+                .get((controller, port))
+                .ok_or(ResponseCode::BadPort),
+            StatsScope::Mux(controller, port, mux) => self
+                .muxes
+                .get((controller, port, mux))
+                .ok_or(ResponseCode::MuxNotFound),
+        }
+    }
+}
+
+fn bump(counter: &mut u32) {
+    *counter = counter.saturating_add(1);
+}
+
+///
+/// Records the bus event (if any) indicated by an error code.
+///
+fn record(stats: &mut I2cStats, code: ResponseCode) {
+    match code {
+        ResponseCode::NoDevice
+        | ResponseCode::NoRegister
+        | ResponseCode::BadMuxAddress
+        | ResponseCode::BadMuxRegister => bump(&mut stats.nacks),
+        ResponseCode::BusError => bump(&mut stats.bus_errors),
+        ResponseCode::BusReset | ResponseCode::BusResetMux => {
+            bump(&mut stats.arbitration_lost)
+        }
+        ResponseCode::ControllerLocked => bump(&mut stats.timeouts),
+        ResponseCode::BusLocked | ResponseCode::BusLockedMux => {
+            bump(&mut stats.clock_stretch)
+        }
+        _ => {}
+    }
+}
+
 fn reset_if_needed(
     code: ResponseCode,
     controller: &I2cController,
     port: PortIndex,
+    pins: &[I2cPin],
     muxes: &[I2cMux],
     mux: Option<(Mux, Segment)>,
+    stats: &mut Stats,
 ) {
     ringbuf_entry!(Some(code));
 
+    let id = mux.map(|(id, _)| id);
+    stats.update(controller.controller, port, id, |s| record(s, code));
+
     match code {
         ResponseCode::BusLocked
         | ResponseCode::BusLockedMux
@@ -131,6 +254,14 @@ fn reset_if_needed(
 
     // First, bounce our I2C controller
     controller.reset();
+    stats.update(controller.controller, port, id, |s| bump(&mut s.resets));
+
+    // If a target is holding SDA low, resetting won't have freed the bus.
+    if clear_bus(controller, port, pins, &sys) {
+        stats.update(controller.controller, port, id, |s| {
+            bump(&mut s.bus_clears)
+        });
+    }
 
     // And now reset the mux, eating any errors.
     let _ = find_mux(controller, port, muxes, mux, |mux, _, _| {
@@ -140,6 +271,123 @@ fn reset_if_needed(
     });
 }
 
+///
+/// Recovers a bus on which a target is holding SDA low -- as it will if it
+/// was interrupted (e.g., by our own reset) in the midst of sending a zero
+/// bit.  We take the pins of the port back as GPIOs and clock SCL until the
+/// target releases SDA (which will take at most nine clocks: eight data bits
+/// and an ACK), and then generate a STOP condition.  The configuration
+/// doesn't tell us which of the pins is SDA, but we don't need it to:  with
+/// the pins as inputs, SDA is the one that is low.  (If both are low, a
+/// target is holding SCL, and there is nothing we can do.)  Returns true if
+/// we clocked the bus.
+///
+fn clear_bus(
+    controller: &I2cController,
+    port: PortIndex,
+    pins: &[I2cPin],
+    sys: &Sys,
+) -> bool {
+    let mut lines: [Option<PinSet>; 2] = [None; 2];
+    let mut nlines = 0;
+
+    for pin in pins
+        .iter()
+        .filter(|p| p.controller == controller.controller && p.port == port)
+    {
+        for i in 0..16 {
+            if pin.gpio_pins.pin_mask & (1 << i) == 0 {
+                continue;
+            }
+
+            if nlines == lines.len() {
+                return false;
+            }
+
+            lines[nlines] = Some(pin.gpio_pins.port.pin(i));
+            nlines += 1;
+        }
+    }
+
+    let (a, b) = match lines {
+        [Some(a), Some(b)] => (a, b),
+        _ => return false,
+    };
+
+    sys.gpio_configure_input(a, Pull::None).unwrap();
+    sys.gpio_configure_input(b, Pull::None).unwrap();
+
+    let low = |line| sys.gpio_read(line).unwrap() == 0;
+
+    let cleared = match (low(a), low(b)) {
+        (false, true) => {
+            clock_bus(a, b, sys);
+            true
+        }
+        (true, false) => {
+            clock_bus(b, a, sys);
+            true
+        }
+        _ => false,
+    };
+
+    for pin in pins
+        .iter()
+        .filter(|p| p.controller == controller.controller && p.port == port)
+    {
+        sys.gpio_configure_alternate(
+            pin.gpio_pins,
+            OutputType::OpenDrain,
+            Speed::High,
+            Pull::None,
+            pin.function,
+        )
+        .unwrap();
+    }
+
+    cleared
+}
+
+fn clock_bus(scl: PinSet, sda: PinSet, sys: &Sys) {
+    let output = |line| {
+        sys.gpio_configure_output(
+            line,
+            OutputType::OpenDrain,
+            Speed::High,
+            Pull::None,
+        )
+        .unwrap();
+    };
+
+    sys.gpio_set(scl).unwrap();
+    output(scl);
+
+    for _ in 0..9 {
+        sys.gpio_reset(scl).unwrap();
+        hl::sleep_for(1);
+        sys.gpio_set(scl).unwrap();
+        hl::sleep_for(1);
+
+        if sys.gpio_read(sda).unwrap() != 0 {
+            break;
+        }
+    }
+
+    //
+    // Now issue a STOP:  with SCL low, drive SDA low, and then release SCL
+    // followed by SDA.
+    //
+    sys.gpio_reset(scl).unwrap();
+    hl::sleep_for(1);
+    sys.gpio_reset(sda).unwrap();
+    output(sda);
+    hl::sleep_for(1);
+    sys.gpio_set(scl).unwrap();
+    hl::sleep_for(1);
+    sys.gpio_set(sda).unwrap();
+    hl::sleep_for(1);
+}
+
 type PortMap = FixedMap<Controller, PortIndex, 8>;
 type MuxMap = FixedMap<Mux, Segment, 4>;
 
@@ -154,6 +402,7 @@ fn main() -> ! {
     // This is our actual mutable state
     let mut portmap = PortMap::new();
     let mut muxmap = MuxMap::new();
+    let mut stats = Stats::new(&controllers, &pins, &muxes);
 
     // Turn the actual peripheral on so that we can interact with it.
     turn_on_i2c(&controllers);
@@ -172,10 +421,26 @@ fn main() -> ! {
         },
     };
 
-    configure_muxes(&muxes, &controllers, &pins, &mut portmap, &ctrl);
+    configure_muxes(
+        &muxes,
+        &controllers,
+        &pins,
+        &mut portmap,
+        &ctrl,
+        &mut stats,
+    );
 
     loop {
         hl::recv_without_notification(&mut buffer, |op, msg| match op {
+            Op::GetStats => {
+                let (payload, caller) = msg
+                    .fixed::<[u8; 4], I2cStats>()
+                    .ok_or(ResponseCode::BadArg)?;
+
+                let scope = StatsScope::unmarshal(payload)?;
+                caller.reply(stats.get(scope)?);
+                Ok(())
+            }
             Op::WriteRead | Op::WriteReadBlock => {
                 let (payload, caller) = msg
                     .fixed_with_leases::<[u8; 4], usize>(2)
@@ -203,7 +468,10 @@ fn main() -> ! {
                 ) {
                     Ok(_) => {}
                     Err(code) => {
-                        reset_if_needed(code, controller, port, &muxes, mux);
+                        reset_if_needed(
+                            code, controller, port, &pins, &muxes, mux,
+                            &mut stats,
+                        );
                         return Err(code);
                     }
                 }
@@ -253,7 +521,10 @@ fn main() -> ! {
                     &ctrl,
                 ) {
                     Err(code) => {
-                        reset_if_needed(code, controller, port, &muxes, mux);
+                        reset_if_needed(
+                            code, controller, port, &pins, &muxes, mux,
+                            &mut stats,
+                        );
                         Err(code)
                     }
                     Ok(_) => {
@@ -369,6 +640,7 @@ fn configure_muxes(
     pins: &[I2cPin],
     map: &mut PortMap,
     ctrl: &I2cControl,
+    stats: &mut Stats,
 ) {
     let sys = SYS.get_task_id();
     let sys = Sys::from(sys);
@@ -385,7 +657,9 @@ fn configure_muxes(
                 }
                 Err(code) => {
                     ringbuf_entry!(Some(code));
-                    reset_if_needed(code, controller, mux.port, muxes, None);
+                    reset_if_needed(
+                        code, controller, mux.port, pins, muxes, None, stats,
+                    );
                 }
             }
         }
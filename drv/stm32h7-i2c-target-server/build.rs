@@ -2,6 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
-fn main() {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     build_util::expose_target_board();
+    build_i2c::codegen(build_i2c::Disposition::Target)?;
+
+    idol::server::build_server_support(
+        "../../idl/i2c-target.idol",
+        "server_stub.rs",
+        idol::server::ServerStyle::InOrder,
+    )?;
+
+    Ok(())
 }
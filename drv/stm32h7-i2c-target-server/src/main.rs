@@ -0,0 +1,346 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A server for the STM32H7 I2C interface operating as a target
+//!
+//! This server operates the (single) I2C controller configured as a target,
+//! and allows tasks to register virtual targets at addresses on its bus.
+//! Each virtual target is a file of registers held by the server; an
+//! initiator accesses them in the manner of an EEPROM, and the owning task
+//! accesses them via IPC -- setting the registers that the initiator reads,
+//! and being notified when the initiator writes them.  (See
+//! `drv_i2c_target_api` for details.)  This allows the SP to present (for
+//! example) a FRU EEPROM or a simulated sensor to another I2C initiator.
+//!
+
+#![no_std]
+#![no_main]
+
+use core::cell::RefCell;
+use drv_i2c_api::ReservedAddress;
+use drv_i2c_target_api::{I2cTargetError, TargetWrite, NUM_REGISTERS};
+use drv_stm32h7_i2c::*;
+use drv_stm32xx_sys_api::{OutputType, Pull, Speed, Sys};
+use idol_runtime::{
+    ClientError, Leased, LenLimit, NotificationHandler, RequestError, R, W,
+};
+use ringbuf::*;
+use userlib::*;
+
+task_slot!(SYS, sys);
+
+include!(concat!(env!("OUT_DIR"), "/i2c_config.rs"));
+
+///
+/// The maximum number of virtual targets, across all tasks.
+///
+const MAX_TARGETS: usize = 4;
+
+#[derive(Copy, Clone, PartialEq)]
+enum Trace {
+    Registered(u8, TaskId),
+    Unregistered(u8),
+    Written(u8, TargetWrite),
+    None,
+}
+
+ringbuf!(Trace, 16, Trace::None);
+
+#[derive(Copy, Clone)]
+struct Target {
+    /// address of the virtual target
+    address: u8,
+
+    /// task that registered the target, and its notification
+    owner: TaskId,
+    notification: u32,
+
+    /// register contents
+    registers: [u8; NUM_REGISTERS],
+
+    /// register pointer
+    pointer: u8,
+
+    /// true if the next byte received is the register pointer
+    addressing: bool,
+
+    /// registers written by the most recent write
+    last: TargetWrite,
+
+    /// true if the registers have been written but the owner not notified
+    written: bool,
+}
+
+impl Target {
+    fn rx(&mut self, byte: u8) {
+        if self.addressing {
+            self.pointer = byte;
+            self.addressing = false;
+            self.last = TargetWrite {
+                offset: byte,
+                len: 0,
+            };
+        } else {
+            self.registers[self.pointer as usize] = byte;
+            self.pointer = self.pointer.wrapping_add(1);
+            self.last.len = self.last.len.saturating_add(1);
+            self.written = true;
+        }
+    }
+
+    fn tx(&mut self) -> u8 {
+        let byte = self.registers[self.pointer as usize];
+        self.pointer = self.pointer.wrapping_add(1);
+        byte
+    }
+}
+
+fn find(targets: &mut [Option<Target>], address: u8) -> Option<&mut Target> {
+    targets.iter_mut().flatten().find(|t| t.address == address)
+}
+
+struct ServerImpl {
+    controller: I2cController<'static>,
+    state: I2cTargetState,
+    targets: [Option<Target>; MAX_TARGETS],
+}
+
+impl ServerImpl {
+    ///
+    /// Returns the virtual target at the specified address, if it exists and
+    /// is owned by the caller.
+    ///
+    fn target(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+    ) -> Result<&mut Target, RequestError<I2cTargetError>> {
+        let target = find(&mut self.targets, address)
+            .ok_or(I2cTargetError::NotRegistered)?;
+
+        if target.owner.index() != rm.sender.index() {
+            return Err(I2cTargetError::NotYours.into());
+        }
+
+        Ok(target)
+    }
+}
+
+///
+/// Checks that the range of `len` registers starting at `offset` exists,
+/// returning the range.
+///
+fn registers(
+    offset: u8,
+    len: usize,
+) -> Result<core::ops::Range<usize>, RequestError<I2cTargetError>> {
+    let offset = offset as usize;
+
+    if offset + len > NUM_REGISTERS {
+        Err(I2cTargetError::BadOffset.into())
+    } else {
+        Ok(offset..offset + len)
+    }
+}
+
+impl idl::InOrderI2cTargetImpl for ServerImpl {
+    fn register(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+        notification: u32,
+    ) -> Result<(), RequestError<I2cTargetError>> {
+        if address > 0x7f || ReservedAddress::from_u8(address).is_some() {
+            return Err(I2cTargetError::BadAddress.into());
+        }
+
+        //
+        // If the caller has already registered this address (e.g., before
+        // it restarted), we update the registration.
+        //
+        if let Some(target) = find(&mut self.targets, address) {
+            if target.owner.index() != rm.sender.index() {
+                return Err(I2cTargetError::AddressInUse.into());
+            }
+
+            target.owner = rm.sender;
+            target.notification = notification;
+            ringbuf_entry!(Trace::Registered(address, rm.sender));
+            return Ok(());
+        }
+
+        let slot = self
+            .targets
+            .iter_mut()
+            .find(|t| t.is_none())
+            .ok_or(I2cTargetError::TargetsFull)?;
+
+        *slot = Some(Target {
+            address,
+            owner: rm.sender,
+            notification,
+            registers: [0; NUM_REGISTERS],
+            pointer: 0,
+            addressing: false,
+            last: TargetWrite::default(),
+            written: false,
+        });
+
+        ringbuf_entry!(Trace::Registered(address, rm.sender));
+        Ok(())
+    }
+
+    fn unregister(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+    ) -> Result<(), RequestError<I2cTargetError>> {
+        self.target(rm, address)?;
+
+        for slot in self.targets.iter_mut() {
+            if matches!(slot, Some(t) if t.address == address) {
+                *slot = None;
+            }
+        }
+
+        ringbuf_entry!(Trace::Unregistered(address));
+        Ok(())
+    }
+
+    fn write_registers(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+        offset: u8,
+        data: LenLimit<Leased<R, [u8]>, 256>,
+    ) -> Result<(), RequestError<I2cTargetError>> {
+        let range = registers(offset, data.len())?;
+        let target = self.target(rm, address)?;
+
+        data.read_range(0..data.len(), &mut target.registers[range])
+            .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+        Ok(())
+    }
+
+    fn read_registers(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+        offset: u8,
+        data: LenLimit<Leased<W, [u8]>, 256>,
+    ) -> Result<(), RequestError<I2cTargetError>> {
+        let range = registers(offset, data.len())?;
+        let target = self.target(rm, address)?;
+
+        data.write_range(0..data.len(), &target.registers[range])
+            .map_err(|_| RequestError::Fail(ClientError::WentAway))?;
+
+        Ok(())
+    }
+
+    fn last_write(
+        &mut self,
+        rm: &RecvMessage,
+        address: u8,
+    ) -> Result<TargetWrite, RequestError<I2cTargetError>> {
+        Ok(self.target(rm, address)?.last)
+    }
+}
+
+impl NotificationHandler for ServerImpl {
+    fn current_notification_mask(&self) -> u32 {
+        self.controller.notification
+    }
+
+    fn handle_notification(&mut self, _bits: u32) {
+        let targets = RefCell::new(&mut self.targets);
+
+        self.controller.operate_as_target_nonblocking(
+            &mut self.state,
+            |addr| match find(&mut **targets.borrow_mut(), addr) {
+                Some(target) => {
+                    target.addressing = true;
+                    true
+                }
+                None => false,
+            },
+            |addr, byte| {
+                if let Some(target) = find(&mut **targets.borrow_mut(), addr) {
+                    target.rx(byte);
+                }
+            },
+            |addr| find(&mut **targets.borrow_mut(), addr).map(|t| t.tx()),
+        );
+
+        sys_irq_control(self.controller.notification, true);
+
+        //
+        // We only notify owners of writes once the transaction is complete,
+        // lest they see a partial write.
+        //
+        if self.state != I2cTargetState::Idle {
+            return;
+        }
+
+        for target in self.targets.iter_mut().flatten() {
+            if target.written {
+                target.written = false;
+                ringbuf_entry!(Trace::Written(target.address, target.last));
+                sys_post(
+                    sys_refresh_task_id(target.owner),
+                    target.notification,
+                );
+            }
+        }
+    }
+}
+
+fn configure_pins(pins: &[I2cPin]) {
+    let sys = SYS.get_task_id();
+    let sys = Sys::from(sys);
+
+    for pin in pins {
+        sys.gpio_configure_alternate(
+            pin.gpio_pins,
+            OutputType::OpenDrain,
+            Speed::High,
+            Pull::None,
+            pin.function,
+        )
+        .unwrap();
+    }
+}
+
+#[export_name = "main"]
+fn main() -> ! {
+    let [controller] = i2c_config::controllers();
+    let pins = i2c_config::pins();
+
+    let sys = Sys::from(SYS.get_task_id());
+
+    controller.enable(&sys);
+    configure_pins(&pins);
+
+    controller.configure_as_target();
+    sys_irq_control(controller.notification, true);
+
+    let mut server = ServerImpl {
+        controller,
+        state: I2cTargetState::Idle,
+        targets: [None; MAX_TARGETS],
+    };
+
+    let mut buffer = [0; idl::INCOMING_SIZE];
+
+    loop {
+        idol_runtime::dispatch_n(&mut buffer, &mut server);
+    }
+}
+
+mod idl {
+    use super::{I2cTargetError, TargetWrite};
+
+    include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
+}
@@ -61,6 +61,20 @@ pub struct I2cControl {
     pub wfi: fn(u32),
 }
 
+///
+/// The state of a controller operating as a target, as maintained by the
+/// caller across calls to [`I2cController::operate_as_target_nonblocking`].
+///
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum I2cTargetState {
+    /// Waiting to be addressed
+    Idle,
+    /// Addressed by a write, which we have either initiated or are NACK'ing
+    Receiving { addr: u8, initiated: bool },
+    /// Addressed by a read, which we have either initiated or are filling
+    Transmitting { addr: u8, initiated: bool },
+}
+
 #[derive(Copy, Clone, PartialEq)]
 pub enum I2cKonamiCode {
     Read,
@@ -700,7 +714,7 @@ impl<'a> I2cController<'a> {
         Ok(())
     }
 
-    fn configure_as_target(&self) {
+    pub fn configure_as_target(&self) {
         let i2c = self.registers;
 
         // Disable PE
@@ -902,4 +916,166 @@ impl<'a> I2cController<'a> {
             }
         }
     }
+
+    ///
+    /// Like [`I2cController::operate_as_target`], but rather than waiting for
+    /// an interrupt when there is nothing to be done, returns to the caller
+    /// -- allowing a server to field messages while operating as a target.
+    /// The controller must have been configured via
+    /// [`I2cController::configure_as_target`], and this should be called
+    /// (and the interrupt reenabled) whenever the controller's interrupt
+    /// fires.  As with `operate_as_target`, `initiate` is called with the
+    /// address when addressed, `rxbyte` with each byte written to an
+    /// initiated address, and `txbyte` for each byte read from one.
+    ///
+    pub fn operate_as_target_nonblocking(
+        &self,
+        state: &mut I2cTargetState,
+        mut initiate: impl FnMut(u8) -> bool,
+        mut rxbyte: impl FnMut(u8, u8),
+        mut txbyte: impl FnMut(u8) -> Option<u8>,
+    ) {
+        let i2c = self.registers;
+
+        loop {
+            let isr = i2c.isr.read();
+
+            match *state {
+                I2cTargetState::Idle => {
+                    ringbuf_entry!(Trace::AddrISR(isr.bits()));
+
+                    if isr.stopf().is_stop() {
+                        i2c.icr.write(|w| w.stopcf().set_bit());
+                        continue;
+                    }
+
+                    if !isr.addr().is_match() {
+                        ringbuf_entry!(Trace::WaitAddr);
+                        return;
+                    }
+
+                    ringbuf_entry!(Trace::AddrMatch);
+
+                    let is_write = isr.dir().is_write();
+                    let addr = isr.addcode().bits();
+
+                    // Flush our TXDR and clear our Address interrupt
+                    i2c.isr.modify(|_, w| w.txe().set_bit());
+                    i2c.icr.write(|w| w.addrcf().set_bit());
+
+                    let initiated = initiate(addr);
+
+                    if !initiated {
+                        i2c.cr2.modify(|_, w| w.nack().set_bit());
+                        ringbuf_entry!(Trace::AddrNack(addr));
+                    }
+
+                    *state = if is_write {
+                        I2cTargetState::Receiving { addr, initiated }
+                    } else {
+                        I2cTargetState::Transmitting { addr, initiated }
+                    };
+                }
+
+                I2cTargetState::Receiving { addr, initiated } => {
+                    ringbuf_entry!(Trace::RxISR(isr.bits()));
+
+                    if isr.addr().is_match() {
+                        i2c.icr.write(|w| w.addrcf().set_bit());
+
+                        //
+                        // If this is a change in direction (i.e., a repeated
+                        // start for a read), we are now transmitting.
+                        //
+                        if !isr.dir().is_write() {
+                            *state = I2cTargetState::Transmitting {
+                                addr,
+                                initiated,
+                            };
+                        }
+
+                        continue;
+                    }
+
+                    if isr.stopf().is_stop() {
+                        i2c.icr.write(|w| w.stopcf().set_bit());
+                        *state = I2cTargetState::Idle;
+                        continue;
+                    }
+
+                    if isr.nackf().is_nack() {
+                        i2c.icr.write(|w| w.nackcf().set_bit());
+                        *state = I2cTargetState::Idle;
+                        continue;
+                    }
+
+                    if isr.rxne().is_not_empty() {
+                        let rx = i2c.rxdr.read().rxdata().bits();
+
+                        if initiated {
+                            ringbuf_entry!(Trace::Rx(addr, rx));
+                            rxbyte(addr, rx);
+                        } else {
+                            ringbuf_entry!(Trace::RxNack(addr, rx));
+                        }
+
+                        continue;
+                    }
+
+                    ringbuf_entry!(Trace::WaitRx);
+                    return;
+                }
+
+                I2cTargetState::Transmitting { addr, initiated } => {
+                    ringbuf_entry!(Trace::TxISR(isr.bits()));
+
+                    if isr.addr().is_match() || isr.stopf().is_stop() {
+                        //
+                        // We are being addressed anew (or the transaction is
+                        // over); either way, sort it out from our idle state.
+                        //
+                        *state = I2cTargetState::Idle;
+                        continue;
+                    }
+
+                    if isr.txis().is_empty() {
+                        //
+                        // As with `operate_as_target`, we have no way of
+                        // NACK'ing a read once our address is ACK'd, so we
+                        // send filler when we have nothing to send.
+                        //
+                        const FILLER: u8 = 0xff;
+
+                        let byte = if initiated {
+                            match txbyte(addr) {
+                                Some(byte) => {
+                                    ringbuf_entry!(Trace::Tx(addr, byte));
+                                    byte
+                                }
+                                None => {
+                                    ringbuf_entry!(Trace::TxOverrun(addr));
+                                    FILLER
+                                }
+                            }
+                        } else {
+                            ringbuf_entry!(Trace::TxBogus(addr));
+                            FILLER
+                        };
+
+                        i2c.txdr.write(|w| w.txdata().bits(byte));
+                        continue;
+                    }
+
+                    if isr.nackf().is_nack() {
+                        i2c.icr.write(|w| w.nackcf().set_bit());
+                        *state = I2cTargetState::Idle;
+                        continue;
+                    }
+
+                    ringbuf_entry!(Trace::WaitTx);
+                    return;
+                }
+            }
+        }
+    }
 }
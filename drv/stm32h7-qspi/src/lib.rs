@@ -27,6 +27,7 @@ pub struct Qspi {
 }
 
 enum Command {
+    WriteStatusReg = 0x01,
     ReadStatusReg = 0x05,
     WriteEnable = 0x06,
     PageProgram = 0x12,
@@ -105,6 +106,14 @@ impl Qspi {
         status
     }
 
+    /// Writes the Status register.  This must be preceded by `write_enable`.
+    ///
+    /// Note that on both of the parts we support, the block protect bits in
+    /// the Status register are non-volatile.
+    pub fn write_status(&self, status: u8) {
+        self.write_impl(Command::WriteStatusReg, None, &[status])
+    }
+
     /// Reads from flash storage starting at `address` and continuing for
     /// `data.len()` bytes, depositing the bytes into `data`.
     pub fn read_memory(&self, address: u32, data: &mut [u8]) {
@@ -0,0 +1,338 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Power state transition history, shared by the sequencers.
+//!
+//! Each sequencer records its transitions in a [History], and returns them
+//! as [PowerTransition]s from its `transition_history` operation.  A
+//! sequencer that the thermal task can ask to power off also records the
+//! [ThermalTrip]s that caused it to.  The sequencer's power states and errors
+//! are recorded as their raw values; the sequencer's API crate interprets
+//! them.
+//!
+//! A sequencer keeps its [History] in RAM that isn't initialized when the
+//! task starts (see [History::resume]), so that the history survives the
+//! sequencer being restarted -- and the SP being reset, as long as it stays
+//! powered.  Each start of the sequencer counts a boot, and each record
+//! carries the boot in which it was made.
+
+#![no_std]
+
+use core::mem::MaybeUninit;
+use core::ptr::{addr_of, read_volatile};
+use userlib::*;
+use zerocopy::{AsBytes, FromBytes};
+
+///
+/// The reason for a power state transition.
+///
+#[derive(Copy, Clone, Debug, FromPrimitive, PartialEq, AsBytes)]
+#[repr(u8)]
+pub enum PowerReason {
+    /// The transition was requested by a task without a specified reason
+    Requested = 1,
+    /// The transition was forced by a thermal trip
+    ThermalTrip = 2,
+    /// A failed transition was rolled back to A2
+    FaultRollback = 3,
+}
+
+///
+/// A record of a power state transition, as returned by a sequencer's
+/// `transition_history` operation.  The states, reason and error are
+/// recorded as their raw values; use the accessors to interpret them.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct PowerTransition {
+    /// Time of the transition, in milliseconds since the SP booted
+    pub timestamp: u64,
+    /// The sequencer's boot in which the transition was made, as counted by
+    /// its [History]
+    pub boot: u64,
+    /// Task that requested the transition (raw `TaskId`)
+    pub task: u16,
+    /// Sequencer's `PowerState` before the transition
+    pub from_state: u8,
+    /// Sequencer's `PowerState` requested
+    pub to_state: u8,
+    /// `PowerReason` for the transition
+    pub reason: u8,
+    /// Sequencer's `SeqError` if the transition failed, or 0 if it succeeded
+    pub error: u8,
+    /// Count of transitions recorded in the history, allowing dropped
+    /// records to be detected
+    pub sequence: u16,
+}
+
+impl PowerTransition {
+    pub fn task(&self) -> TaskId {
+        TaskId(self.task)
+    }
+
+    pub fn from_state<S: FromPrimitive>(&self) -> Option<S> {
+        S::from_u8(self.from_state)
+    }
+
+    pub fn to_state<S: FromPrimitive>(&self) -> Option<S> {
+        S::from_u8(self.to_state)
+    }
+
+    pub fn reason(&self) -> Option<PowerReason> {
+        PowerReason::from_u8(self.reason)
+    }
+
+    pub fn error<E: FromPrimitive>(&self) -> Option<E> {
+        E::from_u8(self.error)
+    }
+}
+
+///
+/// A record of a thermal trip, as returned by a sequencer's
+/// `thermal_trip_history` operation:  the sensor that was at or above its
+/// critical threshold, the power state that the sequencer was in, and the
+/// result of dropping to A2.
+///
+#[derive(Copy, Clone, Debug, Default, PartialEq, AsBytes, FromBytes)]
+#[repr(C)]
+pub struct ThermalTrip {
+    /// Time of the trip, in milliseconds since the SP booted
+    pub timestamp: u64,
+    /// The sequencer's boot in which the trip happened
+    pub boot: u64,
+    /// Sensor (raw `SensorId`) that tripped
+    pub sensor: u32,
+    /// Its reading, in degrees Celsius
+    pub reading: f32,
+    /// Its critical threshold, in degrees Celsius
+    pub critical: f32,
+    /// Sequencer's `PowerState` at the time of the trip
+    pub state: u8,
+    /// Sequencer's `SeqError` if dropping to A2 failed, or 0 if it succeeded
+    /// (or if the system was already in A2)
+    pub error: u8,
+    /// Count of trips recorded in the history
+    pub sequence: u16,
+}
+
+impl ThermalTrip {
+    pub fn state<S: FromPrimitive>(&self) -> Option<S> {
+        S::from_u8(self.state)
+    }
+
+    pub fn error<E: FromPrimitive>(&self) -> Option<E> {
+        E::from_u8(self.error)
+    }
+}
+
+///
+/// The number of power state transitions retained in a [History].
+///
+pub const HISTORY_DEPTH: usize = 16;
+
+///
+/// The number of thermal trips retained in a [History].  A trip is followed
+/// by a transition to A2, so these are kept apart from the transitions, lest
+/// a host that is powered back on (and tripped again) push them out.
+///
+pub const TRIP_DEPTH: usize = 4;
+
+///
+/// Marks RAM that holds a [History], rather than whatever it held when the SP
+/// was powered up.  This must change whenever the layout of [History] (or of
+/// its records) does, so that a new sequencer image doesn't take up a history
+/// that an old one left behind.
+///
+const HISTORY_MAGIC: u32 = 0x5077_4802;
+
+///
+/// A ring of the last `N` records, each numbered by a sequence that counts
+/// every record made.
+///
+#[repr(C)]
+struct Log<T, const N: usize> {
+    next: u32,
+    len: u32,
+    sequence: u16,
+    records: [T; N],
+}
+
+impl<T: Copy + Default, const N: usize> Log<T, N> {
+    fn new() -> Self {
+        Self {
+            next: 0,
+            len: 0,
+            sequence: 0,
+            records: [T::default(); N],
+        }
+    }
+
+    ///
+    /// Checks (through volatile reads, since we don't know what the RAM
+    /// holds) that the log at `p` won't index out of bounds.
+    ///
+    unsafe fn is_valid(p: *const Self) -> bool {
+        read_volatile(addr_of!((*p).next)) < N as u32
+            && read_volatile(addr_of!((*p).len)) <= N as u32
+    }
+
+    fn push(&mut self, record: impl FnOnce(u16) -> T) {
+        self.records[self.next as usize] = record(self.sequence);
+        self.next = (self.next + 1) % N as u32;
+        self.len = u32::min(self.len + 1, N as u32);
+        self.sequence = self.sequence.wrapping_add(1);
+    }
+
+    fn get(&self, index: u8) -> Option<T> {
+        let index = index as usize;
+
+        if index >= self.len as usize {
+            return None;
+        }
+
+        Some(self.records[(self.next as usize + N - 1 - index) % N])
+    }
+}
+
+///
+/// The history of power state transitions and thermal trips, as returned by
+/// the `transition_history` and `thermal_trip_history` operations.
+///
+#[repr(C)]
+pub struct History {
+    magic: u32,
+    boot: u64,
+    transitions: Log<PowerTransition, HISTORY_DEPTH>,
+    trips: Log<ThermalTrip, TRIP_DEPTH>,
+}
+
+impl History {
+    fn new() -> Self {
+        Self {
+            magic: HISTORY_MAGIC,
+            boot: 0,
+            transitions: Log::new(),
+            trips: Log::new(),
+        }
+    }
+
+    ///
+    /// Takes up the history that the sequencer left in `history` before it
+    /// (or the SP) was restarted -- or starts a new one, if `history` holds
+    /// anything else -- and counts a boot.  `history` should be a static in
+    /// the `.uninit` section, which is left alone when the task starts:
+    ///
+    /// ```ignore
+    /// #[link_section = ".uninit.history"]
+    /// static mut HISTORY: MaybeUninit<History> = MaybeUninit::uninit();
+    /// ```
+    ///
+    pub fn resume(
+        history: &'static mut MaybeUninit<History>,
+    ) -> &'static mut History {
+        let p = history.as_mut_ptr();
+
+        //
+        // Every field of a `History` is an integer (or a float), or an array
+        // of structs of them, so any contents are a `History` -- as long as
+        // its indices are in bounds.
+        //
+        let valid = unsafe {
+            read_volatile(addr_of!((*p).magic)) == HISTORY_MAGIC
+                && Log::is_valid(addr_of!((*p).transitions))
+                && Log::is_valid(addr_of!((*p).trips))
+        };
+
+        if !valid {
+            history.write(History::new());
+        }
+
+        let history = unsafe { &mut *p };
+        history.boot = history.boot.wrapping_add(1);
+        history
+    }
+
+    ///
+    /// Returns the sequencer's boot, counting from 1 when the history was
+    /// started.
+    ///
+    pub fn boot(&self) -> u64 {
+        self.boot
+    }
+
+    ///
+    /// Records a transition from state `from` to state `to`, requested by
+    /// `task` for `reason`, with its result `rval`.
+    ///
+    pub fn record<S: ToPrimitive, E: ToPrimitive>(
+        &mut self,
+        task: TaskId,
+        from: S,
+        to: S,
+        reason: PowerReason,
+        rval: Result<(), E>,
+    ) {
+        let boot = self.boot;
+
+        self.transitions.push(|sequence| PowerTransition {
+            timestamp: sys_get_timer().now,
+            boot,
+            task: task.0,
+            from_state: from.to_u8().unwrap_or(0),
+            to_state: to.to_u8().unwrap_or(0),
+            reason: reason as u8,
+            error: match rval {
+                Ok(()) => 0,
+                Err(err) => err.to_u8().unwrap_or(0),
+            },
+            sequence,
+        });
+    }
+
+    ///
+    /// Records a thermal trip of `sensor`, which read `reading` against its
+    /// `critical` threshold, in state `state`, with the result `rval` of
+    /// dropping to A2.
+    ///
+    pub fn record_trip<S: ToPrimitive, E: ToPrimitive>(
+        &mut self,
+        sensor: u32,
+        reading: f32,
+        critical: f32,
+        state: S,
+        rval: Result<(), E>,
+    ) {
+        let boot = self.boot;
+
+        self.trips.push(|sequence| ThermalTrip {
+            timestamp: sys_get_timer().now,
+            boot,
+            sensor,
+            reading,
+            critical,
+            state: state.to_u8().unwrap_or(0),
+            error: match rval {
+                Ok(()) => 0,
+                Err(err) => err.to_u8().unwrap_or(0),
+            },
+            sequence,
+        });
+    }
+
+    ///
+    /// Returns the transition `index` transitions ago, with 0 being the most
+    /// recent.
+    ///
+    pub fn get(&self, index: u8) -> Option<PowerTransition> {
+        self.transitions.get(index)
+    }
+
+    ///
+    /// Returns the thermal trip `index` trips ago, with 0 being the most
+    /// recent.
+    ///
+    pub fn get_trip(&self, index: u8) -> Option<ThermalTrip> {
+        self.trips.get(index)
+    }
+}
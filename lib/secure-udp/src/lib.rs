@@ -0,0 +1,522 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Authenticated encryption of UDP datagrams with a pre-shared key.
+//!
+//! A client and a server that share a 32-byte key establish a session with a
+//! two-message handshake, which mixes a random value from each side into
+//! fresh session keys (so that datagrams from one session can't be replayed
+//! into another).  Datagrams are then sealed with ChaCha20-Poly1305, and each
+//! carries a sequence number that the receiver checks against a sliding
+//! window to reject replays.
+//!
+//! Every message starts with a type byte; multi-byte fields are big-endian.
+//! A hello is padded to the size of a reply, so that the server can't be used
+//! to amplify traffic towards a spoofed source address.
+//!
+//! | Message | Contents                                                  |
+//! |---------|-----------------------------------------------------------|
+//! | Hello   | `1`, client random (32), zeros (20)                       |
+//! | Reply   | `2`, session ID (4), server random (32), tag (16)         |
+//! | Data    | `3`, session ID (4), sequence number (8), ciphertext, tag |
+//!
+//! The session keys (one for each direction) are derived with HKDF-SHA256,
+//! using the concatenated randoms as the salt and the pre-shared key as the
+//! input keying material.  The tag of the reply seals an empty message with
+//! the server's key (and sequence number 0), with the reply's header and the
+//! client random as associated data; this proves to the client that the
+//! server knows the key.  The server learns that the client knows the key
+//! when the client's first data message opens.
+//!
+//! The nonce for each message is four zero bytes followed by its sequence
+//! number, and the associated data for each data message is its header.
+//! Sequence numbers start at 1 in each direction.
+//!
+//! There is no forward secrecy:  anyone who learns the pre-shared key can
+//! decrypt recorded sessions.  (The `net` task's README explains why that's
+//! acceptable for per-device keys.)
+//!
+//! The server's side of a socket, which answers hellos and keeps track of
+//! sessions, is [Server].
+
+#![no_std]
+
+use chacha20poly1305::aead::{AeadInPlace, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce, Tag};
+use core::convert::TryInto;
+use core::ops::Range;
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+mod server;
+
+pub use server::{
+    Endpoint, Received, Server, HELLO_INTERVAL, HELLO_RANDOM_SIZE,
+    HELLO_SOURCES, PENDING_SLOTS, PENDING_TIMEOUT,
+};
+
+/// Size of the pre-shared key.
+pub const KEY_SIZE: usize = 32;
+
+/// Size of the random value that each side contributes to a session.
+pub const RANDOM_SIZE: usize = 32;
+
+/// Size of the authentication tag.
+pub const TAG_SIZE: usize = 16;
+
+/// Size of a reply message.
+pub const REPLY_SIZE: usize = 1 + 4 + RANDOM_SIZE + TAG_SIZE;
+
+/// Size of a hello message, which is padded to the size of a reply.
+pub const HELLO_SIZE: usize = REPLY_SIZE;
+
+/// Size of the header of a data message.
+pub const DATA_HEADER_SIZE: usize = 1 + 4 + 8;
+
+/// Number of bytes that sealing adds to a datagram.
+pub const OVERHEAD: usize = DATA_HEADER_SIZE + TAG_SIZE;
+
+const TYPE_HELLO: u8 = 1;
+const TYPE_REPLY: u8 = 2;
+const TYPE_DATA: u8 = 3;
+
+/// Label mixed into the session keys.
+const KEY_INFO: &[u8] = b"secure-udp v1 keys";
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The message is too short (or the wrong length) for its type
+    Truncated,
+    /// The message has an unknown type
+    BadType,
+    /// The message wasn't sealed with our session's key, or was modified
+    BadTag,
+    /// We've already received a message with this sequence number (or it's
+    /// too old to tell)
+    Replayed,
+    /// The message is for a different session
+    WrongSession,
+    /// The buffer is too small for the message
+    BufferTooSmall,
+    /// The session has sent as many messages as it can
+    SequenceExhausted,
+    /// There's no session with the peer
+    NoSession,
+}
+
+/// A message, as parsed by [parse].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Message<'a> {
+    Hello {
+        client_random: &'a [u8; RANDOM_SIZE],
+    },
+    Reply {
+        session_id: u32,
+    },
+    Data {
+        session_id: u32,
+    },
+}
+
+/// Works out what kind of message `buf` holds.
+pub fn parse(buf: &[u8]) -> Result<Message<'_>, Error> {
+    match buf.first() {
+        Some(&TYPE_HELLO) => {
+            if buf.len() != HELLO_SIZE {
+                return Err(Error::Truncated);
+            }
+            Ok(Message::Hello {
+                client_random: buf[1..1 + RANDOM_SIZE].try_into().unwrap(),
+            })
+        }
+        Some(&TYPE_REPLY) => {
+            if buf.len() != REPLY_SIZE {
+                return Err(Error::Truncated);
+            }
+            Ok(Message::Reply {
+                session_id: session_id(buf),
+            })
+        }
+        Some(&TYPE_DATA) => {
+            if buf.len() < OVERHEAD {
+                return Err(Error::Truncated);
+            }
+            Ok(Message::Data {
+                session_id: session_id(buf),
+            })
+        }
+        Some(_) => Err(Error::BadType),
+        None => Err(Error::Truncated),
+    }
+}
+
+fn session_id(buf: &[u8]) -> u32 {
+    u32::from_be_bytes(buf[1..5].try_into().unwrap())
+}
+
+/// Derives the client-to-server and server-to-client keys of a session.
+fn session_keys(
+    psk: &[u8; KEY_SIZE],
+    client_random: &[u8; RANDOM_SIZE],
+    server_random: &[u8; RANDOM_SIZE],
+) -> (ChaCha20Poly1305, ChaCha20Poly1305) {
+    let mut salt = [0u8; 2 * RANDOM_SIZE];
+    salt[..RANDOM_SIZE].copy_from_slice(client_random);
+    salt[RANDOM_SIZE..].copy_from_slice(server_random);
+
+    let mut okm = [0u8; 2 * KEY_SIZE];
+    Hkdf::<Sha256>::new(Some(&salt), psk)
+        .expand(KEY_INFO, &mut okm)
+        .unwrap();
+
+    let keys = (
+        ChaCha20Poly1305::new(Key::from_slice(&okm[..KEY_SIZE])),
+        ChaCha20Poly1305::new(Key::from_slice(&okm[KEY_SIZE..])),
+    );
+    okm.iter_mut().for_each(|b| *b = 0);
+    keys
+}
+
+fn nonce(seq: u64) -> Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[4..].copy_from_slice(&seq.to_be_bytes());
+    *Nonce::from_slice(&nonce)
+}
+
+/// Writes the header of a reply into `buf`, returning the associated data
+/// for its tag.
+fn reply_header(
+    buf: &mut [u8],
+    session_id: u32,
+    server_random: &[u8; RANDOM_SIZE],
+    client_random: &[u8; RANDOM_SIZE],
+) -> [u8; REPLY_SIZE - TAG_SIZE + RANDOM_SIZE] {
+    let mut aad = [0u8; REPLY_SIZE - TAG_SIZE + RANDOM_SIZE];
+    aad[0] = TYPE_REPLY;
+    aad[1..5].copy_from_slice(&session_id.to_be_bytes());
+    aad[5..5 + RANDOM_SIZE].copy_from_slice(server_random);
+    aad[5 + RANDOM_SIZE..].copy_from_slice(client_random);
+    buf[..REPLY_SIZE - TAG_SIZE].copy_from_slice(&aad[..REPLY_SIZE - TAG_SIZE]);
+    aad
+}
+
+/// Answers a hello from a client with session ID `session_id` and our
+/// random value `server_random`, writing the reply into `reply` and
+/// returning its length along with the new session.
+pub fn respond(
+    psk: &[u8; KEY_SIZE],
+    client_random: &[u8; RANDOM_SIZE],
+    session_id: u32,
+    server_random: &[u8; RANDOM_SIZE],
+    reply: &mut [u8],
+) -> Result<(Session, usize), Error> {
+    if reply.len() < REPLY_SIZE {
+        return Err(Error::BufferTooSmall);
+    }
+
+    let (rx, tx) = session_keys(psk, client_random, server_random);
+    let aad = reply_header(reply, session_id, server_random, client_random);
+    let tag = tx
+        .encrypt_in_place_detached(&nonce(0), &aad, &mut [])
+        .map_err(|_| Error::BufferTooSmall)?;
+    reply[REPLY_SIZE - TAG_SIZE..REPLY_SIZE].copy_from_slice(&tag);
+
+    Ok((Session::new(session_id, tx, rx), REPLY_SIZE))
+}
+
+/// The client's side of a handshake.
+pub struct Handshake {
+    psk: [u8; KEY_SIZE],
+    client_random: [u8; RANDOM_SIZE],
+}
+
+impl Handshake {
+    pub fn new(
+        psk: &[u8; KEY_SIZE],
+        client_random: &[u8; RANDOM_SIZE],
+    ) -> Self {
+        Self {
+            psk: *psk,
+            client_random: *client_random,
+        }
+    }
+
+    /// Writes our hello into `buf`, returning its length.
+    pub fn hello(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.len() < HELLO_SIZE {
+            return Err(Error::BufferTooSmall);
+        }
+        buf[0] = TYPE_HELLO;
+        buf[1..1 + RANDOM_SIZE].copy_from_slice(&self.client_random);
+        buf[1 + RANDOM_SIZE..HELLO_SIZE]
+            .iter_mut()
+            .for_each(|b| *b = 0);
+        Ok(HELLO_SIZE)
+    }
+
+    /// Checks the server's reply, returning the session if it's genuine.
+    pub fn finish(self, reply: &[u8]) -> Result<Session, Error> {
+        let session_id = match parse(reply)? {
+            Message::Reply { session_id } => session_id,
+            _ => return Err(Error::BadType),
+        };
+        let server_random: &[u8; RANDOM_SIZE] =
+            reply[5..5 + RANDOM_SIZE].try_into().unwrap();
+
+        let (tx, rx) =
+            session_keys(&self.psk, &self.client_random, server_random);
+        let mut header = [0u8; REPLY_SIZE - TAG_SIZE];
+        let aad = reply_header(
+            &mut header,
+            session_id,
+            server_random,
+            &self.client_random,
+        );
+        let tag = Tag::from_slice(&reply[REPLY_SIZE - TAG_SIZE..]);
+        rx.decrypt_in_place_detached(&nonce(0), &aad, &mut [], tag)
+            .map_err(|_| Error::BadTag)?;
+
+        Ok(Session::new(session_id, tx, rx))
+    }
+}
+
+impl Drop for Handshake {
+    fn drop(&mut self) {
+        self.psk.iter_mut().for_each(|b| *b = 0);
+    }
+}
+
+/// An established session, from either side.
+#[derive(Clone)]
+pub struct Session {
+    id: u32,
+    tx: ChaCha20Poly1305,
+    rx: ChaCha20Poly1305,
+    /// sequence number of the last message that we sealed
+    tx_seq: u64,
+    rx_window: ReplayWindow,
+}
+
+impl Session {
+    fn new(id: u32, tx: ChaCha20Poly1305, rx: ChaCha20Poly1305) -> Self {
+        Self {
+            id,
+            tx,
+            rx,
+            tx_seq: 0,
+            rx_window: ReplayWindow::default(),
+        }
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Seals the `len` bytes of plaintext at `buf[DATA_HEADER_SIZE..]` into
+    /// a data message in place, returning the length of the message.
+    pub fn seal(&mut self, buf: &mut [u8], len: usize) -> Result<usize, Error> {
+        let total = len + OVERHEAD;
+        if buf.len() < total {
+            return Err(Error::BufferTooSmall);
+        }
+        let seq = self.tx_seq.checked_add(1).ok_or(Error::SequenceExhausted)?;
+
+        let (header, rest) = buf.split_at_mut(DATA_HEADER_SIZE);
+        header[0] = TYPE_DATA;
+        header[1..5].copy_from_slice(&self.id.to_be_bytes());
+        header[5..].copy_from_slice(&seq.to_be_bytes());
+
+        let (body, tag) = rest.split_at_mut(len);
+        let t = self
+            .tx
+            .encrypt_in_place_detached(&nonce(seq), header, body)
+            .map_err(|_| Error::BufferTooSmall)?;
+        tag[..TAG_SIZE].copy_from_slice(&t);
+
+        self.tx_seq = seq;
+        Ok(total)
+    }
+
+    /// Opens the data message in `buf` in place, returning the range of
+    /// `buf` that holds the plaintext.
+    pub fn open(&mut self, buf: &mut [u8]) -> Result<Range<usize>, Error> {
+        match parse(buf)? {
+            Message::Data { session_id } if session_id == self.id => (),
+            Message::Data { .. } => return Err(Error::WrongSession),
+            _ => return Err(Error::BadType),
+        }
+
+        let seq = u64::from_be_bytes(buf[5..13].try_into().unwrap());
+        if !self.rx_window.check(seq) {
+            return Err(Error::Replayed);
+        }
+
+        let len = buf.len() - OVERHEAD;
+        let (header, rest) = buf.split_at_mut(DATA_HEADER_SIZE);
+        let (body, tag) = rest.split_at_mut(len);
+        self.rx
+            .decrypt_in_place_detached(
+                &nonce(seq),
+                header,
+                body,
+                Tag::from_slice(tag),
+            )
+            .map_err(|_| Error::BadTag)?;
+
+        self.rx_window.update(seq);
+        Ok(DATA_HEADER_SIZE..DATA_HEADER_SIZE + len)
+    }
+}
+
+/// Tracks which of the last 64 sequence numbers we've received.
+#[derive(Copy, Clone, Default)]
+struct ReplayWindow {
+    /// highest sequence number received
+    top: u64,
+    /// bit `n` is set if we've received `top - n`
+    mask: u64,
+}
+
+impl ReplayWindow {
+    fn check(&self, seq: u64) -> bool {
+        if seq == 0 {
+            false
+        } else if seq > self.top {
+            true
+        } else {
+            let age = self.top - seq;
+            age < 64 && self.mask & (1 << age) == 0
+        }
+    }
+
+    fn update(&mut self, seq: u64) {
+        if seq > self.top {
+            let shift = seq - self.top;
+            self.mask = if shift < 64 { self.mask << shift } else { 0 };
+            self.mask |= 1;
+            self.top = seq;
+        } else {
+            self.mask |= 1 << (self.top - seq);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PSK: [u8; KEY_SIZE] = [0x42; KEY_SIZE];
+
+    fn handshake(psk: &[u8; KEY_SIZE]) -> Result<(Session, Session), Error> {
+        let client = Handshake::new(psk, &[1; RANDOM_SIZE]);
+        let mut buf = [0u8; 64];
+        let n = client.hello(&mut buf)?;
+        let client_random = match parse(&buf[..n])? {
+            Message::Hello { client_random } => *client_random,
+            m => panic!("unexpected {:?}", m),
+        };
+        let (server, n) =
+            respond(&PSK, &client_random, 7, &[2; RANDOM_SIZE], &mut buf)?;
+        let client = client.finish(&buf[..n])?;
+        Ok((client, server))
+    }
+
+    fn seal(session: &mut Session, msg: &[u8]) -> ([u8; 128], usize) {
+        let mut buf = [0u8; 128];
+        buf[DATA_HEADER_SIZE..][..msg.len()].copy_from_slice(msg);
+        let n = session.seal(&mut buf, msg.len()).unwrap();
+        (buf, n)
+    }
+
+    #[test]
+    fn roundtrip() {
+        let (mut client, mut server) = handshake(&PSK).unwrap();
+        assert_eq!(client.id(), 7);
+
+        for msg in [&b"hello"[..], b"", b"world"] {
+            let (mut buf, n) = seal(&mut client, msg);
+            assert_eq!(n, msg.len() + OVERHEAD);
+            if !msg.is_empty() {
+                assert_ne!(&buf[DATA_HEADER_SIZE..][..msg.len()], msg);
+            }
+            let r = server.open(&mut buf[..n]).unwrap();
+            assert_eq!(&buf[r], msg);
+        }
+
+        let (mut buf, n) = seal(&mut server, b"reply");
+        let r = client.open(&mut buf[..n]).unwrap();
+        assert_eq!(&buf[r], b"reply");
+    }
+
+    #[test]
+    fn no_amplification() {
+        let client = Handshake::new(&PSK, &[1; RANDOM_SIZE]);
+        let mut buf = [0u8; 64];
+        let n = client.hello(&mut buf).unwrap();
+        assert!(n >= REPLY_SIZE);
+        assert_eq!(parse(&buf[..n - 1]), Err(Error::Truncated));
+    }
+
+    #[test]
+    fn wrong_key() {
+        assert_eq!(
+            handshake(&[0x43; KEY_SIZE]).map(|_| ()),
+            Err(Error::BadTag)
+        );
+    }
+
+    #[test]
+    fn tampering() {
+        let (mut client, mut server) = handshake(&PSK).unwrap();
+        let (buf, n) = seal(&mut client, b"hello");
+        for i in 0..n {
+            let mut b = buf;
+            b[i] ^= 0x80;
+            assert!(server.open(&mut b[..n]).is_err(), "byte {}", i);
+        }
+        // None of which should have disturbed the session.
+        let mut b = buf;
+        assert!(server.open(&mut b[..n]).is_ok());
+    }
+
+    #[test]
+    fn replay() {
+        let (mut client, mut server) = handshake(&PSK).unwrap();
+        let mut sealed = [([0u8; 128], 0); 70];
+        for s in sealed.iter_mut() {
+            *s = seal(&mut client, b"x");
+        }
+
+        // Out of order is fine, but only once.
+        let (mut b, n) = sealed[1];
+        assert!(server.open(&mut b[..n]).is_ok());
+        let (mut b, n) = sealed[0];
+        assert!(server.open(&mut b[..n]).is_ok());
+        let (mut b, n) = sealed[0];
+        assert_eq!(server.open(&mut b[..n]), Err(Error::Replayed));
+
+        // Once we've moved on, old messages are rejected.
+        let (mut b, n) = sealed[69];
+        assert!(server.open(&mut b[..n]).is_ok());
+        let (mut b, n) = sealed[2];
+        assert_eq!(server.open(&mut b[..n]), Err(Error::Replayed));
+        let (mut b, n) = sealed[10];
+        assert!(server.open(&mut b[..n]).is_ok());
+    }
+
+    #[test]
+    fn sessions_are_separate() {
+        let (mut client, _) = handshake(&PSK).unwrap();
+
+        // Same key, client random, and session ID, but a different server
+        // random, so different session keys.
+        let mut buf = [0u8; 64];
+        let (mut server, _) =
+            respond(&PSK, &[1; RANDOM_SIZE], 7, &[3; RANDOM_SIZE], &mut buf)
+                .unwrap();
+
+        let (mut b, n) = seal(&mut client, b"hello");
+        assert_eq!(server.open(&mut b[..n]), Err(Error::BadTag));
+    }
+}
@@ -0,0 +1,496 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The server's side of a socket:  answering hellos, and keeping track of the
+//! sessions that they lead to.
+//!
+//! A session that we've answered a hello for is _pending_ until the client's
+//! first data message opens, which proves that the client knows the key; only
+//! then does it take one of the server's session slots (evicting the least
+//! recently used session if need be), so a flood of hellos can't evict
+//! established sessions.  Pending sessions have slots of their own, one per
+//! endpoint.  A client that sends another hello replaces its own pending
+//! session, but another endpoint's hello only displaces a pending session
+//! once it has been pending for `PENDING_TIMEOUT` ms, so spoofed hellos can't
+//! wipe out a handshake that's in progress.
+//!
+//! Answering a hello takes random numbers and derives session keys, so each
+//! source address is answered at most once every `HELLO_INTERVAL` ms, and
+//! the rest are dropped unread.  The limit is kept for the `HELLO_SOURCES`
+//! sources that we've answered most recently, so a flood from one source
+//! doesn't hold up the others; a flood from many (spoofed) sources can still
+//! delay new sessions, but can't break established ones.
+
+use crate::{parse, respond, Error, Message, Session, KEY_SIZE, RANDOM_SIZE};
+use core::ops::Range;
+
+/// Shortest time (in ms) between hellos from a source that we answer.
+pub const HELLO_INTERVAL: u64 = 100;
+
+/// Number of sources whose hellos are rate-limited separately.
+pub const HELLO_SOURCES: usize = 8;
+
+/// Number of handshakes that can be pending at once.
+pub const PENDING_SLOTS: usize = 4;
+
+/// Time (in ms) after which a pending session can be displaced by a hello
+/// from another endpoint.
+pub const PENDING_TIMEOUT: u64 = 1000;
+
+/// Number of random bytes that answering a hello takes:  our random value,
+/// followed by the session ID.
+pub const HELLO_RANDOM_SIZE: usize = RANDOM_SIZE + 4;
+
+/// The address of a peer.
+pub trait Endpoint: Copy + PartialEq {
+    /// What hellos are rate-limited by, e.g. an IP address without the port
+    /// (which a flood could vary).
+    type Source: Copy + PartialEq;
+
+    fn source(&self) -> Self::Source;
+}
+
+struct Peer<E> {
+    endpoint: E,
+    session: Session,
+    /// time (in ms) at which we last received from the peer, or answered its
+    /// hello
+    last_used: u64,
+}
+
+/// What to do with a packet received by a server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Received {
+    /// The packet was a data message, whose plaintext is now in this range
+    /// of the buffer
+    Data(Range<usize>),
+    /// The packet was a hello, and the first `n` bytes of the buffer now hold
+    /// the reply to send back
+    Reply(usize),
+    /// The packet should be dropped
+    Rejected,
+}
+
+/// The sessions of a server with room for up to `N` of them.
+pub struct Server<E: Endpoint, const N: usize> {
+    /// each recent source, and the time (in ms) at which we last answered
+    /// its hello
+    hellos: [Option<(E::Source, u64)>; HELLO_SOURCES],
+    pending: [Option<Peer<E>>; PENDING_SLOTS],
+    peers: [Option<Peer<E>>; N],
+}
+
+impl<E: Endpoint, const N: usize> Server<E, N> {
+    const NO_PEER: Option<Peer<E>> = None;
+
+    pub fn new() -> Self {
+        Self {
+            hellos: [None; HELLO_SOURCES],
+            pending: [Self::NO_PEER; PENDING_SLOTS],
+            peers: [Self::NO_PEER; N],
+        }
+    }
+
+    /// Handles the packet of `len` bytes at the start of `buf` from `from`,
+    /// received at `now` (in ms), with the pre-shared key `psk` and a limit
+    /// of `sessions` sessions (at most `N`).  Answering a hello takes
+    /// `HELLO_RANDOM_SIZE` bytes from `random`, which returns false if it
+    /// can't provide them.  (Any reply is written over the packet, so `buf`
+    /// should be larger than it.)
+    #[allow(clippy::too_many_arguments)]
+    pub fn receive(
+        &mut self,
+        psk: &[u8; KEY_SIZE],
+        sessions: usize,
+        from: E,
+        buf: &mut [u8],
+        len: usize,
+        now: u64,
+        random: impl FnOnce(&mut [u8; HELLO_RANDOM_SIZE]) -> bool,
+    ) -> Received {
+        match parse(&buf[..len]) {
+            Ok(Message::Hello { client_random }) => {
+                let client_random = *client_random;
+                self.hello(psk, from, &client_random, buf, now, random)
+            }
+            Ok(Message::Data { session_id }) => {
+                self.data(sessions, from, session_id, &mut buf[..len], now)
+            }
+            _ => Received::Rejected,
+        }
+    }
+
+    fn hello(
+        &mut self,
+        psk: &[u8; KEY_SIZE],
+        from: E,
+        client_random: &[u8; RANDOM_SIZE],
+        buf: &mut [u8],
+        now: u64,
+        random: impl FnOnce(&mut [u8; HELLO_RANDOM_SIZE]) -> bool,
+    ) -> Received {
+        // Check that there's a slot for the handshake and our rate limit
+        // before doing any work.
+        let slot = match self.pending_slot(from, now) {
+            Some(slot) => slot,
+            None => return Received::Rejected,
+        };
+        if !self.allow_hello(from.source(), now) {
+            return Received::Rejected;
+        }
+
+        let mut random_bytes = [0; HELLO_RANDOM_SIZE];
+        if !random(&mut random_bytes) {
+            return Received::Rejected;
+        }
+        let mut server_random = [0; RANDOM_SIZE];
+        server_random.copy_from_slice(&random_bytes[..RANDOM_SIZE]);
+        let session_id = u32::from_le_bytes([
+            random_bytes[RANDOM_SIZE],
+            random_bytes[RANDOM_SIZE + 1],
+            random_bytes[RANDOM_SIZE + 2],
+            random_bytes[RANDOM_SIZE + 3],
+        ]);
+
+        match respond(psk, client_random, session_id, &server_random, buf) {
+            Ok((session, n)) => {
+                self.pending[slot] = Some(Peer {
+                    endpoint: from,
+                    session,
+                    last_used: now,
+                });
+                Received::Reply(n)
+            }
+            Err(_) => Received::Rejected,
+        }
+    }
+
+    /// Finds the pending slot for a handshake with `from`:  its own, or else
+    /// an empty one, or else the oldest one if it has timed out.
+    fn pending_slot(&self, from: E, now: u64) -> Option<usize> {
+        let slots = &self.pending;
+        slots
+            .iter()
+            .position(|p| matches!(p, Some(p) if p.endpoint == from))
+            .or_else(|| slots.iter().position(Option::is_none))
+            .or_else(|| {
+                slots
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, p)| p.as_ref().map(|p| (i, p.last_used)))
+                    .filter(|(_, t)| now >= t.saturating_add(PENDING_TIMEOUT))
+                    .min_by_key(|(_, t)| *t)
+                    .map(|(i, _)| i)
+            })
+    }
+
+    /// Applies the rate limit to a hello from `source`, recording it if it's
+    /// allowed.  A new source takes an empty entry, or else that of the
+    /// source that we answered longest ago, as long as its interval is up
+    /// (so that new sources can't reset each other's limits).
+    fn allow_hello(&mut self, source: E::Source, now: u64) -> bool {
+        let hellos = &mut self.hellos;
+        let i = hellos
+            .iter()
+            .position(|h| matches!(h, Some((s, _)) if *s == source))
+            .or_else(|| hellos.iter().position(Option::is_none))
+            .or_else(|| {
+                hellos
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, h)| h.map_or(0, |(_, t)| t))
+                    .map(|(i, _)| i)
+            })
+            .unwrap();
+        if let Some((_, last)) = hellos[i] {
+            if now < last.saturating_add(HELLO_INTERVAL) {
+                return false;
+            }
+        }
+        hellos[i] = Some((source, now));
+        true
+    }
+
+    fn data(
+        &mut self,
+        sessions: usize,
+        from: E,
+        session_id: u32,
+        buf: &mut [u8],
+        now: u64,
+    ) -> Received {
+        let is_ours =
+            |p: &Peer<E>| p.endpoint == from && p.session.id() == session_id;
+
+        if let Some(peer) = self.peers.iter_mut().flatten().find(|p| is_ours(p))
+        {
+            return match peer.session.open(buf) {
+                Ok(range) => {
+                    peer.last_used = now;
+                    Received::Data(range)
+                }
+                Err(_) => Received::Rejected,
+            };
+        }
+
+        let slot = match self
+            .pending
+            .iter()
+            .position(|p| matches!(p, Some(p) if is_ours(p)))
+        {
+            Some(slot) => slot,
+            None => return Received::Rejected,
+        };
+        let peer = self.pending[slot].as_mut().unwrap();
+        match peer.session.open(buf) {
+            Ok(range) => {
+                let mut peer = self.pending[slot].take().unwrap();
+                peer.last_used = now;
+                self.establish(peer, sessions);
+                Received::Data(range)
+            }
+            Err(_) => Received::Rejected,
+        }
+    }
+
+    /// Gives `peer` a session slot, replacing any earlier session with the
+    /// same endpoint, or else an empty slot, or else the least recently used
+    /// session.
+    fn establish(&mut self, peer: Peer<E>, sessions: usize) {
+        let slots = &mut self.peers[..sessions.min(N)];
+        let i = slots
+            .iter()
+            .position(|p| matches!(p, Some(p) if p.endpoint == peer.endpoint))
+            .or_else(|| slots.iter().position(Option::is_none))
+            .or_else(|| {
+                slots
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, p)| p.as_ref().map_or(0, |p| p.last_used))
+                    .map(|(i, _)| i)
+            })
+            .unwrap();
+        slots[i] = Some(peer);
+    }
+
+    /// Seals the plaintext at `buf[DATA_HEADER_SIZE..][..len]` for the
+    /// session with `to`, returning the length of the sealed message.
+    pub fn seal(
+        &mut self,
+        to: E,
+        buf: &mut [u8],
+        len: usize,
+    ) -> Result<usize, Error> {
+        let peer = self
+            .peers
+            .iter_mut()
+            .flatten()
+            .find(|p| p.endpoint == to)
+            .ok_or(Error::NoSession)?;
+        peer.session.seal(buf, len)
+    }
+}
+
+impl<E: Endpoint, const N: usize> Default for Server<E, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Handshake, DATA_HEADER_SIZE};
+
+    const PSK: [u8; KEY_SIZE] = [0x42; KEY_SIZE];
+
+    /// An address and a port.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    struct Ep(u8, u16);
+
+    impl Endpoint for Ep {
+        type Source = u8;
+
+        fn source(&self) -> u8 {
+            self.0
+        }
+    }
+
+    type TestServer = Server<Ep, 2>;
+
+    fn random(buf: &mut [u8; HELLO_RANDOM_SIZE]) -> bool {
+        buf.iter_mut().for_each(|b| *b = 7);
+        true
+    }
+
+    /// Sends a hello from `from` at `now`, returning the client's handshake
+    /// and the reply to it.
+    fn hello(
+        server: &mut TestServer,
+        from: Ep,
+        now: u64,
+    ) -> (Handshake, [u8; 128], Received) {
+        let client = Handshake::new(&PSK, &[from.0; RANDOM_SIZE]);
+        let mut buf = [0u8; 128];
+        let n = client.hello(&mut buf).unwrap();
+        let r = server.receive(&PSK, 2, from, &mut buf, n, now, random);
+        (client, buf, r)
+    }
+
+    /// Completes a handshake from `from` at `now`, returning the client's
+    /// side of the session.
+    fn connect(server: &mut TestServer, from: Ep, now: u64) -> Session {
+        let (client, reply, r) = hello(server, from, now);
+        let n = match r {
+            Received::Reply(n) => n,
+            r => panic!("unexpected {:?}", r),
+        };
+        client.finish(&reply[..n]).unwrap()
+    }
+
+    fn seal(session: &mut Session, msg: &[u8]) -> ([u8; 128], usize) {
+        let mut buf = [0u8; 128];
+        buf[DATA_HEADER_SIZE..][..msg.len()].copy_from_slice(msg);
+        let n = session.seal(&mut buf, msg.len()).unwrap();
+        (buf, n)
+    }
+
+    /// Sends `msg` to the server from `from` at `now`, returning true if the
+    /// server opened it.
+    fn send(
+        server: &mut TestServer,
+        client: &mut Session,
+        from: Ep,
+        msg: &[u8],
+        now: u64,
+    ) -> bool {
+        let (mut buf, n) = seal(client, msg);
+        match server.receive(&PSK, 2, from, &mut buf, n, now, random) {
+            Received::Data(r) => &buf[r] == msg,
+            _ => false,
+        }
+    }
+
+    /// Checks that the server still has a session with `to` that `client`
+    /// can open.
+    fn has_session(server: &mut TestServer, client: &mut Session, to: Ep) {
+        let mut buf = [0u8; 128];
+        buf[DATA_HEADER_SIZE..][..5].copy_from_slice(b"reply");
+        let n = server.seal(to, &mut buf, 5).unwrap();
+        let r = client.open(&mut buf[..n]).unwrap();
+        assert_eq!(&buf[r], b"reply");
+    }
+
+    #[test]
+    fn hello_data_establish() {
+        let mut server = TestServer::new();
+        let a = Ep(1, 1000);
+        let mut client = connect(&mut server, a, 0);
+
+        // Until the first data message opens, the session is pending.
+        assert_eq!(server.seal(a, &mut [0; 128], 0), Err(Error::NoSession));
+        assert!(send(&mut server, &mut client, a, b"hi", 1));
+        has_session(&mut server, &mut client, a);
+        assert!(send(&mut server, &mut client, a, b"2", 2));
+
+        // The session belongs to its endpoint.
+        assert!(!send(&mut server, &mut client, Ep(1, 1001), b"x", 3));
+    }
+
+    #[test]
+    fn replay() {
+        let mut server = TestServer::new();
+        let a = Ep(1, 1000);
+        let mut client = connect(&mut server, a, 0);
+
+        // Including the message that establishes the session.
+        let (first, n) = seal(&mut client, b"first");
+        let mut buf = first;
+        let r = server.receive(&PSK, 2, a, &mut buf, n, 1, random);
+        assert!(matches!(r, Received::Data(_)));
+        let mut buf = first;
+        let r = server.receive(&PSK, 2, a, &mut buf, n, 2, random);
+        assert_eq!(r, Received::Rejected);
+
+        let (second, n) = seal(&mut client, b"second");
+        let mut buf = second;
+        let r = server.receive(&PSK, 2, a, &mut buf, n, 3, random);
+        assert!(matches!(r, Received::Data(_)));
+        let mut buf = second;
+        let r = server.receive(&PSK, 2, a, &mut buf, n, 4, random);
+        assert_eq!(r, Received::Rejected);
+    }
+
+    #[test]
+    fn lru_eviction() {
+        let mut server = TestServer::new();
+        let (a, b, c) = (Ep(1, 1000), Ep(2, 1000), Ep(3, 1000));
+        let mut ca = connect(&mut server, a, 0);
+        let mut cb = connect(&mut server, b, 0);
+        assert!(send(&mut server, &mut ca, a, b"a", 1));
+        assert!(send(&mut server, &mut cb, b, b"b", 2));
+        // `a` is used again, so `b` is the least recently used.
+        assert!(send(&mut server, &mut ca, a, b"a", 3));
+
+        let mut cc = connect(&mut server, c, 4);
+        assert!(send(&mut server, &mut cc, c, b"c", 5));
+        has_session(&mut server, &mut ca, a);
+        has_session(&mut server, &mut cc, c);
+        assert_eq!(server.seal(b, &mut [0; 128], 0), Err(Error::NoSession));
+        assert!(!send(&mut server, &mut cb, b, b"b", 6));
+    }
+
+    #[test]
+    fn spoofed_hellos_keep_pending_sessions() {
+        let mut server = TestServer::new();
+        let a = Ep(1, 1000);
+        let mut client = connect(&mut server, a, 0);
+
+        // Hellos from other sources fill the remaining pending slots, and
+        // then are dropped rather than displacing `a`'s handshake.
+        for i in 0..PENDING_SLOTS as u8 {
+            let (_, _, r) = hello(&mut server, Ep(10 + i, 1000), 1);
+            let expected = i < PENDING_SLOTS as u8 - 1;
+            assert_eq!(matches!(r, Received::Reply(_)), expected, "{}", i);
+        }
+        assert!(send(&mut server, &mut client, a, b"hi", 2));
+
+        // Establishing `a`'s session freed its pending slot.
+        let (_, _, r) = hello(&mut server, Ep(20, 1000), 2);
+        assert!(matches!(r, Received::Reply(_)));
+        let (_, _, r) = hello(&mut server, Ep(21, 1000), 3);
+        assert_eq!(r, Received::Rejected);
+
+        // Once pending sessions have timed out, they can be displaced.
+        let (_, _, r) = hello(&mut server, Ep(21, 1000), PENDING_TIMEOUT + 1);
+        assert!(matches!(r, Received::Reply(_)));
+    }
+
+    #[test]
+    fn hellos_are_limited_per_source() {
+        let mut server = TestServer::new();
+
+        let (_, _, r) = hello(&mut server, Ep(1, 1000), 0);
+        assert!(matches!(r, Received::Reply(_)));
+        // A flood from one source (even from other ports) is dropped...
+        let (_, _, r) = hello(&mut server, Ep(1, 1001), 10);
+        assert_eq!(r, Received::Rejected);
+        // ...without holding up another source.
+        let (_, _, r) = hello(&mut server, Ep(2, 1000), 10);
+        assert!(matches!(r, Received::Reply(_)));
+        // The first source is answered again once its interval is up.
+        let (_, _, r) = hello(&mut server, Ep(1, 1001), HELLO_INTERVAL);
+        assert!(matches!(r, Received::Reply(_)));
+
+        // A flood from more sources than we can track can't reset the
+        // limits of the sources that we're tracking.
+        let mut server = TestServer::new();
+        for i in 0..HELLO_SOURCES as u8 {
+            assert!(server.allow_hello(i, 0));
+        }
+        assert!(!server.allow_hello(100, 1));
+        assert!(!server.allow_hello(0, 1));
+        assert!(server.allow_hello(100, HELLO_INTERVAL));
+    }
+}
@@ -0,0 +1,429 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Vital product data (VPD) stored in a FRU ID EEPROM.
+//!
+//! The VPD is a simple tag-length-value format.  It starts with a 12-byte
+//! header:
+//!
+//! | Offset | Size | Contents                                  |
+//! |--------|------|-------------------------------------------|
+//! | 0      | 4    | `VPD_MAGIC`                               |
+//! | 4      | 4    | length of the records that follow (LE)    |
+//! | 8      | 4    | CRC-32 (IEEE) of the records that follow  |
+//!
+//! Each record is a 4-byte ASCII tag, a little-endian 16-bit length, and
+//! that many bytes of data.  Tags that we don't know about are ignored, so
+//! new ones can be added without breaking older parsers; the well-known tags
+//! are defined below.
+
+#![no_std]
+
+use core::convert::TryInto;
+
+/// Magic bytes at the start of the VPD.
+pub const VPD_MAGIC: [u8; 4] = *b"OVPD";
+
+/// Size of the VPD header.
+pub const HEADER_SIZE: usize = 12;
+
+/// Size of the header of each record.
+pub const RECORD_HEADER_SIZE: usize = 6;
+
+/// Board serial number (ASCII).
+pub const TAG_SERIAL: Tag = *b"SER0";
+
+/// Board part number (ASCII).
+pub const TAG_PART: Tag = *b"PRT0";
+
+/// Board revision (little-endian `u32`).
+pub const TAG_REVISION: Tag = *b"REV0";
+
+/// Base MAC address (6 bytes) followed by the number of consecutive
+/// addresses allocated to the board (little-endian `u16`) and, optionally, the
+/// stride (`u8`) between the addresses that are actually used.  If the stride
+/// is absent, it is 1.
+pub const TAG_MAC: Tag = *b"MAC0";
+
+/// A pre-shared key (`PSK_SIZE` bytes) followed by its name (ASCII), e.g.
+/// for the `net` task's secure sockets.  There may be several of these, with
+/// different names.
+pub const TAG_PSK: Tag = *b"PSK0";
+
+/// Size of a pre-shared key.
+pub const PSK_SIZE: usize = 32;
+
+pub type Tag = [u8; 4];
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VpdError {
+    /// The data is too short to contain what its header claims
+    Truncated,
+    /// The magic bytes are wrong, e.g. because the EEPROM is blank
+    BadMagic,
+    /// The records don't match the CRC in the header
+    BadChecksum,
+    /// A record runs past the end of the VPD
+    BadRecord,
+    /// A record has the wrong length for its tag
+    BadLength(Tag),
+    /// There is no space for the encoded VPD in the output buffer
+    NoSpace,
+}
+
+/// A single record.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Record<'a> {
+    pub tag: Tag,
+    pub data: &'a [u8],
+}
+
+/// A block of MAC addresses allocated to the board.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MacBlock {
+    pub base: [u8; 6],
+    pub count: u16,
+    pub stride: u8,
+}
+
+impl MacBlock {
+    /// Returns the number of addresses that can be used, given the stride.
+    pub fn usable(&self) -> u16 {
+        match self.stride {
+            0 => 0,
+            // Round up: the first address of each stride must be in the block
+            stride => {
+                ((self.count as u32 + stride as u32 - 1) / stride as u32) as u16
+            }
+        }
+    }
+
+    /// Returns the `n`th usable address in the block, if there is one.
+    pub fn nth(&self, n: u16) -> Option<[u8; 6]> {
+        if n >= self.usable() {
+            return None;
+        }
+
+        Some(mac_add(self.base, n as u64 * self.stride as u64))
+    }
+}
+
+/// Adds `offset` to the MAC address `base`, treating it as a 48-bit integer.
+pub fn mac_add(base: [u8; 6], offset: u64) -> [u8; 6] {
+    let mut bytes = [0; 8];
+    bytes[2..].copy_from_slice(&base);
+    let mac = u64::from_be_bytes(bytes).wrapping_add(offset);
+    mac.to_be_bytes()[2..].try_into().unwrap()
+}
+
+/// Validated VPD, borrowed from the buffer it was read into.
+#[derive(Copy, Clone, Debug)]
+pub struct Vpd<'a> {
+    records: &'a [u8],
+}
+
+impl<'a> Vpd<'a> {
+    ///
+    /// Validates the header and checksum of the VPD in `buf`, which may be
+    /// longer than the VPD itself (e.g. the entire contents of the EEPROM).
+    ///
+    pub fn parse(buf: &'a [u8]) -> Result<Self, VpdError> {
+        if buf.len() < HEADER_SIZE {
+            return Err(VpdError::Truncated);
+        }
+
+        if buf[0..4] != VPD_MAGIC {
+            return Err(VpdError::BadMagic);
+        }
+
+        let len = u32::from_le_bytes(buf[4..8].try_into().unwrap()) as usize;
+        let crc = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+
+        let records = buf
+            .get(HEADER_SIZE..)
+            .and_then(|b| b.get(..len))
+            .ok_or(VpdError::Truncated)?;
+
+        if crc32(records) != crc {
+            return Err(VpdError::BadChecksum);
+        }
+
+        let vpd = Self { records };
+
+        // Walk the records once, so that iteration can't fail later.
+        let mut rest = records;
+
+        while !rest.is_empty() {
+            let (_, next) = split_record(rest)?;
+            rest = next;
+        }
+
+        Ok(vpd)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Record<'a>> {
+        let mut rest = self.records;
+
+        core::iter::from_fn(move || {
+            let (record, next) = split_record(rest).ok()?;
+            rest = next;
+            Some(record)
+        })
+    }
+
+    /// Returns the data of the first record with the given tag.
+    pub fn get(&self, tag: Tag) -> Option<&'a [u8]> {
+        self.iter().find(|r| r.tag == tag).map(|r| r.data)
+    }
+
+    pub fn serial(&self) -> Option<&'a [u8]> {
+        self.get(TAG_SERIAL)
+    }
+
+    pub fn part(&self) -> Option<&'a [u8]> {
+        self.get(TAG_PART)
+    }
+
+    pub fn revision(&self) -> Result<Option<u32>, VpdError> {
+        match self.get(TAG_REVISION) {
+            Some(data) => data
+                .try_into()
+                .map(|d| Some(u32::from_le_bytes(d)))
+                .map_err(|_| VpdError::BadLength(TAG_REVISION)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn mac(&self) -> Result<Option<MacBlock>, VpdError> {
+        match self.get(TAG_MAC) {
+            Some(data) if data.len() == 8 || data.len() == 9 => {
+                Ok(Some(MacBlock {
+                    base: data[0..6].try_into().unwrap(),
+                    count: u16::from_le_bytes(data[6..8].try_into().unwrap()),
+                    stride: data.get(8).cloned().unwrap_or(1),
+                }))
+            }
+            Some(_) => Err(VpdError::BadLength(TAG_MAC)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the pre-shared key called `name`, if there is one.
+    pub fn psk(
+        &self,
+        name: &[u8],
+    ) -> Result<Option<&'a [u8; PSK_SIZE]>, VpdError> {
+        for r in self.iter().filter(|r| r.tag == TAG_PSK) {
+            if r.data.len() < PSK_SIZE {
+                return Err(VpdError::BadLength(TAG_PSK));
+            }
+            let (key, n) = r.data.split_at(PSK_SIZE);
+            if n == name {
+                return Ok(Some(key.try_into().unwrap()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn split_record(buf: &[u8]) -> Result<(Record<'_>, &[u8]), VpdError> {
+    if buf.len() < RECORD_HEADER_SIZE {
+        return Err(VpdError::BadRecord);
+    }
+
+    let tag = buf[0..4].try_into().unwrap();
+    let len = u16::from_le_bytes(buf[4..6].try_into().unwrap()) as usize;
+    let rest = &buf[RECORD_HEADER_SIZE..];
+
+    if rest.len() < len {
+        return Err(VpdError::BadRecord);
+    }
+
+    let (data, rest) = rest.split_at(len);
+    Ok((Record { tag, data }, rest))
+}
+
+///
+/// Encodes `records` as VPD into `out`, returning the number of bytes used.
+///
+pub fn encode(
+    records: &[Record<'_>],
+    out: &mut [u8],
+) -> Result<usize, VpdError> {
+    let mut pos = HEADER_SIZE;
+
+    for r in records {
+        let len: u16 = r
+            .data
+            .len()
+            .try_into()
+            .map_err(|_| VpdError::BadLength(r.tag))?;
+        let end = pos + RECORD_HEADER_SIZE + r.data.len();
+        let dest = out.get_mut(pos..end).ok_or(VpdError::NoSpace)?;
+
+        dest[0..4].copy_from_slice(&r.tag);
+        dest[4..6].copy_from_slice(&len.to_le_bytes());
+        dest[RECORD_HEADER_SIZE..].copy_from_slice(r.data);
+        pos = end;
+    }
+
+    if out.len() < HEADER_SIZE {
+        return Err(VpdError::NoSpace);
+    }
+
+    let len = (pos - HEADER_SIZE) as u32;
+    let crc = crc32(&out[HEADER_SIZE..pos]);
+
+    out[0..4].copy_from_slice(&VPD_MAGIC);
+    out[4..8].copy_from_slice(&len.to_le_bytes());
+    out[8..12].copy_from_slice(&crc.to_le_bytes());
+
+    Ok(pos)
+}
+
+/// CRC-32 (IEEE 802.3), computed bitwise: the VPD is small and read rarely.
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mac = [0xa8, 0x40, 0x25, 0x00, 0x00, 0x10, 0x08, 0x00];
+        let records = [
+            Record {
+                tag: TAG_SERIAL,
+                data: b"BRM42220001",
+            },
+            Record {
+                tag: *b"XYZ9",
+                data: &[1, 2, 3],
+            },
+            Record {
+                tag: TAG_REVISION,
+                data: &3u32.to_le_bytes(),
+            },
+            Record {
+                tag: TAG_MAC,
+                data: &mac,
+            },
+        ];
+
+        let mut buf = [0xffu8; 128];
+        let len = encode(&records, &mut buf).unwrap();
+        let vpd = Vpd::parse(&buf).unwrap();
+
+        assert_eq!(len, HEADER_SIZE + 4 * RECORD_HEADER_SIZE + 11 + 3 + 4 + 8);
+        assert_eq!(vpd.iter().count(), 4);
+        assert_eq!(vpd.serial(), Some(&b"BRM42220001"[..]));
+        assert_eq!(vpd.part(), None);
+        assert_eq!(vpd.revision(), Ok(Some(3)));
+        assert_eq!(
+            vpd.mac(),
+            Ok(Some(MacBlock {
+                base: [0xa8, 0x40, 0x25, 0x00, 0x00, 0x10],
+                count: 8,
+                stride: 1,
+            }))
+        );
+    }
+
+    #[test]
+    fn psk() {
+        let mut one = [0x11u8; PSK_SIZE + 4];
+        one[PSK_SIZE..].copy_from_slice(b"MGMT");
+        let mut two = [0x22u8; PSK_SIZE + 3];
+        two[PSK_SIZE..].copy_from_slice(b"DBG");
+        let records = [
+            Record {
+                tag: TAG_PSK,
+                data: &one,
+            },
+            Record {
+                tag: TAG_PSK,
+                data: &two,
+            },
+        ];
+
+        let mut buf = [0u8; 128];
+        encode(&records, &mut buf).unwrap();
+        let vpd = Vpd::parse(&buf).unwrap();
+
+        assert_eq!(vpd.psk(b"MGMT"), Ok(Some(&[0x11; PSK_SIZE])));
+        assert_eq!(vpd.psk(b"DBG"), Ok(Some(&[0x22; PSK_SIZE])));
+        assert_eq!(vpd.psk(b"MGM"), Ok(None));
+
+        let short = [Record {
+            tag: TAG_PSK,
+            data: &[0; PSK_SIZE - 1],
+        }];
+        encode(&short, &mut buf).unwrap();
+        assert_eq!(
+            Vpd::parse(&buf).unwrap().psk(b""),
+            Err(VpdError::BadLength(TAG_PSK))
+        );
+    }
+
+    #[test]
+    fn mac_block() {
+        let records = [Record {
+            tag: TAG_MAC,
+            data: &[0xa8, 0x40, 0x25, 0x00, 0x00, 0xfe, 0x07, 0x00, 0x02],
+        }];
+        let mut buf = [0u8; 32];
+        encode(&records, &mut buf).unwrap();
+
+        let block = Vpd::parse(&buf).unwrap().mac().unwrap().unwrap();
+        assert_eq!(block.stride, 2);
+        assert_eq!(block.usable(), 4);
+        assert_eq!(block.nth(0), Some([0xa8, 0x40, 0x25, 0x00, 0x00, 0xfe]));
+        assert_eq!(block.nth(1), Some([0xa8, 0x40, 0x25, 0x00, 0x01, 0x00]));
+        assert_eq!(block.nth(3), Some([0xa8, 0x40, 0x25, 0x00, 0x01, 0x04]));
+        assert_eq!(block.nth(4), None);
+    }
+
+    #[test]
+    fn errors() {
+        let records = [Record {
+            tag: TAG_MAC,
+            data: &[1, 2, 3],
+        }];
+        let mut buf = [0u8; 32];
+        let len = encode(&records, &mut buf).unwrap();
+
+        assert_eq!(
+            Vpd::parse(&buf).unwrap().mac(),
+            Err(VpdError::BadLength(TAG_MAC))
+        );
+        assert_eq!(
+            Vpd::parse(&buf[..len - 1]).err(),
+            Some(VpdError::Truncated)
+        );
+        assert_eq!(Vpd::parse(&[0xff; 32]).err(), Some(VpdError::BadMagic));
+
+        buf[len - 1] ^= 1;
+        assert_eq!(Vpd::parse(&buf).err(), Some(VpdError::BadChecksum));
+
+        assert_eq!(encode(&records, &mut buf[..10]), Err(VpdError::NoSpace));
+    }
+}
@@ -16,6 +16,34 @@ pub enum NetError {
     QueueEmpty = 1,
     NotYours = 2,
     InvalidVLan = 3,
+    /// The operation doesn't apply to this kind of socket (e.g. a UDP
+    /// operation on a TCP socket)
+    WrongSocketKind = 4,
+    /// The socket is already listening or connected
+    InvalidState = 5,
+    /// The socket has no connection
+    NotConnected = 6,
+    /// The remote end has closed the connection, and all data sent before
+    /// it did so has been read
+    ConnectionClosed = 7,
+    /// The remote endpoint can't be connected to
+    Unaddressable = 8,
+    /// The operation isn't supported on this board (e.g. management network
+    /// status on a board without a management network)
+    NotSupported = 9,
+    /// The address isn't a multicast group
+    NotMulticast = 10,
+    /// Sockets are already members of as many multicast groups as we can
+    /// track
+    GroupTableFull = 11,
+    /// The socket is secure, and has no session with the remote endpoint
+    NoSession = 12,
+    /// There is no entry at the given index
+    NoSuchEntry = 13,
+    /// The socket's received payloads aren't loaned to its owner
+    NotLoaned = 14,
+    /// The packet being released isn't the one at the head of the queue
+    WrongPacket = 15,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
@@ -28,6 +56,30 @@ pub struct UdpMetadata {
     pub vid: u16,
 }
 
+/// A received packet whose payload is loaned to the socket's owner, in place
+/// in the socket's shared region, until it's released.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct LoanedPacket {
+    pub metadata: UdpMetadata,
+    /// Address of the payload, which is `metadata.size` bytes long
+    pub payload: u32,
+}
+
+impl LoanedPacket {
+    /// Returns the payload.
+    ///
+    /// # Safety
+    ///
+    /// The socket's shared region must be in the caller's `uses`, and the
+    /// payload must not be used once the packet has been released.
+    pub unsafe fn payload(&self) -> &[u8] {
+        core::slice::from_raw_parts(
+            self.payload as *const u8,
+            self.metadata.size as usize,
+        )
+    }
+}
+
 #[cfg(feature = "use-smoltcp")]
 impl From<UdpMetadata> for smoltcp::wire::IpEndpoint {
     fn from(m: UdpMetadata) -> Self {
@@ -38,12 +90,42 @@ impl From<UdpMetadata> for smoltcp::wire::IpEndpoint {
     }
 }
 
+/// The remote end of a TCP connection.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub addr: Address,
+    pub port: u16,
+}
+
+#[cfg(feature = "use-smoltcp")]
+impl From<Endpoint> for smoltcp::wire::IpEndpoint {
+    fn from(e: Endpoint) -> Self {
+        Self {
+            addr: e.addr.into(),
+            port: e.port,
+        }
+    }
+}
+
+#[cfg(feature = "use-smoltcp")]
+impl TryFrom<smoltcp::wire::IpEndpoint> for Endpoint {
+    type Error = AddressUnspecified;
+
+    fn try_from(e: smoltcp::wire::IpEndpoint) -> Result<Self, Self::Error> {
+        Ok(Self {
+            addr: e.addr.try_into()?,
+            port: e.port,
+        })
+    }
+}
+
 // This must be repr(C); otherwise Rust cleverly optimizes out the enum tag,
 // which breaks ssmarshal's assumptions about struct sizes.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 #[repr(C)]
 pub enum Address {
     Ipv6(Ipv6Address),
+    Ipv4(Ipv4Address),
 }
 
 #[cfg(feature = "use-smoltcp")]
@@ -51,6 +133,7 @@ impl From<Address> for smoltcp::wire::IpAddress {
     fn from(a: Address) -> Self {
         match a {
             Address::Ipv6(a) => Self::Ipv6(a.into()),
+            Address::Ipv4(a) => Self::Ipv4(a.into()),
         }
     }
 }
@@ -64,6 +147,7 @@ impl TryFrom<smoltcp::wire::IpAddress> for Address {
 
         match a {
             IpAddress::Ipv6(a) => Ok(Self::Ipv6(a.into())),
+            IpAddress::Ipv4(a) => Ok(Self::Ipv4(a.into())),
             _ => Err(AddressUnspecified),
         }
     }
@@ -90,5 +174,192 @@ impl From<Ipv6Address> for smoltcp::wire::Ipv6Address {
     }
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Ipv4Address(pub [u8; 4]);
+
+#[cfg(feature = "use-smoltcp")]
+impl From<smoltcp::wire::Ipv4Address> for Ipv4Address {
+    fn from(a: smoltcp::wire::Ipv4Address) -> Self {
+        Self(a.0)
+    }
+}
+
+#[cfg(feature = "use-smoltcp")]
+impl From<Ipv4Address> for smoltcp::wire::Ipv4Address {
+    fn from(a: Ipv4Address) -> Self {
+        Self(a.0)
+    }
+}
+
+/// The IPv4 configuration of the interface, whether static or from DHCP.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct Ipv4Interface {
+    pub address: Ipv4Address,
+    pub prefix: u8,
+    pub gateway: Option<Ipv4Address>,
+}
+
+/// The addresses of the interface, as returned by `Net::addresses`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct InterfaceAddresses {
+    /// Link-local IPv6 address
+    pub ipv6: Ipv6Address,
+    /// IPv4 configuration, if IPv4 is configured (and, if we are using DHCP,
+    /// once we have been assigned an address)
+    pub ipv4: Option<Ipv4Interface>,
+    /// true if IPv4 is configured via DHCP
+    pub dhcp: bool,
+}
+
+/// Where the interface's MAC addresses came from
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum MacAddressSource {
+    /// Derived from a hash of the microcontroller's unique ID, as a
+    /// locally-administered address.  This is the fallback if there is no
+    /// other source.
+    Uid,
+    /// Read from the vital product data in the board's FRU ID EEPROM
+    Vpd,
+    /// Read from vital product data in a config area of the
+    /// microcontroller's flash
+    Flash,
+}
+
+/// The MAC addresses used by the interface, as returned by
+/// `Net::mac_addresses`.
+///
+/// There is one address per VLAN (or only one, without VLANs): VLAN `i`,
+/// counting from the start of the VLAN range, uses `base + i * stride`.  The
+/// link-local IPv6 address of each VLAN is derived from its MAC address.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct MacAddressBlock {
+    pub base: [u8; 6],
+    pub count: u16,
+    pub stride: u8,
+    pub source: MacAddressSource,
+}
+
+impl MacAddressBlock {
+    /// Returns the `n`th address in use, if there is one.
+    pub fn nth(&self, n: u16) -> Option<[u8; 6]> {
+        if n >= self.count {
+            return None;
+        }
+
+        Some(vpd::mac_add(self.base, n as u64 * self.stride as u64))
+    }
+}
+
+/// An entry in our approximation of the neighbor (ARP/NDP) cache, as returned
+/// by `Net::approximate_neighbor`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct NeighborEntry {
+    pub ip: Address,
+    pub mac: [u8; 6],
+    /// Milliseconds until the entry expires, or zero if it has expired (in
+    /// which case it's no longer used, but hasn't yet been evicted)
+    pub expires_in: u32,
+
+    /// VLAN whose cache holds the entry
+    #[cfg(feature = "vlan")]
+    pub vid: u16,
+}
+
+/// Counters and DMA ring occupancy for the Ethernet MAC, as returned by
+/// `Net::interface_stats`.  The counters wrap around.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InterfaceStats {
+    /// Packets handed to the DMA for transmission
+    pub tx_packets: u32,
+    /// Packets received from the DMA
+    pub rx_packets: u32,
+    /// Number of times that the TX ring filled up, counted once on each
+    /// transition into being full (not on each poll that finds it full)
+    pub tx_ring_full: u32,
+    /// Received packets that were dropped by the driver, because they had
+    /// errors or (with VLANs) an unknown VLAN tag
+    pub rx_dropped: u32,
+    /// Received packets that were dropped by the DMA because the RX ring was
+    /// full
+    pub rx_missed: u32,
+    /// Number of TX descriptors
+    pub tx_ring_len: u16,
+    /// Number of TX descriptors waiting to be transmitted
+    pub tx_ring_pending: u16,
+    /// Number of RX descriptors
+    pub rx_ring_len: u16,
+    /// Number of RX descriptors holding packets that have not yet been read
+    pub rx_ring_ready: u16,
+}
+
+/// Counters for a single socket, as returned by `Net::socket_stats`.  With
+/// VLANs, these are summed over all of the VLANs.  The counters wrap around.
+///
+/// For TCP sockets, "packets" are successful calls to `tcp_read` and
+/// `tcp_write`.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SocketStats {
+    pub rx_packets: u32,
+    pub rx_bytes: u32,
+    pub tx_packets: u32,
+    pub tx_bytes: u32,
+    /// Sends that failed because the socket's transmit queue was full
+    pub tx_overflows: u32,
+    /// Received packets that were dropped because they exceeded the
+    /// socket's rate limit
+    pub rx_rate_limited: u32,
+    /// Received packets that were dropped because the socket's receive queue
+    /// was full (the newest packet, or the oldest one if that is the
+    /// socket's drop policy)
+    pub rx_overflows: u32,
+    /// Packets that a secure socket dropped because they weren't valid
+    /// messages of an established session (including handshakes)
+    pub rx_rejected: u32,
+}
+
+// The counter enums below must be repr(C) for the same reason as `Address`.
+
+/// Value of a KSZ8463 MIB counter
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[repr(C)]
+pub enum MibCounter {
+    None,
+    Count(u32),
+    CountOverflow(u32),
+}
+
+/// Value of a VSC85x2 counter
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+#[repr(C)]
+pub enum PhyCounter {
+    Unavailable,
+    Inactive,
+    Value(u16),
+}
+
+/// Status of one port on the management network, where the KSZ8463 switch is
+/// connected to the VSC85x2 PHY over 100BASE-FX.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct ManagementPortStatus {
+    pub ksz8463_100base_fx_link_up: bool,
+    pub ksz8463_rx_bytes: MibCounter,
+    pub ksz8463_tx_bytes: MibCounter,
+
+    pub vsc85x2_100base_fx_link_up: bool,
+    pub vsc85x2_sgmii_link_up: bool,
+
+    pub vsc85x2_media_tx_good_count: PhyCounter,
+    pub vsc85x2_mac_tx_good_count: PhyCounter,
+    pub vsc85x2_media_rx_good_count: PhyCounter,
+    pub vsc85x2_mac_rx_good_count: PhyCounter,
+}
+
+/// Status of the management network, as returned by `Net::management_status`.
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct ManagementStatus {
+    pub ports: [ManagementPortStatus; 2],
+}
+
 include!(concat!(env!("OUT_DIR"), "/client_stub.rs"));
 include!(concat!(env!("OUT_DIR"), "/net_config.rs"));
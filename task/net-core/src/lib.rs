@@ -0,0 +1,27 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Parts of the `net` task that don't depend on the hardware or the kernel.
+//!
+//! The server's interfaces and UDP sockets are kept in [Sockets], which is
+//! generic over the _smoltcp_ device under the interfaces.  With VLANs, that
+//! device reaches the MAC through the [VLanMac] trait (implemented for the
+//! STM32H7 driver with the `stm32h7-vlan` feature).  The kernel is reached
+//! through the [Notifier] trait, so that this crate builds on the host.  Its
+//! tests run the server's sockets over a fake MAC (and over _smoltcp_'s
+//! loopback device), to check socket ownership, VLAN filtering and
+//! notifications without a board.
+
+#![cfg_attr(not(test), no_std)]
+
+mod owners;
+mod sockets;
+mod vlan;
+
+#[cfg(test)]
+mod tests;
+
+pub use owners::{check_owner, NotYours, Notifier, Owner};
+pub use sockets::{Error, SocketKind, Sockets};
+pub use vlan::{VLanEthernet, VLanMac, VLanRxToken, VLanTxToken};
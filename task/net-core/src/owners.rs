@@ -0,0 +1,52 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Socket ownership and notifications.
+
+/// The task that owns a socket.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Owner {
+    /// index of the task
+    pub task: usize,
+    /// notification bits that we post to the task when the socket has work
+    /// for it
+    pub notification: u32,
+}
+
+/// Error returned when a task uses a socket that it doesn't own.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NotYours;
+
+/// Posts notifications to tasks.
+pub trait Notifier {
+    fn post(&mut self, task: usize, notification: u32);
+}
+
+/// Checks that the task with index `sender` owns socket `socket`, returning
+/// the socket's index.
+pub fn check_owner(
+    owners: &[Owner],
+    sender: usize,
+    socket: usize,
+) -> Result<usize, NotYours> {
+    match owners.get(socket) {
+        Some(owner) if owner.task == sender => Ok(socket),
+        _ => Err(NotYours),
+    }
+}
+
+/// Notifies the owner of each socket for which `ready` returns true.
+pub(crate) fn wake_sockets(
+    owners: &[Owner],
+    mut ready: impl FnMut(usize) -> bool,
+    notifier: &mut impl Notifier,
+) {
+    for (i, owner) in owners.iter().enumerate() {
+        if ready(i) {
+            // This can cause spurious wakeups if the owner is already
+            // waiting in our incoming queue, which is harmless.
+            notifier.post(owner.task, owner.notification);
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! The server's interfaces and sockets, and the IPC operations on its UDP
+//! sockets.
+//!
+//! The server has an interface for each VLAN (or a single interface without
+//! VLANs), each with a copy of every socket.  [Sockets] is generic over the
+//! _smoltcp_ device under the interfaces, so that the same code runs over
+//! the MAC in the `net` task and over a fake MAC in our tests; the `net`
+//! task's Idol operations on UDP sockets are thin wrappers around
+//! [Sockets::recv] and [Sockets::send], which take closures in place of the
+//! leases, and around [Sockets::peek] and [Sockets::release], with which a
+//! socket's owner reads packets in place.
+
+use core::ops::Range;
+use smoltcp::iface::{Interface, SocketHandle};
+use smoltcp::phy::Device;
+use smoltcp::socket::UdpSocket;
+use smoltcp::wire::IpEndpoint;
+
+use crate::owners::{check_owner, NotYours, Notifier, Owner};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SocketKind {
+    Udp,
+    Tcp,
+}
+
+/// Errors from operations on sockets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// there's no socket with the given index
+    NoSuchSocket,
+    /// the sender doesn't own the socket
+    NotYours,
+    /// the operation doesn't apply to this kind of socket
+    WrongSocketKind,
+    /// the VID isn't one of ours
+    InvalidVLan,
+    /// no packet is waiting to be received
+    QueueEmpty,
+    /// the packet at the head of the queue isn't the one being released
+    WrongPacket,
+    /// _smoltcp_ wouldn't queue the packet (failing with `Exhausted` if
+    /// there's no room for it)
+    Send(smoltcp::Error),
+}
+
+impl From<NotYours> for Error {
+    fn from(_: NotYours) -> Self {
+        Self::NotYours
+    }
+}
+
+/// `V` interfaces (one for each VID in a range) with `S` sockets each.
+pub struct Sockets<'a, D, const V: usize, const S: usize>
+where
+    D: for<'d> Device<'d>,
+{
+    ifaces: [Interface<'a, D>; V],
+    handles: [[SocketHandle; S]; V],
+    owners: [Owner; S],
+    kinds: [SocketKind; S],
+    vids: Range<u16>,
+}
+
+impl<'a, D, const V: usize, const S: usize> Sockets<'a, D, V, S>
+where
+    D: for<'d> Device<'d>,
+{
+    /// Gathers the interfaces of VLANs `vids` (which are in the same order,
+    /// as are the handles of their sockets).  Without VLANs, there's a single
+    /// interface, whose VID (which is ignored) is 0.
+    pub fn new(
+        ifaces: [Interface<'a, D>; V],
+        handles: [[SocketHandle; S]; V],
+        owners: [Owner; S],
+        kinds: [SocketKind; S],
+        vids: Range<u16>,
+    ) -> Self {
+        assert_eq!(vids.len(), V);
+        Self {
+            ifaces,
+            handles,
+            owners,
+            kinds,
+            vids,
+        }
+    }
+
+    pub fn iface(&self, vlan_index: usize) -> &Interface<'a, D> {
+        &self.ifaces[vlan_index]
+    }
+
+    pub fn iface_mut(&mut self, vlan_index: usize) -> &mut Interface<'a, D> {
+        &mut self.ifaces[vlan_index]
+    }
+
+    pub fn ifaces(&self) -> &[Interface<'a, D>; V] {
+        &self.ifaces
+    }
+
+    pub fn ifaces_mut(&mut self) -> &mut [Interface<'a, D>; V] {
+        &mut self.ifaces
+    }
+
+    /// Returns the handle of socket `index` on VLAN `vlan_index`.
+    pub fn handle(
+        &self,
+        vlan_index: usize,
+        index: usize,
+    ) -> Result<SocketHandle, Error> {
+        self.handles
+            .get(vlan_index)
+            .and_then(|h| h.get(index))
+            .cloned()
+            .ok_or(Error::NoSuchSocket)
+    }
+
+    /// Checks that the task with index `sender` owns socket `index`.
+    pub fn check_owner(
+        &self,
+        sender: usize,
+        index: usize,
+    ) -> Result<usize, Error> {
+        Ok(check_owner(&self.owners, sender, index)?)
+    }
+
+    /// Gets socket `index` on VLAN `vlan_index`, which must be a UDP socket.
+    pub fn udp_socket_mut(
+        &mut self,
+        vlan_index: usize,
+        index: usize,
+    ) -> Result<&mut UdpSocket<'a>, Error> {
+        let handle = self.handle(vlan_index, index)?;
+        if self.kinds[index] != SocketKind::Udp {
+            return Err(Error::WrongSocketKind);
+        }
+        Ok(self.ifaces[vlan_index].get_socket::<UdpSocket>(handle))
+    }
+
+    /// Receives the first packet waiting on UDP socket `index` on any VLAN,
+    /// for its owner `sender`, calling `readout` on its payload, source and
+    /// VID.
+    pub fn recv<R>(
+        &mut self,
+        sender: usize,
+        index: usize,
+        readout: impl FnOnce(&[u8], IpEndpoint, u16) -> R,
+    ) -> Result<R, Error> {
+        self.check_owner(sender, index)?;
+        for (v, vid) in self.vids.clone().enumerate() {
+            let socket = self.udp_socket_mut(v, index)?;
+            // Any error means that there's nothing to receive on this VLAN.
+            if let Ok((body, endp)) = socket.recv() {
+                return Ok(readout(body, endp, vid));
+            }
+        }
+        Err(Error::QueueEmpty)
+    }
+
+    /// Peeks at the first packet waiting on UDP socket `index` on any VLAN
+    /// (the one that [Sockets::recv] would receive), for its owner `sender`,
+    /// calling `readout` on its payload, source and VID.  The packet stays
+    /// at the head of its queue, with its payload in place, until it's
+    /// released.
+    pub fn peek<R>(
+        &mut self,
+        sender: usize,
+        index: usize,
+        readout: impl FnOnce(&[u8], IpEndpoint, u16) -> R,
+    ) -> Result<R, Error> {
+        self.check_owner(sender, index)?;
+        for (v, vid) in self.vids.clone().enumerate() {
+            let socket = self.udp_socket_mut(v, index)?;
+            if let Ok((body, endp)) = socket.peek() {
+                return Ok(readout(body, *endp, vid));
+            }
+        }
+        Err(Error::QueueEmpty)
+    }
+
+    /// Dequeues the packet that [Sockets::peek] finds on UDP socket `index`,
+    /// for its owner `sender`, if `is_peeked` recognizes its payload as the
+    /// one that the owner is done with.
+    pub fn release(
+        &mut self,
+        sender: usize,
+        index: usize,
+        is_peeked: impl FnOnce(&[u8]) -> bool,
+    ) -> Result<(), Error> {
+        self.check_owner(sender, index)?;
+        for v in 0..V {
+            let socket = self.udp_socket_mut(v, index)?;
+            let peeked = match socket.peek() {
+                Ok((body, _)) => is_peeked(body),
+                Err(_) => continue,
+            };
+            if !peeked {
+                return Err(Error::WrongPacket);
+            }
+            return socket.recv().map(|_| ()).map_err(|_| Error::QueueEmpty);
+        }
+        Err(Error::QueueEmpty)
+    }
+
+    /// Queues a packet of `len` bytes, which `fillout` writes, on UDP socket
+    /// `index` on VLAN `vid`, for its owner `sender`.
+    pub fn send<R>(
+        &mut self,
+        sender: usize,
+        index: usize,
+        vid: u16,
+        endpoint: IpEndpoint,
+        len: usize,
+        fillout: impl FnOnce(&mut [u8]) -> R,
+    ) -> Result<R, Error> {
+        self.check_owner(sender, index)?;
+
+        // Convert from absolute VID to an index in our VLAN array
+        if !self.vids.contains(&vid) {
+            return Err(Error::InvalidVLan);
+        }
+        let vlan_index = usize::from(vid - self.vids.start);
+
+        let socket = self.udp_socket_mut(vlan_index, index)?;
+        let buf = socket.send(len, endpoint).map_err(Error::Send)?;
+        Ok(fillout(buf))
+    }
+
+    /// Notifies the owner of each socket that can do work:  a UDP socket can
+    /// if it has received a packet on any VLAN, and other sockets can if
+    /// `ready` returns true for their interface, handle and index.
+    pub fn wake_sockets<F>(
+        &mut self,
+        notifier: &mut impl Notifier,
+        mut ready: F,
+    ) where
+        F: FnMut(&mut Interface<'a, D>, SocketHandle, usize) -> bool,
+    {
+        let Self {
+            ifaces,
+            handles,
+            owners,
+            kinds,
+            ..
+        } = self;
+        crate::owners::wake_sockets(
+            owners,
+            |i| match kinds[i] {
+                SocketKind::Udp => {
+                    ifaces.iter_mut().zip(&*handles).any(|(iface, handles)| {
+                        iface.get_socket::<UdpSocket>(handles[i]).can_recv()
+                    })
+                }
+                _ => ready(&mut ifaces[0], handles[0][i], i),
+            },
+            notifier,
+        );
+    }
+}
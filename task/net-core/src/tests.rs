@@ -0,0 +1,428 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Host tests, which run the server's [Sockets] over a [FakeMac] (or, without
+//! VLANs, over _smoltcp_'s loopback device).
+
+use core::cell::RefCell;
+use core::ops::Range;
+use std::collections::VecDeque;
+
+use smoltcp::iface::{Interface, InterfaceBuilder, NeighborCache};
+use smoltcp::phy::{
+    ChecksumCapabilities, DeviceCapabilities, Loopback, Medium,
+};
+use smoltcp::socket::{
+    TcpSocket, TcpSocketBuffer, UdpPacketMetadata, UdpSocket, UdpSocketBuffer,
+};
+use smoltcp::time::Instant;
+use smoltcp::wire::{
+    EthernetAddress, EthernetFrame, EthernetProtocol, EthernetRepr, IpAddress,
+    IpCidr, IpEndpoint, IpProtocol, Ipv6Address, Ipv6Packet, Ipv6Repr,
+    UdpPacket, UdpRepr,
+};
+
+use crate::*;
+
+const VID_RANGE: Range<u16> = 0x301..0x303;
+const PORTS: [u16; 2] = [7, 8];
+
+const OWNERS: [Owner; 2] = [
+    Owner {
+        task: 3,
+        notification: 1,
+    },
+    Owner {
+        task: 4,
+        notification: 2,
+    },
+];
+
+const PEER_MAC: EthernetAddress = EthernetAddress([0x0e, 0x1d, 0, 0, 0, 9]);
+const PEER_IP: Ipv6Address =
+    Ipv6Address([0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9]);
+
+/// A MAC that behaves like the STM32H7's with VLANs:  frames that aren't
+/// tagged with a VID in the range are discarded when they reach the head of
+/// the RX queue.  Sent frames are recorded along with their VID.
+#[derive(Default)]
+struct FakeMac {
+    rx: RefCell<VecDeque<(Option<u16>, Vec<u8>)>>,
+    tx: RefCell<Vec<(u16, Vec<u8>)>>,
+}
+
+impl FakeMac {
+    fn inject(&self, vid: Option<u16>, frame: Vec<u8>) {
+        self.rx.borrow_mut().push_back((vid, frame));
+    }
+}
+
+impl VLanMac for FakeMac {
+    fn vlan_can_recv(&self, vid: u16, vid_range: Range<u16>) -> bool {
+        let mut rx = self.rx.borrow_mut();
+        loop {
+            match rx.front() {
+                None => return false,
+                Some((Some(v), _)) if vid_range.contains(v) => {
+                    return *v == vid
+                }
+                Some(_) => {
+                    rx.pop_front();
+                }
+            }
+        }
+    }
+
+    fn vlan_recv<R>(
+        &self,
+        vid: u16,
+        readout: impl FnOnce(&mut [u8]) -> R,
+    ) -> R {
+        let (v, mut frame) = self.rx.borrow_mut().pop_front().unwrap();
+        assert_eq!(v, Some(vid));
+        readout(&mut frame)
+    }
+
+    fn can_send(&self) -> bool {
+        true
+    }
+
+    fn vlan_try_send<R>(
+        &self,
+        len: usize,
+        vid: u16,
+        fillout: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R> {
+        let mut frame = vec![0; len];
+        let result = fillout(&mut frame);
+        self.tx.borrow_mut().push((vid, frame));
+        Some(result)
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        let mut caps = DeviceCapabilities::default();
+        caps.max_transmission_unit = 1514;
+        caps
+    }
+}
+
+/// Records the notifications that are posted.
+#[derive(Default)]
+struct FakeNotifier(Vec<(usize, u32)>);
+
+impl Notifier for FakeNotifier {
+    fn post(&mut self, task: usize, notification: u32) {
+        self.0.push((task, notification));
+    }
+}
+
+/// Returns the MAC and IPv6 addresses of the interface of VLAN `vid` (or,
+/// without VLANs, of VLAN 0).
+fn addresses(vid: u16) -> (EthernetAddress, Ipv6Address) {
+    let mac = EthernetAddress([0x0e, 0x1d, 0, 0, 0, vid as u8]);
+    let ip = Ipv6Address([
+        0xfe, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, vid as u8,
+    ]);
+    (mac, ip)
+}
+
+fn udp_socket() -> UdpSocket<'static> {
+    let buffer = || {
+        UdpSocketBuffer::new(vec![UdpPacketMetadata::EMPTY; 4], vec![0; 1024])
+    };
+    UdpSocket::new(buffer(), buffer())
+}
+
+/// Builds the interface of VLAN `vid` over `device`, with a UDP socket on
+/// each of `PORTS` (or, if `tcp`, a UDP socket and then a TCP socket).
+fn iface<'a, D: for<'d> smoltcp::phy::Device<'d>>(
+    device: D,
+    vid: u16,
+    tcp: bool,
+) -> (Interface<'a, D>, [smoltcp::iface::SocketHandle; 2]) {
+    let (mac, ip) = addresses(vid);
+    let mut iface = InterfaceBuilder::new(device, vec![])
+        .hardware_addr(mac.into())
+        .neighbor_cache(NeighborCache::new(std::collections::BTreeMap::new()))
+        .ip_addrs(vec![IpCidr::new(ip.into(), 64)])
+        .finalize();
+
+    let udp = iface.add_socket(udp_socket());
+    iface
+        .get_socket::<UdpSocket>(udp)
+        .bind((ip, PORTS[0]))
+        .unwrap();
+    let other = if tcp {
+        let buffer = || TcpSocketBuffer::new(vec![0; 1024]);
+        iface.add_socket(TcpSocket::new(buffer(), buffer()))
+    } else {
+        let h = iface.add_socket(udp_socket());
+        iface
+            .get_socket::<UdpSocket>(h)
+            .bind((ip, PORTS[1]))
+            .unwrap();
+        h
+    };
+    (iface, [udp, other])
+}
+
+type VLanSockets<'a> = Sockets<'a, VLanEthernet<'a, FakeMac>, 2, 2>;
+
+/// Builds the server's sockets on each VLAN in `VID_RANGE` over `mac`.
+fn vlan_sockets(mac: &FakeMac) -> VLanSockets<'_> {
+    let [(a, ha), (b, hb)] = [0x301, 0x302].map(|vid| {
+        let device = VLanEthernet {
+            mac,
+            vid,
+            vid_range: VID_RANGE,
+        };
+        iface(device, vid, false)
+    });
+    Sockets::new([a, b], [ha, hb], OWNERS, [SocketKind::Udp; 2], VID_RANGE)
+}
+
+/// Polls every VLAN's interface until the MAC's RX queue has drained.
+fn poll(sockets: &mut VLanSockets, mac: &FakeMac) {
+    // Each interface stops at the first frame for another VLAN, so we poll
+    // them in turn.
+    loop {
+        for iface in sockets.ifaces_mut() {
+            iface.poll(Instant::from_millis(0)).unwrap();
+        }
+        if mac.rx.borrow().is_empty() {
+            break;
+        }
+    }
+}
+
+/// Receives from socket `index` as task `sender`, returning the payload and
+/// VID.
+fn recv<D: for<'d> smoltcp::phy::Device<'d>, const V: usize>(
+    sockets: &mut Sockets<D, V, 2>,
+    sender: usize,
+    index: usize,
+) -> Result<(Vec<u8>, u16), Error> {
+    sockets.recv(sender, index, |body, _, vid| (body.to_vec(), vid))
+}
+
+/// Sends `payload` to our peer from socket `index` on VLAN `vid`, as task
+/// `sender`.
+fn send<D: for<'d> smoltcp::phy::Device<'d>, const V: usize>(
+    sockets: &mut Sockets<D, V, 2>,
+    sender: usize,
+    index: usize,
+    vid: u16,
+    payload: &[u8],
+) -> Result<(), Error> {
+    let peer = IpEndpoint::new(PEER_IP.into(), 1234);
+    sockets.send(sender, index, vid, peer, payload.len(), |buf| {
+        buf.copy_from_slice(payload)
+    })
+}
+
+/// Builds a UDP frame from our peer to port `port` of VLAN `vid`'s interface.
+fn udp_frame(vid: u16, port: u16, payload: &[u8]) -> Vec<u8> {
+    let (mac, ip) = addresses(vid);
+    let udp = UdpRepr {
+        src_port: 1234,
+        dst_port: port,
+    };
+    let ipv6 = Ipv6Repr {
+        src_addr: PEER_IP,
+        dst_addr: ip,
+        next_header: IpProtocol::Udp,
+        payload_len: udp.header_len() + payload.len(),
+        hop_limit: 64,
+    };
+    let eth = EthernetRepr {
+        src_addr: PEER_MAC,
+        dst_addr: mac,
+        ethertype: EthernetProtocol::Ipv6,
+    };
+
+    let mut buf =
+        vec![0; eth.buffer_len() + ipv6.buffer_len() + ipv6.payload_len];
+    let mut frame = EthernetFrame::new_unchecked(&mut buf);
+    eth.emit(&mut frame);
+    let mut packet = Ipv6Packet::new_unchecked(frame.payload_mut());
+    ipv6.emit(&mut packet);
+    udp.emit(
+        &mut UdpPacket::new_unchecked(packet.payload_mut()),
+        &IpAddress::from(PEER_IP),
+        &IpAddress::from(ip),
+        payload.len(),
+        |buf| buf.copy_from_slice(payload),
+        &ChecksumCapabilities::default(),
+    );
+    buf
+}
+
+#[test]
+fn frames_only_reach_their_vlan() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+
+    // A frame for the second VLAN's addresses, but on the first VLAN, is
+    // seen by the first VLAN's interface (which ignores it) and never by the
+    // second's.
+    mac.inject(Some(0x301), udp_frame(0x302, PORTS[0], b"wrong vlan"));
+    mac.inject(Some(0x302), udp_frame(0x302, PORTS[0], b"hello b"));
+    mac.inject(Some(0x301), udp_frame(0x301, PORTS[0], b"hello a"));
+    poll(&mut sockets, &mac);
+
+    // The socket receives from each VLAN in turn, and tells us which.
+    assert_eq!(recv(&mut sockets, 3, 0), Ok((b"hello a".to_vec(), 0x301)));
+    assert_eq!(recv(&mut sockets, 3, 0), Ok((b"hello b".to_vec(), 0x302)));
+    assert_eq!(recv(&mut sockets, 3, 0), Err(Error::QueueEmpty));
+    assert_eq!(recv(&mut sockets, 4, 1), Err(Error::QueueEmpty));
+}
+
+#[test]
+fn sends_are_checked_and_tagged() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+
+    assert_eq!(
+        send(&mut sockets, 3, 0, 0x300, b"x"),
+        Err(Error::InvalidVLan)
+    );
+    assert_eq!(
+        send(&mut sockets, 3, 0, 0x303, b"x"),
+        Err(Error::InvalidVLan)
+    );
+    assert_eq!(send(&mut sockets, 3, 0, 0x302, b"hello"), Ok(()));
+    poll(&mut sockets, &mac);
+
+    // We don't know the peer's MAC address yet, so this is a neighbor
+    // solicitation; either way, it must be on the second VLAN.
+    let tx = mac.tx.borrow();
+    assert!(!tx.is_empty());
+    assert!(tx.iter().all(|(vid, _)| *vid == 0x302));
+}
+
+#[test]
+fn full_queues_refuse_sends() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+
+    // Each socket has room for 4 packets (until we poll).
+    for _ in 0..4 {
+        assert_eq!(send(&mut sockets, 3, 0, 0x301, b"hello"), Ok(()));
+    }
+    assert_eq!(
+        send(&mut sockets, 3, 0, 0x301, b"hello"),
+        Err(Error::Send(smoltcp::Error::Exhausted))
+    );
+    // The other VLAN's copy of the socket has its own queue.
+    assert_eq!(send(&mut sockets, 3, 0, 0x302, b"hello"), Ok(()));
+}
+
+#[test]
+fn only_owners_may_use_sockets() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+
+    mac.inject(Some(0x301), udp_frame(0x301, PORTS[0], b"hello"));
+    poll(&mut sockets, &mac);
+
+    assert_eq!(recv(&mut sockets, 4, 0), Err(Error::NotYours));
+    assert_eq!(recv(&mut sockets, 3, 2), Err(Error::NotYours));
+    assert_eq!(send(&mut sockets, 4, 0, 0x301, b"x"), Err(Error::NotYours));
+    assert!(mac.tx.borrow().is_empty());
+
+    // The packet is still there for its owner.
+    assert_eq!(recv(&mut sockets, 3, 0), Ok((b"hello".to_vec(), 0x301)));
+}
+
+#[test]
+fn owners_of_ready_sockets_are_notified() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+    let mut notifier = FakeNotifier::default();
+    let mut wake = |sockets: &mut VLanSockets| {
+        sockets.wake_sockets(&mut notifier, |_, _, _| unreachable!());
+    };
+
+    wake(&mut sockets);
+    mac.inject(Some(0x302), udp_frame(0x302, PORTS[1], b"hello"));
+    poll(&mut sockets, &mac);
+    wake(&mut sockets);
+    recv(&mut sockets, 4, 1).unwrap();
+    wake(&mut sockets);
+
+    assert_eq!(notifier.0, vec![(4, 2)]);
+}
+
+#[test]
+fn peeked_packets_stay_until_released() {
+    let mac = FakeMac::default();
+    let mut sockets = vlan_sockets(&mac);
+
+    mac.inject(Some(0x302), udp_frame(0x302, PORTS[0], b"first"));
+    mac.inject(Some(0x302), udp_frame(0x302, PORTS[0], b"second"));
+    poll(&mut sockets, &mac);
+
+    let peek = |sockets: &mut VLanSockets, sender| {
+        sockets.peek(sender, 0, |body, endp, vid| {
+            (body.as_ptr() as usize, body.to_vec(), endp.port, vid)
+        })
+    };
+    let (addr, body, port, vid) = peek(&mut sockets, 3).unwrap();
+    assert_eq!((body.as_slice(), port, vid), (&b"first"[..], 1234, 0x302));
+
+    // Peeking again finds the same packet, in the same place.
+    assert_eq!(peek(&mut sockets, 3).unwrap().0, addr);
+    assert_eq!(peek(&mut sockets, 4), Err(Error::NotYours));
+
+    // Only the owner can release it, and only if it's the packet they
+    // peeked at.
+    let is = |a: usize| move |body: &[u8]| body.as_ptr() as usize == a;
+    assert_eq!(sockets.release(4, 0, is(addr)), Err(Error::NotYours));
+    assert_eq!(sockets.release(3, 0, is(addr + 1)), Err(Error::WrongPacket));
+    assert_eq!(sockets.release(3, 0, is(addr)), Ok(()));
+
+    let (_, body, _, _) = peek(&mut sockets, 3).unwrap();
+    assert_eq!(body, b"second");
+    assert_eq!(recv(&mut sockets, 3, 0), Ok((b"second".to_vec(), 0x302)));
+    assert_eq!(peek(&mut sockets, 3), Err(Error::QueueEmpty));
+    assert_eq!(sockets.release(3, 0, |_| true), Err(Error::QueueEmpty));
+}
+
+#[test]
+fn sockets_without_vlans() {
+    // A UDP socket and a TCP socket, on a single interface which sends the
+    // frames that it transmits back to itself.
+    let (iface, handles) = iface(Loopback::new(Medium::Ethernet), 0, true);
+    let mut sockets: Sockets<_, 1, 2> = Sockets::new(
+        [iface],
+        [handles],
+        OWNERS,
+        [SocketKind::Udp, SocketKind::Tcp],
+        0..1,
+    );
+
+    // The UDP socket sends a packet to itself.
+    let (_, ip) = addresses(0);
+    let to = IpEndpoint::new(ip.into(), PORTS[0]);
+    sockets
+        .send(3, 0, 0, to, 5, |buf| buf.copy_from_slice(b"hello"))
+        .unwrap();
+    for _ in 0..4 {
+        sockets.iface_mut(0).poll(Instant::from_millis(0)).unwrap();
+    }
+
+    let mut notifier = FakeNotifier::default();
+    sockets.wake_sockets(&mut notifier, |iface, handle, i| {
+        assert_eq!(i, 1);
+        iface.get_socket::<TcpSocket>(handle).is_open()
+    });
+    assert_eq!(notifier.0, vec![(3, 1)]);
+    assert_eq!(recv(&mut sockets, 3, 0), Ok((b"hello".to_vec(), 0)));
+
+    assert_eq!(recv(&mut sockets, 4, 1), Err(Error::WrongSocketKind));
+    assert_eq!(
+        send(&mut sockets, 4, 1, 0, b"x"),
+        Err(Error::WrongSocketKind)
+    );
+}
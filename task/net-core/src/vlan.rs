@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A _smoltcp_ device for each VLAN.
+//!
+//! The MAC strips VLAN tags from the frames that it receives, and inserts
+//! them into the frames that we send.  Each VLAN runs its own (VLAN-unaware)
+//! _smoltcp_ interface over a [VLanEthernet], which only receives frames with
+//! its VID and tags the frames that it sends.
+
+use core::ops::Range;
+use smoltcp::phy::{Device, DeviceCapabilities, RxToken, TxToken};
+use smoltcp::time::Instant;
+
+/// An Ethernet MAC that handles VLAN tags.
+pub trait VLanMac {
+    /// Returns true if the next received frame is on VLAN `vid`.  Frames
+    /// that aren't on any VLAN in `vid_range` (or that have errors) are
+    /// discarded first, so that they can't block the VLANs' interfaces.
+    fn vlan_can_recv(&self, vid: u16, vid_range: Range<u16>) -> bool;
+
+    /// Receives the next frame, which `vlan_can_recv` has found to be on
+    /// VLAN `vid`, calling `readout` on it.
+    fn vlan_recv<R>(&self, vid: u16, readout: impl FnOnce(&mut [u8]) -> R)
+        -> R;
+
+    /// Returns true if there's room to send a frame.
+    fn can_send(&self) -> bool;
+
+    /// Sends a frame of `len` bytes on VLAN `vid`, which `fillout` writes,
+    /// or returns `None` if there's no room.
+    fn vlan_try_send<R>(
+        &self,
+        len: usize,
+        vid: u16,
+        fillout: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R>;
+
+    fn capabilities(&self) -> DeviceCapabilities;
+}
+
+/// The device under the interface of VLAN `vid`, which is one of the VLANs in
+/// `vid_range`.
+pub struct VLanEthernet<'a, M> {
+    pub mac: &'a M,
+    pub vid: u16,
+    pub vid_range: Range<u16>,
+}
+
+impl<'a, 'b, M: VLanMac + 'a> Device<'a> for VLanEthernet<'b, M> {
+    type RxToken = VLanRxToken<'a, M>;
+    type TxToken = VLanTxToken<'a, M>;
+
+    fn receive(&'a mut self) -> Option<(Self::RxToken, Self::TxToken)> {
+        if self.mac.vlan_can_recv(self.vid, self.vid_range.clone())
+            && self.mac.can_send()
+        {
+            Some((
+                VLanRxToken(self.mac, self.vid),
+                VLanTxToken(self.mac, self.vid),
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn transmit(&'a mut self) -> Option<Self::TxToken> {
+        if self.mac.can_send() {
+            Some(VLanTxToken(self.mac, self.vid))
+        } else {
+            None
+        }
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.mac.capabilities()
+    }
+}
+
+pub struct VLanRxToken<'a, M>(&'a M, u16);
+
+impl<'a, M: VLanMac> RxToken for VLanRxToken<'a, M> {
+    fn consume<R, F>(self, _timestamp: Instant, f: F) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        self.0.vlan_recv(self.1, f)
+    }
+}
+
+pub struct VLanTxToken<'a, M>(&'a M, u16);
+
+impl<'a, M: VLanMac> TxToken for VLanTxToken<'a, M> {
+    fn consume<R, F>(
+        self,
+        _timestamp: Instant,
+        len: usize,
+        f: F,
+    ) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        self.0
+            .vlan_try_send(len, self.1, f)
+            .expect("TX token existed without descriptor available")
+    }
+}
+
+#[cfg(feature = "stm32h7-vlan")]
+impl VLanMac for drv_stm32h7_eth::Ethernet {
+    fn vlan_can_recv(&self, vid: u16, vid_range: Range<u16>) -> bool {
+        drv_stm32h7_eth::Ethernet::vlan_can_recv(self, vid, vid_range)
+    }
+
+    fn vlan_recv<R>(
+        &self,
+        vid: u16,
+        readout: impl FnOnce(&mut [u8]) -> R,
+    ) -> R {
+        drv_stm32h7_eth::Ethernet::vlan_recv(self, vid, readout)
+    }
+
+    fn can_send(&self) -> bool {
+        drv_stm32h7_eth::Ethernet::can_send(self)
+    }
+
+    fn vlan_try_send<R>(
+        &self,
+        len: usize,
+        vid: u16,
+        fillout: impl FnOnce(&mut [u8]) -> R,
+    ) -> Option<R> {
+        drv_stm32h7_eth::Ethernet::vlan_try_send(self, len, vid, fillout)
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        drv_stm32h7_eth::Ethernet::capabilities(self)
+    }
+}
@@ -4,6 +4,7 @@
 
 use build_net::{BufSize, NetConfig, SocketConfig};
 use proc_macro2::TokenStream;
+use std::collections::BTreeMap;
 use std::io::Write;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -21,6 +22,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Finds the config area of flash, from which MAC addresses are read with the
+/// `flash-mac` feature, among the peripherals that we use.
+#[cfg(feature = "flash-mac")]
+fn generate_config_flash_consts(
+    out: &mut std::fs::File,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let peripherals = build_util::task_peripherals()?;
+    let flash = peripherals.get("config_flash").ok_or(
+        "the flash-mac feature needs `config_flash` in the task's `uses`",
+    )?;
+    let addr = flash.address as usize;
+    let size = flash.size as usize;
+    writeln!(
+        out,
+        "{}",
+        quote::quote! {
+            pub const CONFIG_FLASH_ADDR: usize = #addr;
+            pub const CONFIG_FLASH_SIZE: usize = #size;
+        }
+    )?;
+    Ok(())
+}
+
 fn generate_net_config(
     config: &NetConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
@@ -35,7 +59,11 @@ fn generate_net_config(
         "{}",
         quote::quote! {
             use core::sync::atomic::{AtomicBool, Ordering};
-            use smoltcp::socket::{UdpPacketMetadata, UdpSocket, UdpSocketBuffer};
+            #[allow(unused_imports)]
+            use smoltcp::socket::{
+                Socket, TcpSocket, TcpSocketBuffer, UdpPacketMetadata,
+                UdpSocket, UdpSocketBuffer,
+            };
 
             pub const SOCKET_COUNT: usize = #socket_count;
         }
@@ -44,18 +72,45 @@ fn generate_net_config(
     #[cfg(feature = "vlan")]
     build_net::generate_vlan_consts(&config, &mut out)?;
 
+    build_net::generate_ipv4_consts(&config, &mut out)?;
+    build_net::generate_neighbor_consts(&config, &mut out)?;
+    build_net::generate_multicast_consts(&config, &mut out)?;
+    build_net::generate_policy_consts(&config, &mut out)?;
+
+    #[cfg(feature = "pcap")]
+    build_net::generate_pcap_consts(&config, &mut out)?;
+
+    #[cfg(feature = "secure")]
+    build_net::generate_secure_consts(&config, &mut out)?;
+
+    #[cfg(feature = "flash-mac")]
+    generate_config_flash_consts(&mut out)?;
+
+    let loans = find_loans(config)?;
     for (name, socket) in &config.sockets {
         writeln!(
             out,
             "{}",
-            generate_socket_state(name, socket, config.vlan.map(|v| v.count))?
+            generate_socket_state(
+                name,
+                socket,
+                config.vlan.map(|v| v.count),
+                loans.get(name).copied(),
+            )?
         )?;
     }
     writeln!(out, "{}", generate_state_struct(&config)?)?;
-    writeln!(out, "{}", generate_constructor(&config)?)?;
+    writeln!(out, "{}", generate_constructor(&config, &loans)?)?;
     writeln!(out, "{}", generate_owner_info(&config)?)?;
     writeln!(out, "{}", generate_port_table(&config)?)?;
 
+    //
+    // TCP sockets aren't supported with VLANs (build_net rejects them), so
+    // with VLANs this table is all UDP; the receive policy uses it either way.
+    //
+    writeln!(out, "{}", generate_kind_table(&config)?)?;
+    writeln!(out, "{}", generate_loan_table(&config, &loans)?)?;
+
     build_net::generate_socket_enum(&config, &mut out)?;
 
     drop(out);
@@ -82,6 +137,87 @@ fn generate_port_table(
     })
 }
 
+fn generate_kind_table(
+    config: &NetConfig,
+) -> Result<TokenStream, Box<dyn std::error::Error>> {
+    let consts = config.sockets.values().map(|socket| {
+        if socket.is_tcp() {
+            quote::quote! { task_net_core::SocketKind::Tcp }
+        } else {
+            quote::quote! { task_net_core::SocketKind::Udp }
+        }
+    });
+
+    let n = config.sockets.len();
+
+    Ok(quote::quote! {
+        pub(crate) const SOCKET_KINDS: [task_net_core::SocketKind; #n] = [
+            #( #consts ),*
+        ];
+    })
+}
+
+///
+/// Finds the shared region in which each loaned socket keeps its received
+/// payloads, returning their addresses by socket name.
+///
+fn find_loans(
+    config: &NetConfig,
+) -> Result<BTreeMap<String, u32>, Box<dyn std::error::Error>> {
+    if config.sockets.values().all(|s| s.loan.is_none()) {
+        return Ok(BTreeMap::new());
+    }
+
+    let peripherals = build_util::task_peripherals()?;
+    let mut loans = BTreeMap::new();
+    for (name, socket) in &config.sockets {
+        let loan = match &socket.loan {
+            Some(loan) => loan,
+            None => continue,
+        };
+        let region = peripherals.get(loan).ok_or_else(|| {
+            format!(
+                "socket {}: loan region `{}` isn't in the task's `uses`",
+                name, loan
+            )
+        })?;
+        if (region.size as usize) < socket.rx.bytes {
+            return Err(format!(
+                "socket {}: loan region `{}` is smaller than rx.bytes",
+                name, loan
+            )
+            .into());
+        }
+        if loans.values().any(|&addr| addr == region.address) {
+            return Err(format!(
+                "socket {}: loan region `{}` is already loaned",
+                name, loan
+            )
+            .into());
+        }
+        loans.insert(name.clone(), region.address);
+    }
+    Ok(loans)
+}
+
+fn generate_loan_table(
+    config: &NetConfig,
+    loans: &BTreeMap<String, u32>,
+) -> Result<TokenStream, Box<dyn std::error::Error>> {
+    let consts = config.sockets.keys().map(|name| {
+        let loaned = loans.contains_key(name);
+        quote::quote! { #loaned }
+    });
+
+    let n = config.sockets.len();
+
+    Ok(quote::quote! {
+        pub(crate) const SOCKET_LOANED: [bool; #n] = [
+            #( #consts ),*
+        ];
+    })
+}
+
 fn generate_owner_info(
     config: &NetConfig,
 ) -> Result<TokenStream, Box<dyn std::error::Error>> {
@@ -89,20 +225,17 @@ fn generate_owner_info(
         let task: syn::Ident = syn::parse_str(&socket.owner.name).unwrap();
         let note = socket.owner.notification;
         quote::quote! {
-            (
-                userlib::TaskId::for_index_and_gen(
-                    hubris_num_tasks::Task::#task as usize,
-                    userlib::Generation::ZERO,
-                ),
-                #note,
-            )
+            task_net_core::Owner {
+                task: hubris_num_tasks::Task::#task as usize,
+                notification: #note,
+            }
         }
     });
 
     let n = config.sockets.len();
 
     Ok(quote::quote! {
-        pub(crate) const SOCKET_OWNERS: [(userlib::TaskId, u32); #n] = [
+        pub(crate) const SOCKET_OWNERS: [task_net_core::Owner; #n] = [
             #( #consts ),*
         ];
     })
@@ -112,13 +245,22 @@ fn generate_socket_state(
     name: &str,
     config: &SocketConfig,
     vlan_count: Option<usize>,
+    loan: Option<u32>,
 ) -> Result<TokenStream, Box<dyn std::error::Error>> {
-    if config.kind != "udp" {
-        return Err("unsupported socket kind".into());
+    if config.is_tcp() {
+        let tx = generate_tcp_buffers(name, "TX", &config.tx)?;
+        let rx = generate_tcp_buffers(name, "RX", &config.rx)?;
+        return Ok(quote::quote! {
+            #tx
+            #rx
+        });
     }
 
     let tx = generate_buffers(name, "TX", &config.tx, vlan_count)?;
-    let rx = generate_buffers(name, "RX", &config.rx, vlan_count)?;
+    let rx = match loan {
+        Some(addr) => generate_loaned_buffers(name, &config.rx, addr)?,
+        None => generate_buffers(name, "RX", &config.rx, vlan_count)?,
+    };
     Ok(quote::quote! {
         #tx
         #rx
@@ -154,26 +296,84 @@ fn generate_buffers(
     })
 }
 
+///
+/// A loaned socket's received payloads are kept in a shared region, at
+/// `addr`, so only their metadata is in our own memory.  (VLANs are rejected
+/// for loaned sockets when the configuration is loaded.)
+///
+fn generate_loaned_buffers(
+    name: &str,
+    config: &BufSize,
+    addr: u32,
+) -> Result<TokenStream, Box<dyn std::error::Error>> {
+    let pktcnt = config.packets;
+    let addr = addr as usize;
+    let upname = name.to_ascii_uppercase();
+    let hdrname: syn::Ident =
+        syn::parse_str(&format!("SOCK_RX_HDR_{}", upname)).unwrap();
+    let loanname: syn::Ident =
+        syn::parse_str(&format!("SOCK_RX_LOAN_{}", upname)).unwrap();
+    Ok(quote::quote! {
+        static mut #hdrname: [UdpPacketMetadata; #pktcnt] =
+            [UdpPacketMetadata::EMPTY; #pktcnt];
+        const #loanname: usize = #addr;
+    })
+}
+
+///
+/// TCP sockets are streams, and so have only a byte buffer in each direction.
+/// (VLANs are rejected for TCP sockets when the configuration is loaded.)
+///
+fn generate_tcp_buffers(
+    name: &str,
+    dir: &str,
+    config: &BufSize,
+) -> Result<TokenStream, Box<dyn std::error::Error>> {
+    let bytecnt = config.bytes;
+    let upname = name.to_ascii_uppercase();
+    let bufname: syn::Ident =
+        syn::parse_str(&format!("SOCK_{}_DAT_{}", dir, upname)).unwrap();
+    Ok(quote::quote! {
+        static mut #bufname: [u8; #bytecnt] = [0u8; #bytecnt];
+    })
+}
+
 fn generate_state_struct(
     config: &NetConfig,
 ) -> Result<TokenStream, Box<dyn std::error::Error>> {
     let n = config.sockets.len();
     Ok(if let Some(vlan_count) = config.vlan.map(|v| v.count) {
         quote::quote! {
-            pub(crate) struct Sockets<'a>(pub [[UdpSocket<'a>; #n]; #vlan_count]);
+            pub(crate) struct Sockets<'a>(pub [[Socket<'a>; #n]; #vlan_count]);
         }
     } else {
         quote::quote! {
-            pub(crate) struct Sockets<'a>(pub [UdpSocket<'a>; #n]);
+            pub(crate) struct Sockets<'a>(pub [Socket<'a>; #n]);
         }
     })
 }
 
 fn generate_constructor(
     config: &NetConfig,
+    loans: &BTreeMap<String, u32>,
 ) -> Result<TokenStream, Box<dyn std::error::Error>> {
     let name_to_sockets = |name: &String, i: Option<usize>| {
         let upname = name.to_ascii_uppercase();
+
+        if config.sockets[name].is_tcp() {
+            let rxbytes: syn::Ident =
+                syn::parse_str(&format!("SOCK_RX_DAT_{}", upname)).unwrap();
+            let txbytes: syn::Ident =
+                syn::parse_str(&format!("SOCK_TX_DAT_{}", upname)).unwrap();
+
+            return quote::quote! {
+                Socket::Tcp(TcpSocket::new(
+                    TcpSocketBuffer::new(unsafe { &mut #rxbytes[..] }),
+                    TcpSocketBuffer::new(unsafe { &mut #txbytes[..] }),
+                ))
+            };
+        }
+
         let rxhdrs: syn::Ident =
             syn::parse_str(&format!("SOCK_RX_HDR_{}", upname)).unwrap();
         let rxbytes: syn::Ident =
@@ -185,7 +385,7 @@ fn generate_constructor(
 
         if let Some(i) = i {
             quote::quote! {
-                UdpSocket::new(
+                Socket::Udp(UdpSocket::new(
                     UdpSocketBuffer::new(
                     unsafe { &mut #rxhdrs[#i][..] },
                     unsafe { &mut #rxbytes[#i][..] },
@@ -194,20 +394,39 @@ fn generate_constructor(
                     unsafe { &mut #txhdrs[#i][..] },
                     unsafe { &mut #txbytes[#i][..] },
                     ),
-                )
+                ))
             }
         } else {
+            // A loaned socket's shared region is mapped into our address
+            // space (find_loans checked that it's in our `uses`, and big
+            // enough), and only this socket's buffer is placed in it.
+            let rxbytes = if loans.contains_key(name) {
+                let bytecnt = config.sockets[name].rx.bytes;
+                let loanname: syn::Ident =
+                    syn::parse_str(&format!("SOCK_RX_LOAN_{}", upname))
+                        .unwrap();
+                quote::quote! {
+                    unsafe {
+                        core::slice::from_raw_parts_mut(
+                            #loanname as *mut u8,
+                            #bytecnt,
+                        )
+                    }
+                }
+            } else {
+                quote::quote! { unsafe { &mut #rxbytes[..] } }
+            };
             quote::quote! {
-                UdpSocket::new(
+                Socket::Udp(UdpSocket::new(
                     UdpSocketBuffer::new(
                        unsafe { &mut #rxhdrs[..] },
-                       unsafe { &mut #rxbytes[..] },
+                       #rxbytes,
                     ),
                     UdpSocketBuffer::new(
                         unsafe { &mut #txhdrs[..] },
                         unsafe { &mut #txbytes[..] },
                     ),
-                )
+                ))
             }
         }
     };
@@ -7,6 +7,7 @@ use drv_gimlet_seq_api::Sequencer;
 use drv_spi_api::Spi;
 use drv_stm32h7_eth as eth;
 use drv_stm32xx_sys_api::{Alternate, Port, Sys};
+use task_net_api::ManagementStatus;
 use userlib::{hl::sleep_for, task_slot};
 
 task_slot!(SPI, spi_driver);
@@ -83,4 +84,8 @@ impl Bsp {
     pub fn wake(&self, eth: &eth::Ethernet) {
         self.0.wake(eth);
     }
+
+    pub fn management_status(&self) -> Option<ManagementStatus> {
+        Some(self.0.status())
+    }
 }
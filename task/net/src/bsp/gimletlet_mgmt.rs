@@ -11,6 +11,7 @@ use ksz8463::{
     Error as KszError, MIBCounter, MIBCounterValue, Register as KszRegister,
 };
 use ringbuf::*;
+use task_net_api::ManagementStatus;
 use userlib::task_slot;
 use vsc7448_pac::{phy, types::PhyRegisterAddress};
 use vsc85xx::VscError;
@@ -259,4 +260,8 @@ impl Bsp {
             self.leds.led_off(2).unwrap();
         }
     }
+
+    pub fn management_status(&self) -> Option<ManagementStatus> {
+        Some(self.mgmt.status())
+    }
 }
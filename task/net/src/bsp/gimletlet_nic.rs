@@ -11,6 +11,7 @@ use ksz8463::{
     Register as KszRegister,
 };
 use ringbuf::*;
+use task_net_api::ManagementStatus;
 use userlib::{hl::sleep_for, task_slot};
 
 task_slot!(SPI, spi_driver);
@@ -110,4 +111,10 @@ impl Bsp {
             });
         }
     }
+
+    pub fn management_status(&self) -> Option<ManagementStatus> {
+        // The KSZ8463 isn't attached to a VSC85x2 here, so this board doesn't
+        // have a management network in the sense of `mgmt`.
+        None
+    }
 }
@@ -5,6 +5,7 @@
 use crate::pins;
 use drv_stm32h7_eth as eth;
 use drv_stm32xx_sys_api::{Alternate, Port, Sys};
+use task_net_api::ManagementStatus;
 
 /// Address used on the MDIO link by our Ethernet PHY. Different
 /// vendors have different defaults for this, it will likely need to
@@ -69,4 +70,9 @@ impl Bsp {
     pub fn wake(&self, _eth: &eth::Ethernet) {
         panic!("Wake should never be called, because WAKE_INTERVAL is None");
     }
+
+    pub fn management_status(&self) -> Option<ManagementStatus> {
+        // There's no management network on this board
+        None
+    }
 }
@@ -97,6 +97,21 @@ pub fn claim_rx_statics() -> (
             [eth::ring::Buffer::new(); _];
     }
 }
+/// Grabs references to the state of each secure socket, and to a buffer in
+/// which packets are sealed and opened.  Can only be called once.
+#[cfg(feature = "secure")]
+pub fn claim_secure_statics() -> (
+    &'static mut [crate::secure::SocketState; crate::generated::SOCKET_COUNT],
+    &'static mut [u8; crate::secure::BUF_SIZE],
+) {
+    mutable_statics! {
+        static mut SECURE_STATE: [
+            crate::secure::SocketState;
+            crate::generated::SOCKET_COUNT
+        ] = [crate::secure::SocketState::new(); _];
+        static mut SECURE_BUF: [u8; crate::secure::BUF_SIZE] = [0; _];
+    }
+}
 /// Grabs references to the MAC address buffer.  Can only be called once.
 pub fn claim_mac_address() -> &'static mut [u8; 6] {
     mutable_statics! {
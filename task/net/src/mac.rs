@@ -0,0 +1,201 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Choosing our MAC addresses.
+//!
+//! With the `vpd-mac` feature, we look for a block of MAC addresses in the
+//! vital product data stored in the board's FRU ID EEPROM (via the `eeprom`
+//! task).  With the `flash-mac` feature, we look for the same vital product
+//! data in a config area at the end of the microcontroller's flash.  If
+//! neither is enabled, or there's no valid block with enough addresses for
+//! all of our VLANs, we fall back to hashing the microcontroller's unique ID
+//! into a locally administered address.
+
+use task_net_api::{MacAddressBlock, MacAddressSource};
+use zerocopy::AsBytes;
+
+#[cfg(feature = "vlan")]
+const MAC_COUNT: u16 = crate::generated::VLAN_COUNT as u16;
+
+#[cfg(not(feature = "vlan"))]
+const MAC_COUNT: u16 = 1;
+
+/// Chooses our MAC addresses.  This can only be called once.
+pub fn mac_addresses() -> MacAddressBlock {
+    #[cfg(feature = "vpd-mac")]
+    let block = from_vpd::mac_addresses();
+
+    #[cfg(not(feature = "vpd-mac"))]
+    let block = None;
+
+    #[cfg(feature = "flash-mac")]
+    let block = block.or_else(from_flash::mac_addresses);
+
+    let block = block.unwrap_or_else(uid_mac_addresses);
+
+    // Keep the base address somewhere that we can find it with a debugger.
+    let buf = crate::buf::claim_mac_address();
+    *buf = block.base;
+
+    block
+}
+
+/// Derives a block of MAC addresses from the microcontroller's unique ID.
+fn uid_mac_addresses() -> MacAddressBlock {
+    let uid = drv_stm32xx_uid::read_uid();
+    // Jenkins hash
+    let mut hash: u32 = 0;
+    for byte in uid.as_bytes() {
+        hash = hash.wrapping_add(*byte as u32);
+        hash = hash.wrapping_add(hash << 10);
+        hash ^= hash >> 6;
+    }
+    hash = hash.wrapping_add(hash << 3);
+    hash ^= hash >> 11;
+    hash = hash.wrapping_add(hash >> 15);
+
+    // Locally administered, unicast address
+    let mut base = [0x0e, 0x1d, 0, 0, 0, 0];
+
+    // Set the lower 32-bits based on the hashed UID
+    base[2..].copy_from_slice(&hash.to_be_bytes());
+
+    MacAddressBlock {
+        base,
+        count: MAC_COUNT,
+        stride: 1,
+        source: MacAddressSource::Uid,
+    }
+}
+
+#[cfg(feature = "vpd-mac")]
+mod from_vpd {
+    use crate::vpd_eeprom::{self, MAX_VPD_SIZE};
+    use ringbuf::*;
+    use task_net_api::{MacAddressBlock, MacAddressSource};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Trace {
+        None,
+        ReadFailed(vpd_eeprom::Error),
+    }
+    ringbuf!(Trace, 4, Trace::None);
+
+    /// Reads a block of MAC addresses from the VPD, returning `None` (and
+    /// recording why in a ringbuf) if we can't.
+    pub fn mac_addresses() -> Option<MacAddressBlock> {
+        let mut buf = [0u8; MAX_VPD_SIZE];
+        match vpd_eeprom::read(&mut buf) {
+            Ok(vpd) => super::vpd_block::find(vpd, MacAddressSource::Vpd),
+            Err(e) => {
+                ringbuf_entry!(Trace::ReadFailed(e));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(feature = "flash-mac")]
+mod from_flash {
+    use crate::generated::{CONFIG_FLASH_ADDR, CONFIG_FLASH_SIZE};
+    use ringbuf::*;
+    use task_net_api::{MacAddressBlock, MacAddressSource};
+    use vpd::{Vpd, VpdError};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Trace {
+        None,
+        VpdError(VpdError),
+    }
+    ringbuf!(Trace, 4, Trace::None);
+
+    /// Largest VPD that we're willing to read out of the config area (onto
+    /// our stack), which matches what the FRU ID EEPROM can hold.
+    const MAX_VPD_SIZE: usize = 512;
+
+    /// Reads a block of MAC addresses from VPD in the config area, returning
+    /// `None` (and recording why in a ringbuf) if we can't.
+    pub fn mac_addresses() -> Option<MacAddressBlock> {
+        // The config area is mapped into our address space as a device
+        // (it's a peripheral as far as the build is concerned), where
+        // unaligned loads fault, so we copy it into RAM a byte at a time
+        // before parsing it.
+        let mut buf = [0u8; MAX_VPD_SIZE];
+        let len = buf.len().min(CONFIG_FLASH_SIZE);
+        for (i, b) in buf[..len].iter_mut().enumerate() {
+            // Safety: the config area is mapped into our address space (the
+            // build fails if it isn't in our `uses`), `i` is within it, and
+            // nothing in the system writes it while we're running.
+            *b = unsafe {
+                core::ptr::read_volatile(
+                    (CONFIG_FLASH_ADDR as *const u8).add(i),
+                )
+            };
+        }
+        let area = &buf[..len];
+
+        match Vpd::parse(area) {
+            Ok(vpd) => super::vpd_block::find(vpd, MacAddressSource::Flash),
+            Err(e) => {
+                ringbuf_entry!(Trace::VpdError(e));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(any(feature = "vpd-mac", feature = "flash-mac"))]
+mod vpd_block {
+    use super::MAC_COUNT;
+    use ringbuf::*;
+    use task_net_api::{MacAddressBlock, MacAddressSource};
+    use vpd::{Vpd, VpdError};
+
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Trace {
+        None,
+        VpdError(MacAddressSource, VpdError),
+        NoMacBlock(MacAddressSource),
+        NotEnoughMacs {
+            source: MacAddressSource,
+            usable: u16,
+        },
+        Found(MacAddressSource, [u8; 6]),
+    }
+    ringbuf!(Trace, 4, Trace::None);
+
+    /// Finds a block of MAC addresses with enough addresses for all of our
+    /// VLANs in `vpd`, returning `None` (and recording why in our ringbuf)
+    /// if there isn't one.
+    pub fn find(
+        vpd: Vpd<'_>,
+        source: MacAddressSource,
+    ) -> Option<MacAddressBlock> {
+        let block = match vpd.mac() {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                ringbuf_entry!(Trace::NoMacBlock(source));
+                return None;
+            }
+            Err(e) => {
+                ringbuf_entry!(Trace::VpdError(source, e));
+                return None;
+            }
+        };
+
+        let usable = block.usable();
+        if usable < MAC_COUNT {
+            ringbuf_entry!(Trace::NotEnoughMacs { source, usable });
+            return None;
+        }
+
+        ringbuf_entry!(Trace::Found(source, block.base));
+        Some(MacAddressBlock {
+            base: block.base,
+            count: MAC_COUNT,
+            stride: block.stride,
+            source,
+        })
+    }
+}
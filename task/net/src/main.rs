@@ -7,6 +7,19 @@
 
 mod bsp;
 mod buf;
+mod mac;
+mod multicast;
+mod neighbors;
+#[cfg(feature = "pcap")]
+mod pcap;
+mod policy;
+#[cfg(feature = "secure")]
+mod secure;
+#[cfg(any(feature = "vpd-mac", feature = "secure"))]
+mod vpd_eeprom;
+
+#[cfg(all(feature = "secure", feature = "vlan"))]
+compile_error!("secure sockets are not supported with VLANs");
 
 pub mod pins;
 
@@ -28,12 +41,15 @@ cfg_if::cfg_if! {
 }
 
 mod idl {
-    use task_net_api::{NetError, SocketName, UdpMetadata};
+    use task_net_api::{
+        Address, Endpoint, InterfaceAddresses, InterfaceStats, LoanedPacket,
+        MacAddressBlock, ManagementStatus, NeighborEntry, NetError, SocketName,
+        SocketStats, UdpMetadata,
+    };
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
 
 use core::sync::atomic::{AtomicU32, Ordering};
-use zerocopy::AsBytes;
 
 #[cfg(feature = "h743")]
 use stm32h7::stm32h743 as device;
@@ -51,30 +67,6 @@ task_slot!(SYS, sys);
 //
 // Much of this needs to move into the board-level configuration.
 
-/// Claims and calculates the MAC address.  This can only be called once.
-fn mac_address() -> &'static [u8; 6] {
-    let buf = crate::buf::claim_mac_address();
-    let uid = drv_stm32xx_uid::read_uid();
-    // Jenkins hash
-    let mut hash: u32 = 0;
-    for byte in uid.as_bytes() {
-        hash = hash.wrapping_add(*byte as u32);
-        hash = hash.wrapping_add(hash << 10);
-        hash ^= hash >> 6;
-    }
-    hash = hash.wrapping_add(hash << 3);
-    hash ^= hash >> 11;
-    hash = hash.wrapping_add(hash >> 15);
-
-    // Locally administered, unicast address
-    buf[0] = 0x0e;
-    buf[1] = 0x1d;
-
-    // Set the lower 32-bits based on the hashed UID
-    buf[2..].copy_from_slice(&hash.to_be_bytes());
-    buf
-}
-
 const TX_RING_SZ: usize = 4;
 
 const RX_RING_SZ: usize = 4;
@@ -82,12 +74,10 @@ const RX_RING_SZ: usize = 4;
 /// Notification mask for our IRQ; must match configuration in app.toml.
 const ETH_IRQ: u32 = 1;
 
-/// Notification mask for optional periodic logging
+/// Notification mask for our timer, which is used both for optional periodic
+/// logging and for waking smoltcp when it has something to do
 const WAKE_IRQ: u32 = 2;
 
-/// Number of entries to maintain in our neighbor cache (ARP/NDP).
-const NEIGHBORS: usize = 4;
-
 /////////////////////////////////////////////////////////////////////////////
 // Main driver loop.
 
@@ -134,17 +124,15 @@ fn main() -> ! {
     );
 
     // Set up the network stack.
-    use smoltcp::wire::EthernetAddress;
-    let mac = EthernetAddress::from_bytes(mac_address());
+    let macs = mac::mac_addresses();
 
     // Configure the server and its local storage arrays (on the stack)
-    let ipv6_addr = link_local_iface_addr(mac);
     let mut storage = ServerStorage::new(eth);
 
     // Board-dependant initialization (e.g. bringing up the PHYs)
     let bsp = bsp::Bsp::new(&storage.eth, &sys);
 
-    let mut server = ServerImpl::new(&mut storage, ipv6_addr, mac, bsp);
+    let mut server = ServerImpl::new(&mut storage, macs, bsp);
 
     // Turn on our IRQ.
     userlib::sys_irq_control(ETH_IRQ, true);
@@ -168,13 +156,22 @@ fn main() -> ! {
         } else {
             // No work to do immediately. Wait for an ethernet IRQ or an
             // incoming message, or for a certain amount of time to pass.
+            let now = sys_get_timer().now;
+            let mut deadline = None;
             if let Some(wake_interval) = bsp::WAKE_INTERVAL {
-                let now = sys_get_timer().now;
                 if now >= wake_target_time {
                     server.wake();
                     wake_target_time = now + wake_interval;
                 }
-                sys_set_timer(Some(wake_target_time), WAKE_IRQ);
+                deadline = Some(wake_target_time);
+            }
+            // smoltcp may also need to be polled at a particular time, e.g.
+            // to retransmit or to renew a DHCP lease.
+            if let Some(t) = server.poll_at(now) {
+                deadline = Some(deadline.map_or(t, |d: u64| d.min(t)));
+            }
+            if deadline.is_some() {
+                sys_set_timer(deadline, WAKE_IRQ);
             }
             let mut msgbuf = [0u8; ServerImpl::INCOMING_SIZE];
             idol_runtime::dispatch_n(&mut msgbuf, &mut server);
@@ -182,6 +179,83 @@ fn main() -> ! {
     }
 }
 
+/// Converts the driver's counters into their IPC representation.
+fn interface_stats(eth: &eth::Ethernet) -> task_net_api::InterfaceStats {
+    let s = eth.stats();
+    task_net_api::InterfaceStats {
+        tx_packets: s.tx_packets,
+        rx_packets: s.rx_packets,
+        tx_ring_full: s.tx_ring_full,
+        rx_dropped: s.rx_dropped,
+        rx_missed: s.rx_missed,
+        tx_ring_len: s.tx_ring_len,
+        tx_ring_pending: s.tx_ring_pending,
+        rx_ring_len: s.rx_ring_len,
+        rx_ring_ready: s.rx_ring_ready,
+    }
+}
+
+/// Records that `len` bytes have been received through a socket.
+fn count_rx(stats: &mut task_net_api::SocketStats, len: usize) {
+    stats.rx_packets = stats.rx_packets.wrapping_add(1);
+    stats.rx_bytes = stats.rx_bytes.wrapping_add(len as u32);
+}
+
+/// Records that `len` bytes have been sent through a socket.
+fn count_tx(stats: &mut task_net_api::SocketStats, len: usize) {
+    stats.tx_packets = stats.tx_packets.wrapping_add(1);
+    stats.tx_bytes = stats.tx_bytes.wrapping_add(len as u32);
+}
+
+/// Checks that the task that sent `msg` owns `socket`, returning the socket's
+/// index if so.
+fn check_owner(
+    msg: &RecvMessage,
+    socket: task_net_api::SocketName,
+) -> Result<usize, idol_runtime::RequestError<task_net_api::NetError>> {
+    task_net_core::check_owner(
+        &generated::SOCKET_OWNERS,
+        msg.sender.index(),
+        socket as usize,
+    )
+    .map_err(|_| task_net_api::NetError::NotYours.into())
+}
+
+/// Converts an error from an operation on our sockets into its IPC
+/// representation.
+fn socket_error(
+    e: task_net_core::Error,
+) -> idol_runtime::RequestError<task_net_api::NetError> {
+    use idol_runtime::{ClientError, RequestError};
+    use task_net_api::NetError;
+    use task_net_core::Error;
+
+    match e {
+        Error::NoSuchSocket => {
+            RequestError::Fail(ClientError::BadMessageContents)
+        }
+        Error::NotYours => NetError::NotYours.into(),
+        Error::WrongSocketKind => NetError::WrongSocketKind.into(),
+        Error::InvalidVLan => NetError::InvalidVLan.into(),
+        Error::WrongPacket => NetError::WrongPacket.into(),
+        // TODO sending errors other than a full queue are not quite right
+        Error::QueueEmpty | Error::Send(_) => NetError::QueueEmpty.into(),
+    }
+}
+
+/// Posts notifications to the owners of sockets through the kernel.
+struct Kernel;
+
+impl task_net_core::Notifier for Kernel {
+    fn post(&mut self, task: usize, notification: u32) {
+        let task_id = sys_refresh_task_id(TaskId::for_index_and_gen(
+            task,
+            Generation::ZERO,
+        ));
+        sys_post(task_id, notification);
+    }
+}
+
 /// We can map an Ethernet MAC address into the IPv6 space as follows.
 ///
 /// - The top 64 bits are `fe80::`, putting it in the link-local (non-routable)
@@ -3,11 +3,17 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 use crate::miim_bridge::MiimBridge;
+use core::cell::Cell;
 use drv_spi_api::SpiDevice;
 use drv_stm32h7_eth::Ethernet;
 use drv_stm32xx_sys_api::{self as sys_api, OutputType, Pull, Speed, Sys};
-use ksz8463::{Error as KszError, Ksz8463, Register as KszRegister};
+use ksz8463::{
+    Error as KszError, Ksz8463, MIBCounterValue, Register as KszRegister,
+};
 use ringbuf::*;
+use task_net_api::{
+    ManagementPortStatus, ManagementStatus, MibCounter, PhyCounter,
+};
 use userlib::hl::sleep_for;
 use vsc7448_pac::phy;
 use vsc85xx::{vsc85x2::Vsc85x2, Counter, VscError};
@@ -41,6 +47,37 @@ pub struct Status {
     vsc85x2_mac_rx_good_count: [Counter; 2],
 }
 
+impl From<Status> for ManagementStatus {
+    fn from(s: Status) -> Self {
+        let mib = |c| match c {
+            MIBCounterValue::None => MibCounter::None,
+            MIBCounterValue::Count(v) => MibCounter::Count(v),
+            MIBCounterValue::CountOverflow(v) => MibCounter::CountOverflow(v),
+        };
+        let phy = |c| match c {
+            Counter::Unavailable => PhyCounter::Unavailable,
+            Counter::Inactive => PhyCounter::Inactive,
+            Counter::Value(v) => PhyCounter::Value(v),
+        };
+        let port = |i: usize| ManagementPortStatus {
+            ksz8463_100base_fx_link_up: s.ksz8463_100base_fx_link_up[i],
+            ksz8463_rx_bytes: mib(s.ksz8463_rx_bytes[i]),
+            ksz8463_tx_bytes: mib(s.ksz8463_tx_bytes[i]),
+
+            vsc85x2_100base_fx_link_up: s.vsc85x2_100base_fx_link_up[i],
+            vsc85x2_sgmii_link_up: s.vsc85x2_sgmii_link_up[i],
+
+            vsc85x2_media_tx_good_count: phy(s.vsc85x2_media_tx_good_count[i]),
+            vsc85x2_mac_tx_good_count: phy(s.vsc85x2_mac_tx_good_count[i]),
+            vsc85x2_media_rx_good_count: phy(s.vsc85x2_media_rx_good_count[i]),
+            vsc85x2_mac_rx_good_count: phy(s.vsc85x2_mac_rx_good_count[i]),
+        };
+        Self {
+            ports: [port(0), port(1)],
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum Trace {
     None,
@@ -90,7 +127,11 @@ impl Config {
         // VSC8552 over 100-BASE FX
         let ksz8463 = self.configure_ksz8463(sys);
 
-        Bsp { ksz8463, vsc85x2 }
+        Bsp {
+            ksz8463,
+            vsc85x2,
+            status: Cell::new(Status::default()),
+        }
     }
 
     fn configure_ksz8463(self, sys: &Sys) -> ksz8463::Ksz8463 {
@@ -178,9 +219,18 @@ impl Config {
 pub struct Bsp {
     pub ksz8463: Ksz8463,
     pub vsc85x2: Vsc85x2,
+
+    /// Status as of the most recent call to `wake`
+    status: Cell<Status>,
 }
 
 impl Bsp {
+    /// Returns the status of the management network as of the most recent
+    /// call to `wake`.
+    pub fn status(&self) -> ManagementStatus {
+        self.status.get().into()
+    }
+
     pub fn wake(&self, eth: &Ethernet) {
         let mut s = Status::default();
         let rw = &mut MiimBridge::new(eth);
@@ -261,5 +311,6 @@ impl Bsp {
         });
 
         ringbuf_entry!(Trace::Status(s));
+        self.status.set(s);
     }
 }
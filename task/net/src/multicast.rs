@@ -0,0 +1,436 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Multicast group membership.
+//!
+//! _smoltcp_ joins multicast groups per interface, rather than per socket, so
+//! we keep a table of the groups that sockets have joined along with a mask
+//! of their members.  The interface joins a group when its first member
+//! joins, and leaves it when its last member leaves.
+//!
+//! _smoltcp_ implements IGMP for IPv4 groups, but not MLD for IPv6 groups,
+//! and it accepts IPv6 packets regardless of their destination.  Each
+//! interface's [Policy](crate::policy::Policy) therefore has a [Listener],
+//! which keeps track of the IPv6 groups that the interface has joined: the
+//! policy drops UDP packets to any other group (or to any other unicast
+//! address), and the listener sends the MLDv2 (RFC 3810) reports that tell
+//! routers which groups we want, both when membership changes and in answer
+//! to their queries.
+
+use smoltcp::time::{Duration, Instant};
+use smoltcp::wire::{
+    EthernetAddress, EthernetFrame, EthernetProtocol, Icmpv6Message,
+    Icmpv6Packet, IpAddress, IpProtocol, Ipv6Address, Ipv6HopByHopHeader,
+    Ipv6Packet, Ipv6Repr,
+};
+use task_net_api::{Address, NetError};
+
+use crate::generated::MULTICAST_GROUPS;
+
+pub struct Groups {
+    /// each group, with a mask of the indices of its member sockets (which
+    /// is never zero)
+    table: [Option<(IpAddress, u32)>; MULTICAST_GROUPS],
+}
+
+impl Groups {
+    pub fn new() -> Self {
+        Self {
+            table: [None; MULTICAST_GROUPS],
+        }
+    }
+
+    /// Makes socket `socket` a member of `group`, returning true if it is the
+    /// group's first member (i.e. the interface needs to join the group).
+    pub fn join(
+        &mut self,
+        socket: usize,
+        group: IpAddress,
+    ) -> Result<bool, NetError> {
+        let bit = 1 << socket;
+
+        if let Some((_, mask)) =
+            self.table.iter_mut().flatten().find(|(g, _)| *g == group)
+        {
+            *mask |= bit;
+            return Ok(false);
+        }
+
+        let entry = self
+            .table
+            .iter_mut()
+            .find(|e| e.is_none())
+            .ok_or(NetError::GroupTableFull)?;
+        *entry = Some((group, bit));
+        Ok(true)
+    }
+
+    /// Removes socket `socket` from `group` (if it's a member), returning
+    /// true if it was the group's last member (i.e. the interface needs to
+    /// leave the group).
+    pub fn leave(&mut self, socket: usize, group: IpAddress) -> bool {
+        let bit = 1 << socket;
+
+        for entry in &mut self.table {
+            let mask = match entry {
+                Some((g, mask)) if *g == group => mask,
+                _ => continue,
+            };
+            if *mask & bit == 0 {
+                return false;
+            }
+            *mask &= !bit;
+            let last = *mask == 0;
+            if last {
+                *entry = None;
+            }
+            return last;
+        }
+        false
+    }
+}
+
+/// Converts `group` into a _smoltcp_ address, checking that it's a multicast
+/// group.
+pub fn group_address(group: Address) -> Result<IpAddress, NetError> {
+    let group = IpAddress::from(group);
+    if group.is_multicast() {
+        Ok(group)
+    } else {
+        Err(NetError::NotMulticast)
+    }
+}
+
+/// How many times we report each change in membership (RFC 3810's
+/// Robustness Variable), in case a report is lost.
+const ROBUSTNESS: u8 = 2;
+
+/// How long we wait between reports of a change in membership (the
+/// Unsolicited Report Interval).
+const UNSOLICITED_REPORT_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// The group that reports are sent to, `ff02::16`, and its MAC address.
+const ALL_MLDV2_ROUTERS: Ipv6Address =
+    Ipv6Address([0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x16]);
+const ALL_MLDV2_ROUTERS_MAC: EthernetAddress =
+    EthernetAddress([0x33, 0x33, 0, 0, 0, 0x16]);
+
+/// A hop-by-hop options header for MLD, which holds a Router Alert option
+/// (for MLD) followed by two bytes of padding (a `PadN` option).
+const HOP_BY_HOP: [u8; 8] = [0, 0, 0x05, 0x02, 0, 0, 0x01, 0];
+
+/// The types of record that we report (RFC 3810 section 5.2.12):  we always
+/// listen to every source, so a group's filter mode is EXCLUDE (with no
+/// sources) while we're a member, and INCLUDE (with none) once we've left.
+const MODE_IS_EXCLUDE: u8 = 0x02;
+const CHANGE_TO_INCLUDE: u8 = 0x03;
+const CHANGE_TO_EXCLUDE: u8 = 0x04;
+
+/// Each record holds its type, the lengths of its auxiliary data and its
+/// list of sources (which are both zero), and its group.
+const RECORD_LEN: usize = 20;
+
+/// Offset of the records in a report frame, after the Ethernet, IPv6,
+/// hop-by-hop and MLD headers.
+const RECORDS_OFFSET: usize = 14 + 40 + HOP_BY_HOP.len() + 8;
+
+#[derive(Copy, Clone)]
+struct Membership {
+    group: Ipv6Address,
+    /// false once we've left the group (and are still reporting that)
+    member: bool,
+    /// number of reports of the last change in membership that we have yet
+    /// to send
+    changes: u8,
+}
+
+#[derive(Copy, Clone)]
+enum Report {
+    /// a report of the changes in our membership
+    Change,
+    /// a report of our current membership, in answer to a query
+    Current,
+}
+
+/// The IPv6 groups that an interface has joined, for which it speaks MLDv2.
+pub struct Listener {
+    mac: EthernetAddress,
+    addr: Ipv6Address,
+    /// each group that the interface has joined, or has recently left
+    groups: [Option<Membership>; MULTICAST_GROUPS],
+    /// when we next need to report changes in membership, if we do
+    change_report_at: Option<Instant>,
+    /// when we need to answer a query, if we've been asked
+    query_report_at: Option<Instant>,
+}
+
+impl Listener {
+    /// Makes a listener for an interface whose MAC and (link-local) IPv6
+    /// addresses are `mac` and `addr`.
+    pub fn new(mac: EthernetAddress, addr: Ipv6Address) -> Self {
+        Self {
+            mac,
+            addr,
+            groups: [None; MULTICAST_GROUPS],
+            change_report_at: None,
+            query_report_at: None,
+        }
+    }
+
+    fn is_member(&self, group: Ipv6Address) -> bool {
+        self.groups
+            .iter()
+            .flatten()
+            .any(|m| m.member && m.group == group)
+    }
+
+    /// Joins `group`, which we must report (starting at `now`).
+    pub fn join(&mut self, group: Ipv6Address, now: Instant) {
+        self.change(group, true, now);
+    }
+
+    /// Leaves `group`, which we must report (starting at `now`).
+    pub fn leave(&mut self, group: Ipv6Address, now: Instant) {
+        self.change(group, false, now);
+    }
+
+    fn change(&mut self, group: Ipv6Address, member: bool, now: Instant) {
+        let index = self.groups.iter().position(|m| match m {
+            Some(m) => m.group == group,
+            None => false,
+        });
+        // [Groups] limits us to as many groups as we have entries for, so a
+        // new group can always have an entry that's free (or that's only
+        // still reporting that we've left its group).
+        let index = index.or_else(|| {
+            if !member {
+                return None;
+            }
+            self.groups.iter().position(|m| match m {
+                Some(m) => !m.member,
+                None => true,
+            })
+        });
+        if let Some(i) = index {
+            self.groups[i] = Some(Membership {
+                group,
+                member,
+                changes: ROBUSTNESS,
+            });
+            self.change_report_at = Some(now);
+        }
+    }
+
+    /// Checks whether an IPv6 `frame` (which holds a UDP packet) is for us,
+    /// i.e. is to our address or to a group that we've joined.  Other frames
+    /// are accepted.
+    pub fn accepts(&self, frame: &[u8]) -> bool {
+        match ipv6_packet(frame) {
+            Some(packet) => {
+                let dst = packet.dst_addr();
+                dst == self.addr || self.is_member(dst)
+            }
+            None => true,
+        }
+    }
+
+    /// Watches `frame` for MLD queries, which we answer (after a delay) with
+    /// a report of our current membership.
+    pub fn receive(&mut self, frame: &[u8], now: Instant) {
+        let (group, max_delay) = match query(frame) {
+            Some(query) => query,
+            None => return,
+        };
+        // A general query is for the unspecified address; we only answer a
+        // query for a specific group if we're a member.
+        if !group.is_unspecified() && !self.is_member(group) {
+            return;
+        }
+        // The delay should be random (so that listeners' reports don't all
+        // arrive at once):  we use the bottom byte of our MAC address, which
+        // is different for each of our neighbors.
+        let delay = max_delay * u32::from(self.mac.0[5]) / 256;
+        let at = now + delay;
+        self.query_report_at = Some(match self.query_report_at {
+            Some(earlier) => earlier.min(at),
+            None => at,
+        });
+    }
+
+    /// Returns when we next need to send a report, if ever.
+    pub fn poll_at(&self) -> Option<Instant> {
+        [self.change_report_at, self.query_report_at]
+            .into_iter()
+            .flatten()
+            .min()
+    }
+
+    /// Returns the report that's due at `now`, if any.
+    fn due(&self, now: Instant) -> Option<Report> {
+        let due = |at: Option<Instant>| at.map_or(false, |at| at <= now);
+        if due(self.change_report_at) {
+            Some(Report::Change)
+        } else if due(self.query_report_at) {
+            Some(Report::Current)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the type and group of each of the records in `report`.
+    fn records(
+        &self,
+        report: Report,
+    ) -> impl Iterator<Item = (u8, Ipv6Address)> + '_ {
+        self.groups
+            .iter()
+            .flatten()
+            .filter_map(move |m| match report {
+                Report::Change if m.changes > 0 => {
+                    let kind = if m.member {
+                        CHANGE_TO_EXCLUDE
+                    } else {
+                        CHANGE_TO_INCLUDE
+                    };
+                    Some((kind, m.group))
+                }
+                Report::Current if m.member => Some((MODE_IS_EXCLUDE, m.group)),
+                _ => None,
+            })
+    }
+
+    /// Returns the length of the frame holding the report that's due at
+    /// `now`, if any, which [Listener::emit_report] writes.
+    pub fn report_len(&mut self, now: Instant) -> Option<usize> {
+        let report = self.due(now)?;
+        match self.records(report).count() {
+            // We have no groups, so there's nothing to answer a query with.
+            0 => {
+                self.query_report_at = None;
+                None
+            }
+            n => Some(RECORDS_OFFSET + n * RECORD_LEN),
+        }
+    }
+
+    /// Writes the report that's due at `now` into `frame` (whose length is
+    /// given by [Listener::report_len]), and schedules the next one.
+    pub fn emit_report(&mut self, now: Instant, frame: &mut [u8]) {
+        let report = match self.due(now) {
+            Some(report) => report,
+            None => return,
+        };
+
+        let len = frame.len();
+        let mut eth = EthernetFrame::new_unchecked(frame);
+        eth.set_src_addr(self.mac);
+        eth.set_dst_addr(ALL_MLDV2_ROUTERS_MAC);
+        eth.set_ethertype(EthernetProtocol::Ipv6);
+
+        let mut ip = Ipv6Packet::new_unchecked(eth.payload_mut());
+        Ipv6Repr {
+            src_addr: self.addr,
+            dst_addr: ALL_MLDV2_ROUTERS,
+            next_header: IpProtocol::HopByHop,
+            payload_len: len - 14 - 40,
+            hop_limit: 1,
+        }
+        .emit(&mut ip);
+
+        let (options, mld) = ip.payload_mut().split_at_mut(HOP_BY_HOP.len());
+        options.copy_from_slice(&HOP_BY_HOP);
+        let mut options = Ipv6HopByHopHeader::new_unchecked(options);
+        options.set_next_header(IpProtocol::Icmpv6);
+
+        let mut mld = Icmpv6Packet::new_unchecked(mld);
+        mld.set_msg_type(Icmpv6Message::MldReport);
+        mld.set_msg_code(0);
+        mld.clear_reserved();
+        let mut n = 0;
+        for (record, (kind, group)) in mld
+            .payload_mut()
+            .chunks_exact_mut(RECORD_LEN)
+            .zip(self.records(report))
+        {
+            record[..4].copy_from_slice(&[kind, 0, 0, 0]);
+            record[4..].copy_from_slice(group.as_bytes());
+            n += 1;
+        }
+        mld.set_nr_mcast_addr_rcrds(n);
+        mld.fill_checksum(&self.addr.into(), &ALL_MLDV2_ROUTERS.into());
+
+        match report {
+            Report::Current => self.query_report_at = None,
+            Report::Change => self.sent_change(now),
+        }
+    }
+
+    /// Notes that we've sent a report of the changes in membership at `now`,
+    /// forgetting groups that we've finished reporting that we've left.
+    fn sent_change(&mut self, now: Instant) {
+        for entry in &mut self.groups {
+            let forget = match entry {
+                Some(m) => {
+                    m.changes = m.changes.saturating_sub(1);
+                    m.changes == 0 && !m.member
+                }
+                None => false,
+            };
+            if forget {
+                *entry = None;
+            }
+        }
+        let more = self.groups.iter().flatten().any(|m| m.changes > 0);
+        self.change_report_at = more.then(|| now + UNSOLICITED_REPORT_INTERVAL);
+    }
+}
+
+/// Returns the IPv6 packet in `frame`, if it holds one.
+fn ipv6_packet(frame: &[u8]) -> Option<Ipv6Packet<&[u8]>> {
+    let frame = EthernetFrame::new_checked(frame).ok()?;
+    if frame.ethertype() != EthernetProtocol::Ipv6 {
+        return None;
+    }
+    Ipv6Packet::new_checked(frame.payload()).ok()
+}
+
+/// Returns the group and Maximum Response Delay of an MLD query in `frame`,
+/// if it holds one that we should answer:  it must come from a link-local
+/// address, with a hop limit of 1 and a Router Alert option (which we
+/// assume is the hop-by-hop header).
+fn query(frame: &[u8]) -> Option<(Ipv6Address, Duration)> {
+    let packet = ipv6_packet(frame)?;
+    if !packet.src_addr().is_link_local()
+        || packet.hop_limit() != 1
+        || packet.next_header() != IpProtocol::HopByHop
+    {
+        return None;
+    }
+
+    let options = Ipv6HopByHopHeader::new_checked(packet.payload()).ok()?;
+    if options.next_header() != IpProtocol::Icmpv6 {
+        return None;
+    }
+    let options_len = (usize::from(options.header_len()) + 1) * 8;
+    let mld =
+        Icmpv6Packet::new_checked(packet.payload().get(options_len..)?).ok()?;
+    let (src, dst) = (packet.src_addr().into(), packet.dst_addr().into());
+    if mld.msg_type() != Icmpv6Message::MldQuery
+        || !mld.verify_checksum(&src, &dst)
+    {
+        return None;
+    }
+
+    // The Maximum Response Code is in milliseconds, but large values are in
+    // floating point (RFC 3810 section 5.1.3).
+    let code = u64::from(mld.max_resp_code());
+    let millis = if code < 0x8000 {
+        code
+    } else {
+        let mant = code & 0xfff;
+        let exp = (code >> 12) & 0x7;
+        (mant | 0x1000) << (exp + 3)
+    };
+    Some((mld.mcast_addr(), Duration::from_millis(millis)))
+}
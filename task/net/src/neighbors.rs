@@ -0,0 +1,200 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! A readable approximation of the neighbor (ARP/NDP) cache.
+//!
+//! _smoltcp_ 0.8 keeps its neighbor cache private, so each interface's
+//! [Policy](crate::policy::Policy) keeps a [Neighbors] table of the same
+//! size alongside it.  The table watches received frames for the ARP and NDP
+//! packets that _smoltcp_ learns neighbors from, and applies the same checks
+//! before filling (or evicting from) its entries in the same way.  The server
+//! flushes the table whenever _smoltcp_ flushes its cache, i.e. when the
+//! interface's addresses change.
+//!
+//! This is only an approximation, which can drift from the real cache:  the
+//! entry lifetime and the eviction rule are copies of _smoltcp_'s (which
+//! could change under us), and a frame that _smoltcp_ drops after we've
+//! seen it (or a path by which it learns that we don't model) leaves the two
+//! tables different until the entry is replaced or expires.  Anything that
+//! needs the real entries needs a _smoltcp_ that exposes its cache.
+
+use smoltcp::phy::Medium;
+use smoltcp::time::{Duration, Instant};
+use smoltcp::wire::{
+    ArpOperation, ArpPacket, ArpRepr, EthernetAddress, EthernetFrame,
+    EthernetProtocol, HardwareAddress, Icmpv6Packet, IpAddress, IpProtocol,
+    Ipv4Cidr, Ipv6Packet, NdiscNeighborFlags, NdiscRepr, RawHardwareAddress,
+};
+
+use crate::generated::NEIGHBORS;
+
+/// How long _smoltcp_ keeps an entry (a copy of its private constant).
+const ENTRY_LIFETIME: Duration = Duration::from_millis(60_000);
+
+#[derive(Copy, Clone)]
+pub struct Neighbor {
+    pub ip: IpAddress,
+    pub mac: EthernetAddress,
+    pub expires_at: Instant,
+}
+
+impl Neighbor {
+    /// Returns the number of milliseconds until the entry expires at `now`,
+    /// or zero if it has expired.
+    pub fn expires_in(&self, now: Instant) -> u32 {
+        if now < self.expires_at {
+            (self.expires_at - now).total_millis() as u32
+        } else {
+            0
+        }
+    }
+}
+
+pub struct Neighbors {
+    table: [Option<Neighbor>; NEIGHBORS],
+    /// our IPv4 address, if any:  ARP packets are only learned from if
+    /// they're for it, and from its network
+    ipv4: Option<Ipv4Cidr>,
+}
+
+impl Neighbors {
+    pub fn new() -> Self {
+        Self {
+            table: [None; NEIGHBORS],
+            ipv4: None,
+        }
+    }
+
+    /// Discards every entry, as _smoltcp_ does when the interface's
+    /// addresses are updated.
+    pub fn flush(&mut self) {
+        self.table = [None; NEIGHBORS];
+    }
+
+    /// Records our new IPv4 address, flushing the table.
+    pub fn set_ipv4(&mut self, ipv4: Option<Ipv4Cidr>) {
+        self.ipv4 = ipv4;
+        self.flush();
+    }
+
+    /// Returns the entries (including those that have expired but not yet
+    /// been evicted).
+    pub fn iter(&self) -> impl Iterator<Item = &Neighbor> {
+        self.table.iter().flatten()
+    }
+
+    /// Learns from `frame`, if it's an ARP or NDP packet that _smoltcp_ has
+    /// learned a neighbor from.
+    pub fn learn(&mut self, frame: &[u8], now: Instant) {
+        let frame = match EthernetFrame::new_checked(frame) {
+            Ok(frame) => frame,
+            Err(_) => return,
+        };
+        match frame.ethertype() {
+            EthernetProtocol::Arp => self.learn_arp(frame.payload(), now),
+            EthernetProtocol::Ipv6 => self.learn_ndp(frame.payload(), now),
+            _ => (),
+        }
+    }
+
+    fn learn_arp(&mut self, packet: &[u8], now: Instant) {
+        let repr =
+            ArpPacket::new_checked(packet).and_then(|p| ArpRepr::parse(&p));
+        let (operation, mac, ip, target) = match repr {
+            Ok(ArpRepr::EthernetIpv4 {
+                operation,
+                source_hardware_addr,
+                source_protocol_addr,
+                target_protocol_addr,
+                ..
+            }) => (
+                operation,
+                source_hardware_addr,
+                source_protocol_addr,
+                target_protocol_addr,
+            ),
+            _ => return,
+        };
+        let cidr = match self.ipv4 {
+            Some(cidr) if cidr.address() == target => cidr,
+            _ => return,
+        };
+        if let ArpOperation::Unknown(_) = operation {
+            return;
+        }
+        if ip.is_unicast() && mac.is_unicast() && cidr.contains_addr(&ip) {
+            self.fill(IpAddress::Ipv4(ip), mac, now);
+        }
+    }
+
+    fn learn_ndp(&mut self, packet: &[u8], now: Instant) {
+        let packet = match Ipv6Packet::new_checked(packet) {
+            Ok(packet) if packet.next_header() == IpProtocol::Icmpv6 => packet,
+            _ => return,
+        };
+        let src = IpAddress::Ipv6(packet.src_addr());
+        let repr = Icmpv6Packet::new_checked(packet.payload())
+            .and_then(|p| NdiscRepr::parse(&p));
+        match repr {
+            Ok(NdiscRepr::NeighborAdvert {
+                flags,
+                target_addr,
+                lladdr: Some(lladdr),
+            }) => {
+                let mac = match ethernet(lladdr) {
+                    Some(mac) if target_addr.is_unicast() => mac,
+                    _ => return,
+                };
+                if flags.contains(NdiscNeighborFlags::OVERRIDE)
+                    || !self.iter().any(|n| n.ip == src && now < n.expires_at)
+                {
+                    self.fill(src, mac, now);
+                }
+            }
+            Ok(NdiscRepr::NeighborSolicit {
+                target_addr,
+                lladdr: Some(lladdr),
+            }) => {
+                if let Some(mac) = ethernet(lladdr) {
+                    if target_addr.is_unicast() {
+                        self.fill(src, mac, now);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Records that `ip` is at `mac`, replacing its entry (if any), or else
+    /// taking a free entry or evicting the one that expires soonest.
+    fn fill(&mut self, ip: IpAddress, mac: EthernetAddress, now: Instant) {
+        let neighbor = Neighbor {
+            ip,
+            mac,
+            expires_at: now + ENTRY_LIFETIME,
+        };
+        let i = self
+            .table
+            .iter()
+            .position(|e| e.map_or(false, |n| n.ip == ip))
+            .or_else(|| self.table.iter().position(|e| e.is_none()));
+        let entry = match i {
+            Some(i) => &mut self.table[i],
+            None => self
+                .table
+                .iter_mut()
+                .min_by_key(|e| e.map(|n| n.expires_at))
+                .unwrap(),
+        };
+        *entry = Some(neighbor);
+    }
+}
+
+/// Parses a link-layer address option into a unicast Ethernet address.
+fn ethernet(lladdr: RawHardwareAddress) -> Option<EthernetAddress> {
+    match lladdr.parse(Medium::Ethernet) {
+        Ok(HardwareAddress::Ethernet(mac)) if mac.is_unicast() => Some(mac),
+        _ => None,
+    }
+}
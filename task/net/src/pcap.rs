@@ -0,0 +1,211 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Packet capture, for debugging.
+//!
+//! With the `pcap` feature, each interface's device is wrapped in a [Tap],
+//! which copies the frames that pass through it (subject to the filters in
+//! `config.net.pcap`) into a ring in RAM.  Each slot of the ring holds a pcap
+//! record (header and data), so that a debugger can dump the ring and a host
+//! tool can write the records -- oldest first -- after a pcap file header
+//! to get a capture that Wireshark can read.
+//!
+//! The hardware strips VLAN tags on receive and inserts them on transmit, so
+//! we insert the tag into the captured frame ourselves.  The hardware also
+//! computes checksums on transmit, so frames that we send are captured with
+//! their checksums zeroed.
+
+use smoltcp::phy::{Device, DeviceCapabilities, RxToken, TxToken};
+use smoltcp::time::Instant;
+use userlib::util::StaticCell;
+
+use crate::generated::{PCAP_FRAMES, PCAP_PORT, PCAP_SNAPLEN, PCAP_VID};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(u32)]
+pub enum Direction {
+    /// The slot hasn't been used yet
+    None = 0,
+    Rx = 1,
+    Tx = 2,
+}
+
+/// A captured frame.  Everything after `direction` is a pcap record, whose
+/// header fields are in our (little-endian) byte order.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct Slot {
+    pub direction: Direction,
+    /// time since boot at which the frame was captured
+    pub ts_sec: u32,
+    pub ts_usec: u32,
+    /// number of bytes of `data` that hold the frame
+    pub incl_len: u32,
+    /// length of the frame
+    pub orig_len: u32,
+    pub data: [u8; PCAP_SNAPLEN],
+}
+
+impl Slot {
+    const EMPTY: Self = Self {
+        direction: Direction::None,
+        ts_sec: 0,
+        ts_usec: 0,
+        incl_len: 0,
+        orig_len: 0,
+        data: [0; PCAP_SNAPLEN],
+    };
+}
+
+pub struct Capture {
+    /// number of frames captured; the next frame goes into slot
+    /// `count % PCAP_FRAMES`, and (once the ring is full) that is also the
+    /// oldest frame
+    pub count: u32,
+    pub slots: [Slot; PCAP_FRAMES],
+}
+
+#[used]
+static PCAP: StaticCell<Capture> = StaticCell::new(Capture {
+    count: 0,
+    slots: [Slot::EMPTY; PCAP_FRAMES],
+});
+
+impl Capture {
+    fn record(
+        &mut self,
+        direction: Direction,
+        vid: Option<u16>,
+        timestamp: Instant,
+        frame: &[u8],
+    ) {
+        let slot = &mut self.slots[self.count as usize % PCAP_FRAMES];
+        self.count = self.count.wrapping_add(1);
+
+        let mut n = 0;
+        let mut put = |bytes: &[u8]| {
+            let m = usize::min(bytes.len(), slot.data.len() - n);
+            slot.data[n..n + m].copy_from_slice(&bytes[..m]);
+            n += m;
+        };
+        let orig_len = match vid {
+            // Put the VLAN tag back between the addresses and the EtherType.
+            Some(vid) if frame.len() >= 12 => {
+                put(&frame[..12]);
+                put(&[0x81, 0x00]);
+                put(&vid.to_be_bytes());
+                put(&frame[12..]);
+                frame.len() + 4
+            }
+            _ => {
+                put(frame);
+                frame.len()
+            }
+        };
+
+        let ms = timestamp.total_millis();
+        slot.direction = direction;
+        slot.ts_sec = (ms / 1000) as u32;
+        slot.ts_usec = (ms % 1000 * 1000) as u32;
+        slot.incl_len = n as u32;
+        slot.orig_len = orig_len as u32;
+    }
+}
+
+/// Captures `frame` if it passes our filters.
+fn capture(
+    direction: Direction,
+    vid: Option<u16>,
+    timestamp: Instant,
+    frame: &[u8],
+) {
+    if PCAP_VID.is_some() && vid != PCAP_VID {
+        return;
+    }
+    if let Some(port) = PCAP_PORT {
+        match crate::policy::ports(frame) {
+            Some((_, src, dst)) if src == port || dst == port => (),
+            _ => return,
+        }
+    }
+    PCAP.borrow_mut().record(direction, vid, timestamp, frame);
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+/// A device that captures the frames passing through another device, which
+/// is on VLAN `vid` (if any).
+pub struct Tap<D> {
+    inner: D,
+    vid: Option<u16>,
+}
+
+impl<D> Tap<D> {
+    pub fn new(inner: D, vid: Option<u16>) -> Self {
+        Self { inner, vid }
+    }
+}
+
+impl<D> core::ops::Deref for Tap<D> {
+    type Target = D;
+
+    fn deref(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl<'a, D: Device<'a>> Device<'a> for Tap<D> {
+    type RxToken = TapRxToken<D::RxToken>;
+    type TxToken = TapTxToken<D::TxToken>;
+
+    fn receive(&'a mut self) -> Option<(Self::RxToken, Self::TxToken)> {
+        let vid = self.vid;
+        self.inner
+            .receive()
+            .map(|(rx, tx)| (TapRxToken(rx, vid), TapTxToken(tx, vid)))
+    }
+
+    fn transmit(&'a mut self) -> Option<Self::TxToken> {
+        let vid = self.vid;
+        self.inner.transmit().map(|tx| TapTxToken(tx, vid))
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.inner.capabilities()
+    }
+}
+
+pub struct TapRxToken<T>(T, Option<u16>);
+impl<T: RxToken> RxToken for TapRxToken<T> {
+    fn consume<R, F>(self, timestamp: Instant, f: F) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        let vid = self.1;
+        self.0.consume(timestamp, |buf| {
+            capture(Direction::Rx, vid, timestamp, buf);
+            f(buf)
+        })
+    }
+}
+
+pub struct TapTxToken<T>(T, Option<u16>);
+impl<T: TxToken> TxToken for TapTxToken<T> {
+    fn consume<R, F>(
+        self,
+        timestamp: Instant,
+        len: usize,
+        f: F,
+    ) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        let vid = self.1;
+        self.0.consume(timestamp, len, |buf| {
+            let result = f(buf);
+            capture(Direction::Tx, vid, timestamp, buf);
+            result
+        })
+    }
+}
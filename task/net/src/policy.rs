@@ -0,0 +1,379 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Per-socket receive policy.
+//!
+//! Each interface's device is wrapped in a [Policy], which looks at the UDP
+//! destination port of each received frame to find the socket that it's for.
+//! Frames that exceed the socket's rate limit are dropped before _smoltcp_
+//! sees them.
+//!
+//! When a socket's receive queue is full, _smoltcp_ drops the newest packet.
+//! For sockets whose policy is to drop the oldest packet instead, we keep a
+//! copy of each of their frames; if _smoltcp_ reports that the queue was
+//! full, we hold on to the frame and stop receiving, so that the server can
+//! discard the oldest packet in the queue (see [Policy::blocked]) before
+//! the frame is delivered again.
+//!
+//! The policy also keeps our approximation of the interface's neighbor cache
+//! (see [Neighbors]) up to date with the frames that it passes on, and holds
+//! its IPv6 multicast [Listener]:  it drops IPv6 UDP packets that aren't for
+//! us, and passes the MLD reports that the listener sends straight to the
+//! inner device.
+
+use drv_stm32h7_eth as eth;
+use smoltcp::phy::{Device, DeviceCapabilities, RxToken, TxToken};
+use smoltcp::time::Instant;
+use smoltcp::wire::{
+    EthernetAddress, EthernetFrame, EthernetProtocol, IpProtocol, Ipv4Packet,
+    Ipv6Address, Ipv6Packet,
+};
+use task_net_api::SocketStats;
+use task_net_core::SocketKind;
+
+use crate::generated::{
+    ANY_DROP_OLDEST, SOCKET_COUNT, SOCKET_DROP_OLDEST, SOCKET_KINDS,
+    SOCKET_PORTS, SOCKET_RATE_LIMITS,
+};
+use crate::multicast::Listener;
+use crate::neighbors::Neighbors;
+
+/// Size of the buffer that holds a copy of a frame for a drop-oldest socket,
+/// which we only need if there are any.
+const HOLD_SIZE: usize = if ANY_DROP_OLDEST { eth::ring::BUFSZ } else { 0 };
+
+/// A token bucket, holding thousandths of a packet so that refills are exact.
+#[derive(Copy, Clone)]
+struct Bucket {
+    millipackets: u64,
+    last: Instant,
+}
+
+impl Bucket {
+    /// Takes a packet from the bucket of a socket that can receive `rate`
+    /// packets per second in bursts of `burst`, returning false if the
+    /// bucket is empty.
+    fn take(&mut self, now: Instant, (rate, burst): (u32, u32)) -> bool {
+        let elapsed = (now - self.last).total_millis();
+        self.last = now;
+        self.millipackets = u64::min(
+            self.millipackets + elapsed * rate as u64,
+            burst as u64 * 1000,
+        );
+
+        if self.millipackets < 1000 {
+            return false;
+        }
+        self.millipackets -= 1000;
+        true
+    }
+}
+
+/// Counters of the packets that we (or _smoltcp_) have dropped for a socket.
+#[derive(Copy, Clone, Default)]
+struct Drops {
+    rate_limited: u32,
+    overflows: u32,
+}
+
+/// A frame for a drop-oldest socket, which didn't fit into its queue.
+#[derive(Copy, Clone)]
+struct Held {
+    socket: usize,
+    len: usize,
+    /// whether the server has yet to make room for the frame
+    blocked: bool,
+}
+
+pub struct State {
+    buckets: [Bucket; SOCKET_COUNT],
+    drops: [Drops; SOCKET_COUNT],
+    held: Option<Held>,
+    hold: [u8; HOLD_SIZE],
+    neighbors: Neighbors,
+    listener: Listener,
+}
+
+/// A device that applies our sockets' receive policies to the frames from
+/// another device.
+pub struct Policy<D> {
+    inner: D,
+    state: State,
+}
+
+impl<D> Policy<D> {
+    /// Wraps `inner`, under an interface whose MAC and IPv6 addresses are
+    /// `mac` and `ipv6_addr`.
+    pub fn new(inner: D, mac: EthernetAddress, ipv6_addr: Ipv6Address) -> Self {
+        let bucket = |limit: &Option<(u32, u32)>| Bucket {
+            millipackets: limit.map_or(0, |(_, burst)| burst as u64 * 1000),
+            last: Instant::from_millis(0),
+        };
+        Self {
+            inner,
+            state: State {
+                buckets: SOCKET_RATE_LIMITS.map(|l| bucket(&l)),
+                drops: [Drops::default(); SOCKET_COUNT],
+                held: None,
+                hold: [0; HOLD_SIZE],
+                neighbors: Neighbors::new(),
+                listener: Listener::new(mac, ipv6_addr),
+            },
+        }
+    }
+
+    /// Returns the socket that we're waiting for the server to make room in,
+    /// if any.  Receiving is stopped until [Policy::unblock] is called.
+    pub fn blocked(&self) -> Option<usize> {
+        self.state.held.filter(|h| h.blocked).map(|h| h.socket)
+    }
+
+    /// Resumes receiving, once the server has discarded the oldest packet in
+    /// the queue of the socket that we were blocked on.  If the queue was
+    /// empty (`made_room` is false), we give up on the held frame instead.
+    pub fn unblock(&mut self, made_room: bool) {
+        if let Some(held) = &mut self.state.held {
+            let drops = &mut self.state.drops[held.socket];
+            drops.overflows = drops.overflows.wrapping_add(1);
+            if made_room {
+                held.blocked = false;
+            } else {
+                self.state.held = None;
+            }
+        }
+    }
+
+    /// Adds our counters for socket `socket` to `stats`.
+    pub fn add_stats(&self, socket: usize, stats: &mut SocketStats) {
+        let drops = &self.state.drops[socket];
+        stats.rx_rate_limited =
+            stats.rx_rate_limited.wrapping_add(drops.rate_limited);
+        stats.rx_overflows = stats.rx_overflows.wrapping_add(drops.overflows);
+    }
+
+    /// Returns our approximation of the interface's neighbor cache.
+    pub fn neighbors(&self) -> &Neighbors {
+        &self.state.neighbors
+    }
+
+    pub fn neighbors_mut(&mut self) -> &mut Neighbors {
+        &mut self.state.neighbors
+    }
+
+    /// Returns the interface's IPv6 multicast listener.
+    pub fn listener(&self) -> &Listener {
+        &self.state.listener
+    }
+
+    pub fn listener_mut(&mut self) -> &mut Listener {
+        &mut self.state.listener
+    }
+}
+
+impl<D> Policy<D>
+where
+    D: for<'d> Device<'d>,
+{
+    /// Sends the MLD reports that are due at `now`, for as long as the inner
+    /// device can transmit them (any that can't be sent yet stay due).
+    pub fn send_reports(&mut self, now: Instant) {
+        let Self { inner, state } = self;
+        while let Some(len) = state.listener.report_len(now) {
+            let tx = match inner.transmit() {
+                Some(tx) => tx,
+                None => break,
+            };
+            let listener = &mut state.listener;
+            let sent = tx.consume(now, len, |frame| {
+                listener.emit_report(now, frame);
+                Ok(())
+            });
+            if sent.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl<D> core::ops::Deref for Policy<D> {
+    type Target = D;
+
+    fn deref(&self) -> &D {
+        &self.inner
+    }
+}
+
+impl<'a, D: Device<'a>> Device<'a> for Policy<D> {
+    type RxToken = PolicyRxToken<'a, D::RxToken>;
+    type TxToken = PolicyTxToken<D::TxToken>;
+
+    fn receive(&'a mut self) -> Option<(Self::RxToken, Self::TxToken)> {
+        let Self { inner, state } = self;
+        match state.held.map(|h| h.blocked) {
+            Some(true) => None,
+            // Deliver the held frame, even if we can't transmit a response
+            // to it right now.
+            Some(false) => Some((
+                PolicyRxToken::Held(state),
+                PolicyTxToken(inner.transmit()),
+            )),
+            None => inner.receive().map(|(rx, tx)| {
+                (PolicyRxToken::Inner(rx, state), PolicyTxToken(Some(tx)))
+            }),
+        }
+    }
+
+    fn transmit(&'a mut self) -> Option<Self::TxToken> {
+        self.inner.transmit().map(|tx| PolicyTxToken(Some(tx)))
+    }
+
+    fn capabilities(&self) -> DeviceCapabilities {
+        self.inner.capabilities()
+    }
+}
+
+pub enum PolicyRxToken<'a, T> {
+    Inner(T, &'a mut State),
+    Held(&'a mut State),
+}
+
+impl<'a, T: RxToken> RxToken for PolicyRxToken<'a, T> {
+    fn consume<R, F>(self, timestamp: Instant, f: F) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        match self {
+            Self::Inner(token, state) => token.consume(timestamp, |buf| {
+                let result = state.receive(timestamp, buf, f);
+                // _smoltcp_ learns neighbors before replying, so failing to
+                // send a reply doesn't stop it from having learned one.
+                if let Ok(_) | Err(smoltcp::Error::Exhausted) = result {
+                    state.neighbors.learn(buf, timestamp);
+                }
+                state.listener.receive(buf, timestamp);
+                result
+            }),
+            Self::Held(state) => {
+                let held = state.held.take().unwrap();
+                let result = f(&mut state.hold[..held.len]);
+                if let Err(smoltcp::Error::Exhausted) = result {
+                    state.held = Some(Held {
+                        blocked: true,
+                        ..held
+                    });
+                }
+                result
+            }
+        }
+    }
+}
+
+impl State {
+    /// Applies the policy of the socket that `frame` is for (if any) before
+    /// passing it to `f`.
+    fn receive<R, F>(
+        &mut self,
+        timestamp: Instant,
+        frame: &mut [u8],
+        f: F,
+    ) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        let socket = match ports(frame) {
+            Some((IpProtocol::Udp, _, dst)) => {
+                if !self.listener.accepts(frame) {
+                    return Err(smoltcp::Error::Dropped);
+                }
+                socket_for(dst)
+            }
+            _ => None,
+        };
+        let socket = match socket {
+            Some(socket) => socket,
+            None => return f(frame),
+        };
+
+        if let Some(limit) = SOCKET_RATE_LIMITS[socket] {
+            if !self.buckets[socket].take(timestamp, limit) {
+                let drops = &mut self.drops[socket];
+                drops.rate_limited = drops.rate_limited.wrapping_add(1);
+                return Err(smoltcp::Error::Dropped);
+            }
+        }
+
+        let oldest = SOCKET_DROP_OLDEST[socket] && frame.len() <= HOLD_SIZE;
+        if oldest {
+            self.hold[..frame.len()].copy_from_slice(frame);
+        }
+        let result = f(frame);
+        if let Err(smoltcp::Error::Exhausted) = result {
+            if oldest {
+                self.held = Some(Held {
+                    socket,
+                    len: frame.len(),
+                    blocked: true,
+                });
+            } else {
+                let drops = &mut self.drops[socket];
+                drops.overflows = drops.overflows.wrapping_add(1);
+            }
+        }
+        result
+    }
+}
+
+/// A transmit token, which may be missing if we're delivering a held frame
+/// while the device can't transmit.
+pub struct PolicyTxToken<T>(Option<T>);
+
+impl<T: TxToken> TxToken for PolicyTxToken<T> {
+    fn consume<R, F>(
+        self,
+        timestamp: Instant,
+        len: usize,
+        f: F,
+    ) -> smoltcp::Result<R>
+    where
+        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
+    {
+        match self.0 {
+            Some(token) => token.consume(timestamp, len, f),
+            None => Err(smoltcp::Error::Exhausted),
+        }
+    }
+}
+
+/// Returns the UDP socket bound to `port`, if any.
+fn socket_for(port: u16) -> Option<usize> {
+    (0..SOCKET_COUNT).find(|&i| {
+        SOCKET_KINDS[i] == SocketKind::Udp && SOCKET_PORTS[i] == port
+    })
+}
+
+/// Returns the protocol, and source and destination ports, of a UDP or TCP
+/// frame.
+pub fn ports(frame: &[u8]) -> Option<(IpProtocol, u16, u16)> {
+    let frame = EthernetFrame::new_checked(frame).ok()?;
+    let (protocol, payload) = match frame.ethertype() {
+        EthernetProtocol::Ipv4 => {
+            let packet = Ipv4Packet::new_checked(frame.payload()).ok()?;
+            (packet.protocol(), packet.payload())
+        }
+        EthernetProtocol::Ipv6 => {
+            let packet = Ipv6Packet::new_checked(frame.payload()).ok()?;
+            (packet.next_header(), packet.payload())
+        }
+        _ => return None,
+    };
+    match protocol {
+        // Both UDP and TCP headers start with the ports.
+        IpProtocol::Udp | IpProtocol::Tcp if payload.len() >= 4 => Some((
+            protocol,
+            u16::from_be_bytes([payload[0], payload[1]]),
+            u16::from_be_bytes([payload[2], payload[3]]),
+        )),
+        _ => None,
+    }
+}
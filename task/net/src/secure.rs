@@ -0,0 +1,147 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Sessions of secure sockets.
+//!
+//! With the `secure` feature, UDP sockets that have a `secure` configuration
+//! only exchange datagrams sealed with their pre-shared key (see the
+//! `secure-udp` crate).  Keys are provisioned per device, in the vital
+//! product data of the board's FRU ID EEPROM, and are read at startup; a
+//! socket whose key can't be found rejects every packet.
+//!
+//! We answer handshakes ourselves as the interface is polled, whether or not
+//! the owner is receiving, and only hand the owner the plaintext of data
+//! messages from established sessions.  Each socket's sessions (and pending
+//! handshakes, and the rate limit on hellos) are kept by a
+//! [secure_udp::Server], which has host tests of its own; this module gives
+//! it the socket's key and random numbers from the `rng` task.
+
+use drv_rng_api::Rng;
+use ringbuf::*;
+pub use secure_udp::Received;
+use secure_udp::{Message, Server, KEY_SIZE};
+use smoltcp::wire::{IpAddress, IpEndpoint};
+use task_net_api::NetError;
+use userlib::task_slot;
+use vpd::VpdError;
+
+use crate::generated::{SECURE_SESSIONS, SOCKET_COUNT, SOCKET_SECURITY};
+use crate::vpd_eeprom::{self, MAX_VPD_SIZE};
+
+task_slot!(RNG, rng_driver);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Trace {
+    None,
+    ReadFailed(vpd_eeprom::Error),
+    NoKey(usize),
+    BadKey(usize, VpdError),
+}
+ringbuf!(Trace, 4, Trace::None);
+
+/// Size of the buffer in which packets are opened and sealed, which bounds
+/// the size of datagrams on secure sockets.
+pub const BUF_SIZE: usize = 1500;
+
+/// A remote endpoint, whose hellos are rate-limited by its address.
+#[derive(Copy, Clone, PartialEq)]
+struct Remote(IpEndpoint);
+
+impl secure_udp::Endpoint for Remote {
+    type Source = IpAddress;
+
+    fn source(&self) -> IpAddress {
+        self.0.addr
+    }
+}
+
+pub struct SocketState {
+    /// the pre-shared key, once it has been read from the VPD
+    psk: Option<[u8; KEY_SIZE]>,
+    server: Server<Remote, SECURE_SESSIONS>,
+}
+
+impl SocketState {
+    pub fn new() -> Self {
+        Self {
+            psk: None,
+            server: Server::new(),
+        }
+    }
+
+    /// Handles the packet of `len` bytes at the start of `buf` from `from`,
+    /// with the socket's limit of `sessions` sessions.  (Any reply is written
+    /// over the packet, so `buf` should be larger than it.)
+    pub fn receive(
+        &mut self,
+        sessions: usize,
+        from: IpEndpoint,
+        buf: &mut [u8],
+        len: usize,
+        now: u64,
+    ) -> Received {
+        let psk = match &self.psk {
+            Some(psk) => psk,
+            None => return Received::Rejected,
+        };
+        self.server.receive(
+            psk,
+            sessions,
+            Remote(from),
+            buf,
+            len,
+            now,
+            |random| {
+                let rng = Rng::from(RNG.get_task_id());
+                matches!(rng.fill(random), Ok(n) if n == random.len())
+            },
+        )
+    }
+
+    /// Seals the plaintext at `buf[DATA_HEADER_SIZE..][..len]` for the
+    /// session with `to`, returning the length of the sealed message.
+    pub fn seal(
+        &mut self,
+        to: IpEndpoint,
+        buf: &mut [u8],
+        len: usize,
+    ) -> Result<usize, NetError> {
+        self.server
+            .seal(Remote(to), buf, len)
+            .map_err(|_| NetError::NoSession)
+    }
+}
+
+/// Reads the pre-shared key of each secure socket from the VPD, recording
+/// any that can't be found in our ringbuf.
+pub fn load_keys(states: &mut [SocketState; SOCKET_COUNT]) {
+    let mut buf = [0u8; MAX_VPD_SIZE];
+    let vpd = match vpd_eeprom::read(&mut buf) {
+        Ok(vpd) => vpd,
+        Err(e) => {
+            ringbuf_entry!(Trace::ReadFailed(e));
+            return;
+        }
+    };
+
+    for (i, (state, security)) in
+        states.iter_mut().zip(&SOCKET_SECURITY).enumerate()
+    {
+        let name = match security {
+            Some((name, _)) => name,
+            None => continue,
+        };
+        match vpd.psk(name.as_bytes()) {
+            Ok(Some(key)) => state.psk = Some(*key),
+            Ok(None) => ringbuf_entry!(Trace::NoKey(i)),
+            Err(e) => ringbuf_entry!(Trace::BadKey(i, e)),
+        }
+    }
+}
+
+/// Returns true if `packet` is a data message, which is left for the
+/// socket's owner to receive (rather than being handled as it arrives).
+pub fn is_data(packet: &[u8]) -> bool {
+    matches!(secure_udp::parse(packet), Ok(Message::Data { .. }))
+}
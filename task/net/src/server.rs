@@ -4,21 +4,51 @@
 
 //! Network IPC server implementation.
 //!
-//! This module implements a server which listens on a single IPv6 address
+//! This module implements a server which listens on a single link-local IPv6
+//! address and, if configured, an IPv4 address -- which is either static or
+//! obtained via DHCP.
+//!
+//! Sockets may be either UDP or TCP.  Each TCP socket carries at most one
+//! connection at a time:  it is either told to listen on its port (in which
+//! case it becomes the connection once a peer connects) or to connect from
+//! its port to a remote endpoint, and must be closed before it is reused.
+//!
+//! UDP sockets may be members of multicast groups, which the interface joins
+//! on behalf of their members:  _smoltcp_ speaks IGMP for IPv4 groups, and
+//! our receive policy speaks MLD for IPv6 groups (see the `multicast`
+//! module).
+//!
+//! With the `secure` feature, UDP sockets may also be secure, in which case
+//! packets are opened and sealed here (see the `secure` module).
 
 use drv_stm32h7_eth as eth;
 
 use idol_runtime::{ClientError, NotificationHandler, RequestError};
-use smoltcp::iface::{Interface, Neighbor, SocketHandle, SocketStorage};
-use smoltcp::socket::UdpSocket;
+use smoltcp::iface::{
+    Interface, Neighbor, Route, Routes, SocketHandle, SocketStorage,
+};
+use smoltcp::socket::{
+    Dhcpv4Event, Dhcpv4Socket, Socket, TcpSocket, TcpState, UdpSocket,
+};
 use smoltcp::wire::{
-    EthernetAddress, IpAddress, IpCidr, Ipv6Address, Ipv6Cidr,
+    EthernetAddress, IpAddress, IpCidr, Ipv4Address, Ipv4Cidr, Ipv6Address,
+    Ipv6Cidr,
 };
-use task_net_api::{NetError, SocketName, UdpMetadata};
-use userlib::{sys_post, sys_refresh_task_id};
+use task_net_api::{
+    Address, Endpoint, InterfaceAddresses, InterfaceStats, Ipv4Interface,
+    LoanedPacket, MacAddressBlock, ManagementStatus, NeighborEntry, NetError,
+    SocketName, SocketStats, UdpMetadata,
+};
+use userlib::sys_get_timer;
+
+use task_net_core::{SocketKind, Sockets};
 
-use crate::generated::{self, SOCKET_COUNT};
-use crate::{idl, ETH_IRQ, NEIGHBORS, WAKE_IRQ};
+use crate::generated::{self, MULTICAST_GROUPS, NEIGHBORS, SOCKET_COUNT};
+use crate::multicast::{self, Groups};
+use crate::policy::Policy;
+use crate::{
+    check_owner, count_rx, count_tx, idl, socket_error, ETH_IRQ, WAKE_IRQ,
+};
 
 /// Storage required to run a single [ServerImpl]. This should be allocated
 /// on the stack and passed into the constructor for the [ServerImpl].
@@ -26,8 +56,18 @@ pub struct ServerStorage<'a> {
     pub eth: eth::Ethernet,
 
     neighbor_cache_storage: [Option<(IpAddress, Neighbor)>; NEIGHBORS],
-    socket_storage: [SocketStorage<'a>; SOCKET_COUNT],
-    ipv6_net: [IpCidr; 1],
+
+    /// storage for our sockets, plus one for the DHCP socket (if any)
+    socket_storage: [SocketStorage<'a>; SOCKET_COUNT + 1],
+
+    /// our IPv6 address, followed by our IPv4 address (if any)
+    ip_addrs: [IpCidr; 2],
+
+    /// storage for our default IPv4 route
+    routes_storage: [Option<(IpCidr, Route)>; 1],
+
+    /// storage for the IPv4 multicast groups that the interface has joined
+    ipv4_multicast_storage: [Option<(Ipv4Address, ())>; MULTICAST_GROUPS],
 }
 
 impl<'a> ServerStorage<'a> {
@@ -36,18 +76,60 @@ impl<'a> ServerStorage<'a> {
             eth,
             neighbor_cache_storage: [None; NEIGHBORS],
             socket_storage: Default::default(),
-            ipv6_net: [Ipv6Cidr::default().into()],
+            ip_addrs: [Ipv6Cidr::default().into(), unspecified_ipv4()],
+            routes_storage: [None; 1],
+            ipv4_multicast_storage: [None; MULTICAST_GROUPS],
         }
     }
 }
 
+/// The device under our interface, which applies our sockets' receive
+/// policies.  With the `pcap` feature, it's also wrapped in a tap that
+/// captures the frames passing through it.
+#[cfg(not(feature = "pcap"))]
+type Device<'a> = Policy<&'a eth::Ethernet>;
+#[cfg(feature = "pcap")]
+type Device<'a> = Policy<crate::pcap::Tap<&'a eth::Ethernet>>;
+
+/// The IPv4 address that we have before DHCP has given us one.
+fn unspecified_ipv4() -> IpCidr {
+    Ipv4Cidr::new(Ipv4Address::UNSPECIFIED, 0).into()
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
 /// State for the running network server.
 pub struct ServerImpl<'a> {
-    socket_handles: [SocketHandle; SOCKET_COUNT],
-    iface: Interface<'a, &'a eth::Ethernet>,
+    /// our interface (we have one, with no VLANs), with our sockets
+    sockets: Sockets<'a, Device<'a>, 1, SOCKET_COUNT>,
+
+    /// state of each TCP socket as of our last call to `wake_sockets`, that
+    /// we might notify owners of changes
+    tcp_states: [TcpState; SOCKET_COUNT],
+
+    socket_stats: [SocketStats; SOCKET_COUNT],
+
+    /// multicast groups that our sockets are members of
+    groups: Groups,
+
     bsp: crate::bsp::Bsp,
+
+    macs: MacAddressBlock,
+    ipv6_addr: Ipv6Address,
+
+    /// our current IPv4 configuration, if any
+    ipv4: Option<Ipv4Interface>,
+
+    /// our DHCP socket, if we are using DHCP
+    dhcp: Option<SocketHandle>,
+
+    /// sessions of each secure socket
+    #[cfg(feature = "secure")]
+    secure: &'static mut [crate::secure::SocketState; SOCKET_COUNT],
+
+    /// buffer in which packets of secure sockets are opened and sealed
+    #[cfg(feature = "secure")]
+    secure_buf: &'static mut [u8; crate::secure::BUF_SIZE],
 }
 
 impl<'a> ServerImpl<'a> {
@@ -57,50 +139,224 @@ impl<'a> ServerImpl<'a> {
     /// Builds a new `ServerImpl`, using the provided storage space.
     pub fn new(
         storage: &'a mut ServerStorage<'a>,
-        ipv6_addr: Ipv6Address,
-        mac: EthernetAddress,
+        macs: MacAddressBlock,
         bsp: crate::bsp::Bsp,
     ) -> Self {
-        storage.ipv6_net[0] = Ipv6Cidr::new(ipv6_addr, 64).into();
+        let mac = EthernetAddress(macs.base);
+        let ipv6_addr = crate::link_local_iface_addr(mac);
+        storage.ip_addrs[0] = Ipv6Cidr::new(ipv6_addr, 64).into();
+
+        let ipv4 = generated::IPV4_ADDRESS.map(|a| Ipv4Interface {
+            address: task_net_api::Ipv4Address(a),
+            prefix: generated::IPV4_PREFIX,
+            gateway: generated::IPV4_GATEWAY.map(task_net_api::Ipv4Address),
+        });
+
+        let ipv4_cidr = ipv4.map(|c| Ipv4Cidr::new(c.address.into(), c.prefix));
+        if let Some(cidr) = ipv4_cidr {
+            storage.ip_addrs[1] = cidr.into();
+        }
+
+        let naddrs = if generated::IPV4_ENABLED { 2 } else { 1 };
+
         let neighbor_cache = smoltcp::iface::NeighborCache::new(
             &mut storage.neighbor_cache_storage[..],
         );
+        #[cfg(not(feature = "pcap"))]
+        let device = &storage.eth;
+        #[cfg(feature = "pcap")]
+        let device = crate::pcap::Tap::new(&storage.eth, None);
+        let device = Policy::new(device, mac, ipv6_addr);
+
         let mut iface = smoltcp::iface::InterfaceBuilder::new(
-            &storage.eth,
+            device,
             &mut storage.socket_storage[..],
         )
         .hardware_addr(mac.into())
         .neighbor_cache(neighbor_cache)
-        .ip_addrs(&mut storage.ipv6_net[..])
+        .ip_addrs(&mut storage.ip_addrs[..naddrs])
+        .routes(Routes::new(&mut storage.routes_storage[..]))
+        .ipv4_multicast_groups(&mut storage.ipv4_multicast_storage[..])
         .finalize();
 
+        iface.device_mut().neighbors_mut().set_ipv4(ipv4_cidr);
+
+        if let Some(gateway) = ipv4.and_then(|c| c.gateway) {
+            iface
+                .routes_mut()
+                .add_default_ipv4_route(gateway.into())
+                .map_err(|_| ())
+                .unwrap();
+        }
+
         // Create sockets and associate them with the interface.
         let sockets = generated::construct_sockets();
         let mut socket_handles = [None; generated::SOCKET_COUNT];
         for (socket, h) in sockets.0.into_iter().zip(&mut socket_handles) {
-            *h = Some(iface.add_socket(socket));
+            *h = Some(match socket {
+                Socket::Udp(s) => iface.add_socket(s),
+                Socket::Tcp(s) => iface.add_socket(s),
+                Socket::Dhcpv4(_) => unreachable!(),
+            });
         }
         let socket_handles = socket_handles.map(|h| h.unwrap());
-        // Bind sockets to their ports.
-        for (&h, &port) in socket_handles.iter().zip(&generated::SOCKET_PORTS) {
-            iface
-                .get_socket::<UdpSocket>(h)
-                .bind((ipv6_addr, port))
-                .map_err(|_| ())
-                .unwrap();
+        // Bind UDP sockets to their ports (on all of our addresses); TCP
+        // sockets are bound when they listen or connect.
+        for ((&h, &port), &kind) in socket_handles
+            .iter()
+            .zip(&generated::SOCKET_PORTS)
+            .zip(&generated::SOCKET_KINDS)
+        {
+            if kind == SocketKind::Udp {
+                iface
+                    .get_socket::<UdpSocket>(h)
+                    .bind(port)
+                    .map_err(|_| ())
+                    .unwrap();
+            }
         }
 
-        Self {
-            socket_handles,
-            iface,
+        let dhcp = if generated::IPV4_DHCP {
+            Some(iface.add_socket(Dhcpv4Socket::new()))
+        } else {
+            None
+        };
+
+        #[cfg(feature = "secure")]
+        let (secure, secure_buf) = crate::buf::claim_secure_statics();
+        #[cfg(feature = "secure")]
+        crate::secure::load_keys(secure);
+
+        let mut server = Self {
+            sockets: Sockets::new(
+                [iface],
+                [socket_handles],
+                generated::SOCKET_OWNERS,
+                generated::SOCKET_KINDS,
+                0..1,
+            ),
+            tcp_states: [TcpState::Closed; SOCKET_COUNT],
+            socket_stats: [SocketStats::default(); SOCKET_COUNT],
+            groups: Groups::new(),
             bsp,
+            macs,
+            ipv6_addr,
+            ipv4,
+            dhcp,
+            #[cfg(feature = "secure")]
+            secure,
+            #[cfg(feature = "secure")]
+            secure_buf,
+        };
+
+        // Join the multicast groups in our configuration, which has already
+        // been checked to fit into our table.
+        for &(socket_index, group) in &generated::SOCKET_MULTICAST {
+            let group = multicast::group_address(group).unwrap();
+            server.join_group(socket_index, group).unwrap();
         }
+
+        server
     }
 
-    /// Calls `smoltcp`'s internal poll function on our interface
+    /// Calls `smoltcp`'s internal poll function on our interface, and then
+    /// applies any change in our DHCP configuration (and, with the `secure`
+    /// feature, answers any handshakes on secure sockets)
     pub fn poll(&mut self, t: u64) -> smoltcp::Result<bool> {
-        self.iface
-            .poll(smoltcp::time::Instant::from_millis(t as i64))
+        let rval =
+            self.poll_iface(smoltcp::time::Instant::from_millis(t as i64));
+
+        // An error from the interface (e.g. a malformed frame) mustn't stop
+        // us from noticing that our lease has changed.
+        let dhcp = self.poll_dhcp();
+
+        #[cfg(feature = "secure")]
+        let dhcp = self.answer_handshakes() || dhcp;
+
+        rval.map(|activity| activity || dhcp)
+    }
+
+    fn poll_iface(
+        &mut self,
+        t: smoltcp::time::Instant,
+    ) -> smoltcp::Result<bool> {
+        self.iface_mut().device_mut().send_reports(t);
+        let mut activity = self.iface_mut().poll(t)?;
+
+        // A frame for a full drop-oldest socket stops us from receiving until
+        // we've made room for it.
+        while let Some(i) = self.iface().device().blocked() {
+            let socket = self.get_udp_socket_mut(i).unwrap();
+            let made_room = socket.recv().is_ok();
+            self.iface_mut().device_mut().unblock(made_room);
+            activity |= self.iface_mut().poll(t)?;
+        }
+
+        Ok(activity)
+    }
+
+    /// Returns the time at which `poll` next needs to be called (e.g. to
+    /// retransmit, to renew a DHCP lease, or to send an MLD report), if any.
+    pub fn poll_at(&mut self, t: u64) -> Option<u64> {
+        let iface = self.iface_mut();
+        let mld = iface.device().listener().poll_at();
+        iface
+            .poll_at(smoltcp::time::Instant::from_millis(t as i64))
+            .into_iter()
+            .chain(mld)
+            .min()
+            .map(|t| t.total_millis() as u64)
+    }
+
+    /// Applies any change in our DHCP configuration to the interface,
+    /// returning true if there was one.
+    fn poll_dhcp(&mut self) -> bool {
+        let handle = match self.dhcp {
+            Some(handle) => handle,
+            None => return false,
+        };
+
+        let config =
+            match self.iface_mut().get_socket::<Dhcpv4Socket>(handle).poll() {
+                None => return false,
+                Some(Dhcpv4Event::Configured(config)) => {
+                    Some((config.address, config.router))
+                }
+                Some(Dhcpv4Event::Deconfigured) => None,
+            };
+
+        let cidr = match config {
+            Some((address, _)) => address.into(),
+            None => unspecified_ipv4(),
+        };
+
+        self.iface_mut().update_ip_addrs(|addrs| addrs[1] = cidr);
+        self.iface_mut()
+            .device_mut()
+            .neighbors_mut()
+            .set_ipv4(config.map(|(address, _)| address));
+
+        let routes = self.iface_mut().routes_mut();
+
+        match config.and_then(|(_, router)| router) {
+            Some(router) => {
+                routes
+                    .add_default_ipv4_route(router)
+                    .map_err(|_| ())
+                    .unwrap();
+            }
+            None => {
+                routes.remove_default_ipv4_route();
+            }
+        }
+
+        self.ipv4 = config.map(|(address, router)| Ipv4Interface {
+            address: address.address().into(),
+            prefix: address.prefix_len(),
+            gateway: router.map(Into::into),
+        });
+
+        true
     }
 
     /// Iterate over sockets, waking any that can do work.
@@ -108,50 +364,265 @@ impl<'a> ServerImpl<'a> {
         // There's something to do! Iterate over sockets looking for work.
         // TODO making every packet O(n) in the number of sockets is super
         // lame; provide a Waker to fix this.
-        for i in 0..SOCKET_COUNT {
-            if self.get_socket_mut(i).unwrap().can_recv() {
-                // Make sure the owner knows about this. This can
-                // technically cause spurious wakeups if the owner is
-                // already waiting in our incoming queue to recv. Maybe we
-                // fix this later.
-                let (task_id, notification) = generated::SOCKET_OWNERS[i];
-                let task_id = sys_refresh_task_id(task_id);
-                sys_post(task_id, notification);
+        let tcp_states = &mut self.tcp_states;
+        self.sockets
+            .wake_sockets(&mut crate::Kernel, |iface, handle, i| {
+                // For TCP, the owner also wants to know about changes in the
+                // state of the connection (e.g. that it has been established,
+                // or that the remote end has closed it).
+                let socket = iface.get_socket::<TcpSocket>(handle);
+                let state = socket.state();
+                let ready = socket.can_recv() || state != tcp_states[i];
+                tcp_states[i] = state;
+                ready
+            });
+    }
+
+    /// Handles the packets at the front of the queue of each secure socket
+    /// that aren't data messages (i.e. handshakes, and anything that won't
+    /// open), so that handshakes are answered even if the socket's owner
+    /// isn't receiving.  Returns true if any were handled.
+    #[cfg(feature = "secure")]
+    fn answer_handshakes(&mut self) -> bool {
+        let mut handled = false;
+        for (i, security) in generated::SOCKET_SECURITY.iter().enumerate() {
+            let sessions = match security {
+                Some((_, sessions)) => *sessions,
+                None => continue,
+            };
+            loop {
+                let socket = self.get_udp_socket_mut(i).unwrap();
+                match socket.peek() {
+                    Ok((packet, _)) if !crate::secure::is_data(packet) => (),
+                    _ => break,
+                }
+                let _ = self.recv_secure_next(i, sessions);
+                handled = true;
+            }
+        }
+        handled
+    }
+
+    /// Receives from secure socket `socket_index`, which has at most
+    /// `sessions` sessions.  Handshakes are answered, and packets that don't
+    /// open are dropped, until we find a data message to return or the queue
+    /// is empty.
+    #[cfg(feature = "secure")]
+    fn recv_secure(
+        &mut self,
+        socket_index: usize,
+        sessions: usize,
+        payload: idol_runtime::Leased<idol_runtime::W, [u8]>,
+    ) -> Result<UdpMetadata, RequestError<NetError>> {
+        let (range, endp) = loop {
+            if let Some(data) = self.recv_secure_next(socket_index, sessions)? {
+                break data;
+            }
+        };
+
+        let body = &self.secure_buf[range];
+        if payload.len() < body.len() {
+            return Err(RequestError::Fail(ClientError::BadLease));
+        }
+        payload
+            .write_range(0..body.len(), body)
+            .map_err(|_| RequestError::went_away())?;
+
+        let metadata = UdpMetadata {
+            port: endp.port,
+            size: body.len() as u32,
+            addr: endp.addr.try_into().map_err(|_| ()).unwrap(),
+        };
+        count_rx(&mut self.socket_stats[socket_index], metadata.size as usize);
+        Ok(metadata)
+    }
+
+    /// Receives the next packet on secure socket `socket_index`, returning
+    /// the range of `secure_buf` that holds its plaintext (and its source) if
+    /// it's a data message.  Otherwise, it's handled here, and we return
+    /// `None`; if the queue is empty, returns `QueueEmpty`.
+    #[cfg(feature = "secure")]
+    fn recv_secure_next(
+        &mut self,
+        socket_index: usize,
+        sessions: usize,
+    ) -> Result<
+        Option<(core::ops::Range<usize>, smoltcp::wire::IpEndpoint)>,
+        RequestError<NetError>,
+    > {
+        use crate::secure::Received;
+
+        let handle = self.get_handle(socket_index)?;
+        let socket = self.sockets.iface_mut(0).get_socket::<UdpSocket>(handle);
+        // Packets that are too large for our buffer are truncated, and so
+        // won't open.
+        let (n, endp) = socket
+            .recv_slice(&mut self.secure_buf[..])
+            .map_err(|_| RequestError::from(NetError::QueueEmpty))?;
+
+        let now = sys_get_timer().now;
+        let state = &mut self.secure[socket_index];
+        match state.receive(sessions, endp, &mut self.secure_buf[..], n, now) {
+            Received::Data(range) => return Ok(Some((range, endp))),
+            Received::Reply(n) => {
+                // If this fails, the client will retry its hello.
+                let socket =
+                    self.sockets.iface_mut(0).get_socket::<UdpSocket>(handle);
+                let _ = socket.send_slice(&self.secure_buf[..n], endp);
+            }
+            Received::Rejected => {
+                let stats = &mut self.socket_stats[socket_index];
+                stats.rx_rejected = stats.rx_rejected.wrapping_add(1);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Seals the bytes loaned in `payload` for the session with the
+    /// endpoint in `metadata`, and queues them on secure socket
+    /// `socket_index`.
+    #[cfg(feature = "secure")]
+    fn send_secure(
+        &mut self,
+        socket_index: usize,
+        metadata: UdpMetadata,
+        payload: idol_runtime::Leased<idol_runtime::R, [u8]>,
+    ) -> Result<(), RequestError<NetError>> {
+        use secure_udp::{DATA_HEADER_SIZE, OVERHEAD};
+
+        let len = payload.len();
+        if len + OVERHEAD > self.secure_buf.len() {
+            return Err(RequestError::Fail(ClientError::BadLease));
+        }
+        payload
+            .read_range(0..len, &mut self.secure_buf[DATA_HEADER_SIZE..][..len])
+            .map_err(|_| RequestError::went_away())?;
+
+        let endpoint = metadata.into();
+        let n = self.secure[socket_index].seal(
+            endpoint,
+            &mut self.secure_buf[..],
+            len,
+        )?;
+
+        let handle = self.get_handle(socket_index)?;
+        let socket = self.sockets.iface_mut(0).get_socket::<UdpSocket>(handle);
+        match socket.send_slice(&self.secure_buf[..n], endpoint) {
+            Ok(()) => {
+                count_tx(&mut self.socket_stats[socket_index], len);
+                Ok(())
             }
+            Err(smoltcp::Error::Exhausted) => {
+                let stats = &mut self.socket_stats[socket_index];
+                stats.tx_overflows = stats.tx_overflows.wrapping_add(1);
+                Err(NetError::QueueEmpty.into())
+            }
+            Err(_e) => Err(NetError::QueueEmpty.into()),
         }
     }
 
+    /// Returns our interface.
+    fn iface(&self) -> &Interface<'a, Device<'a>> {
+        self.sockets.iface(0)
+    }
+
+    /// Returns our interface mutably.
+    ///
+    /// Since this claims `self` mutably, it's sometimes necessary to inline
+    /// it (as `self.sockets.iface_mut(0)`).
+    fn iface_mut(&mut self) -> &mut Interface<'a, Device<'a>> {
+        self.sockets.iface_mut(0)
+    }
+
     /// Gets the socket handle for socket `index`. If `index` is out of range,
     /// returns `BadMessage`.
-    ///
-    /// You often want `get_socket_mut` instead of this, but since it claims
-    /// `self` mutably, it is sometimes useful to inline it by calling this
-    /// followed by `eth.get_socket`.
     fn get_handle(
         &self,
         index: usize,
     ) -> Result<SocketHandle, RequestError<NetError>> {
-        self.socket_handles
-            .get(index)
-            .cloned()
-            .ok_or(RequestError::Fail(ClientError::BadMessageContents))
+        self.sockets.handle(0, index).map_err(socket_error)
     }
 
-    /// Gets the socket `index`. If `index` is out of range, returns
-    /// `BadMessage`.
-    ///
-    /// Sockets are currently assumed to be UDP.
-    fn get_socket_mut(
+    /// Gets the UDP socket `index`. If `index` is out of range, returns
+    /// `BadMessage`; if the socket isn't a UDP socket, returns
+    /// `WrongSocketKind`.
+    fn get_udp_socket_mut(
         &mut self,
         index: usize,
     ) -> Result<&mut UdpSocket<'a>, RequestError<NetError>> {
-        Ok(self.iface.get_socket::<UdpSocket>(self.get_handle(index)?))
+        self.sockets.udp_socket_mut(0, index).map_err(socket_error)
+    }
+
+    /// Gets the TCP socket `index`. If `index` is out of range, returns
+    /// `BadMessage`; if the socket isn't a TCP socket, returns
+    /// `WrongSocketKind`.
+    fn get_tcp_socket_mut(
+        &mut self,
+        index: usize,
+    ) -> Result<&mut TcpSocket<'a>, RequestError<NetError>> {
+        let handle = self.get_handle(index)?;
+
+        if generated::SOCKET_KINDS[index] != SocketKind::Tcp {
+            return Err(NetError::WrongSocketKind.into());
+        }
+
+        Ok(self.iface_mut().get_socket::<TcpSocket>(handle))
+    }
+
+    /// Makes socket `socket_index` a member of multicast group `group`,
+    /// joining the group on the interface if it's the first member.
+    fn join_group(
+        &mut self,
+        socket_index: usize,
+        group: IpAddress,
+    ) -> Result<(), NetError> {
+        if let (IpAddress::Ipv4(_), false) = (group, generated::IPV4_ENABLED) {
+            return Err(NetError::Unaddressable);
+        }
+
+        if !self.groups.join(socket_index, group)? {
+            return Ok(());
+        }
+        match group {
+            IpAddress::Ipv4(_) => {
+                // Our table is the same size as the interface's, so this can
+                // only fail to send the initial IGMP report, which isn't
+                // fatal: we'll still answer queries for the group.
+                let _ = self.iface_mut().join_multicast_group(group, now());
+            }
+            IpAddress::Ipv6(group) => {
+                let device = self.iface_mut().device_mut();
+                device.listener_mut().join(group, now());
+            }
+            _ => unreachable!(),
+        }
+        Ok(())
+    }
+
+    /// Removes socket `socket_index` from multicast group `group`, leaving
+    /// the group on the interface if it was the last member.
+    fn leave_group(&mut self, socket_index: usize, group: IpAddress) {
+        if !self.groups.leave(socket_index, group) {
+            return;
+        }
+        match group {
+            IpAddress::Ipv4(_) => {
+                // As with joining, failing to send the IGMP leave message
+                // isn't fatal; the router will time out our membership.
+                let _ = self.iface_mut().leave_multicast_group(group, now());
+            }
+            IpAddress::Ipv6(group) => {
+                let device = self.iface_mut().device_mut();
+                device.listener_mut().leave(group, now());
+            }
+            _ => unreachable!(),
+        }
     }
 
     /// Calls the `wake` function on the BSP, which handles things like
     /// periodic logging and monitoring of ports.
     pub fn wake(&self) {
-        self.bsp.wake(&self.iface.device());
+        self.bsp.wake(&self.iface().device());
     }
 }
 
@@ -171,35 +642,35 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
     ) -> Result<UdpMetadata, RequestError<NetError>> {
         let socket_index = socket as usize;
 
-        // Check that the task owns the socket.
-        if generated::SOCKET_OWNERS[socket_index].0.index()
-            != msg.sender.index()
-        {
-            return Err(NetError::NotYours.into());
+        #[cfg(feature = "secure")]
+        if let Some((_, sessions)) = generated::SOCKET_SECURITY[socket_index] {
+            check_owner(msg, socket)?;
+            return self.recv_secure(socket_index, sessions, payload);
         }
 
-        let socket = self.get_socket_mut(socket_index)?;
-        match socket.recv() {
-            Ok((body, endp)) => {
-                if payload.len() < body.len() {
-                    return Err(RequestError::Fail(ClientError::BadLease));
-                }
-                payload
-                    .write_range(0..body.len(), body)
-                    .map_err(|_| RequestError::went_away())?;
-
-                Ok(UdpMetadata {
-                    port: endp.port,
-                    size: body.len() as u32,
-                    addr: endp.addr.try_into().map_err(|_| ()).unwrap(),
-                })
-            }
-            Err(smoltcp::Error::Exhausted) => Err(NetError::QueueEmpty.into()),
-            Err(_) => {
-                // uhhhh TODO
-                Err(NetError::QueueEmpty.into())
-            }
-        }
+        let metadata = self
+            .sockets
+            .recv(
+                msg.sender.index(),
+                socket_index,
+                |body, endp, _| -> Result<_, RequestError<NetError>> {
+                    if payload.len() < body.len() {
+                        return Err(RequestError::Fail(ClientError::BadLease));
+                    }
+                    payload
+                        .write_range(0..body.len(), body)
+                        .map_err(|_| RequestError::went_away())?;
+
+                    Ok(UdpMetadata {
+                        port: endp.port,
+                        size: body.len() as u32,
+                        addr: endp.addr.try_into().map_err(|_| ()).unwrap(),
+                    })
+                },
+            )
+            .map_err(socket_error)??;
+        count_rx(&mut self.socket_stats[socket_index], metadata.size as usize);
+        Ok(metadata)
     }
 
     /// Requests to copy a packet into the tx queue of socket `socket`,
@@ -212,32 +683,352 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
         payload: idol_runtime::Leased<idol_runtime::R, [u8]>,
     ) -> Result<(), RequestError<NetError>> {
         let socket_index = socket as usize;
-        if generated::SOCKET_OWNERS[socket_index].0.index()
-            != msg.sender.index()
-        {
-            return Err(NetError::NotYours.into());
+
+        #[cfg(feature = "secure")]
+        if generated::SOCKET_SECURITY[socket_index].is_some() {
+            check_owner(msg, socket)?;
+            return self.send_secure(socket_index, metadata, payload);
         }
 
-        let socket = self.get_socket_mut(socket_index)?;
-        match socket.send(payload.len(), metadata.into()) {
-            Ok(buf) => {
-                payload
-                    .read_range(0..payload.len(), buf)
-                    .map_err(|_| RequestError::went_away())?;
+        let result = self.sockets.send(
+            msg.sender.index(),
+            socket_index,
+            0,
+            metadata.into(),
+            payload.len(),
+            |buf| payload.read_range(0..payload.len(), buf),
+        );
+        match result {
+            Ok(Ok(())) => {
+                count_tx(&mut self.socket_stats[socket_index], payload.len());
                 Ok(())
             }
-            Err(smoltcp::Error::Exhausted) => {
-                // TODO this is not quite right
-                Err(NetError::QueueEmpty.into())
+            Ok(Err(_)) => Err(RequestError::went_away()),
+            Err(e @ task_net_core::Error::Send(smoltcp::Error::Exhausted)) => {
+                let stats = &mut self.socket_stats[socket_index];
+                stats.tx_overflows = stats.tx_overflows.wrapping_add(1);
+                Err(socket_error(e))
             }
-            Err(_e) => {
-                // uhhhh TODO
-                // TODO this is not quite right
+            Err(e) => Err(socket_error(e)),
+        }
+    }
+
+    /// Returns the packet at the head of loaned socket `socket`'s queue,
+    /// leaving it there (with its payload in place in the socket's shared
+    /// region) until it's released.
+    fn peek_packet(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<LoanedPacket, RequestError<NetError>> {
+        let socket_index = socket as usize;
+        check_owner(msg, socket)?;
+        if !generated::SOCKET_LOANED[socket_index] {
+            return Err(NetError::NotLoaned.into());
+        }
+
+        self.sockets
+            .peek(msg.sender.index(), socket_index, |body, endp, _| {
+                LoanedPacket {
+                    metadata: UdpMetadata {
+                        port: endp.port,
+                        size: body.len() as u32,
+                        addr: endp.addr.try_into().map_err(|_| ()).unwrap(),
+                    },
+                    payload: body.as_ptr() as u32,
+                }
+            })
+            .map_err(socket_error)
+    }
+
+    /// Unqueues the packet at the head of loaned socket `socket`'s queue,
+    /// once its owner is done with the payload at `payload`.
+    fn release_packet(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        payload: u32,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = socket as usize;
+        check_owner(msg, socket)?;
+        if !generated::SOCKET_LOANED[socket_index] {
+            return Err(NetError::NotLoaned.into());
+        }
+
+        let mut len = 0;
+        self.sockets
+            .release(msg.sender.index(), socket_index, |body| {
+                len = body.len();
+                body.as_ptr() as u32 == payload
+            })
+            .map_err(socket_error)?;
+        count_rx(&mut self.socket_stats[socket_index], len);
+        Ok(())
+    }
+
+    /// Puts TCP socket `socket` into the listening state on its port.  Once
+    /// a peer connects, `tcp_accept` will return its endpoint.
+    fn tcp_listen(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let port = generated::SOCKET_PORTS[socket_index];
+        let socket = self.get_tcp_socket_mut(socket_index)?;
+
+        if socket.is_open() {
+            return Err(NetError::InvalidState.into());
+        }
+
+        socket
+            .listen(port)
+            .map_err(|_| NetError::InvalidState.into())
+    }
+
+    /// Returns the remote endpoint of the connection on TCP socket `socket`,
+    /// or `QueueEmpty` if the connection is still being established (either
+    /// by listening or by connecting).
+    fn tcp_accept(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<Endpoint, RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let socket = self.get_tcp_socket_mut(socket_index)?;
+
+        match socket.state() {
+            TcpState::Listen | TcpState::SynSent | TcpState::SynReceived => {
                 Err(NetError::QueueEmpty.into())
             }
+            TcpState::Closed | TcpState::TimeWait => {
+                Err(NetError::NotConnected.into())
+            }
+            _ => socket
+                .remote_endpoint()
+                .try_into()
+                .map_err(|_| NetError::NotConnected.into()),
         }
     }
 
+    /// Initiates a connection from TCP socket `socket` (using its configured
+    /// port as the local port) to `remote`.  Once the connection has been
+    /// established, `tcp_accept` will return.
+    fn tcp_connect(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        remote: Endpoint,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let port = generated::SOCKET_PORTS[socket_index];
+
+        // Check the kind of the socket before we go to get its context.
+        self.get_tcp_socket_mut(socket_index)?;
+
+        let handle = self.get_handle(socket_index)?;
+        let (socket, cx) =
+            self.iface_mut().get_socket_and_context::<TcpSocket>(handle);
+
+        match socket.connect(cx, remote, port) {
+            Ok(()) => Ok(()),
+            Err(smoltcp::Error::Illegal) => Err(NetError::InvalidState.into()),
+            Err(_) => Err(NetError::Unaddressable.into()),
+        }
+    }
+
+    /// Reads as much received data from TCP socket `socket` as fits into
+    /// `data`, returning the number of bytes read.
+    fn tcp_read(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        data: idol_runtime::Leased<idol_runtime::W, [u8]>,
+    ) -> Result<u32, RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let socket = self.get_tcp_socket_mut(socket_index)?;
+
+        if !socket.can_recv() {
+            return Err(if socket.may_recv() {
+                NetError::QueueEmpty.into()
+            } else if socket.is_active() {
+                NetError::ConnectionClosed.into()
+            } else {
+                NetError::NotConnected.into()
+            });
+        }
+
+        let result = socket.recv(|buf| {
+            let n = usize::min(buf.len(), data.len());
+            match data.write_range(0..n, &buf[..n]) {
+                Ok(()) => (n, Ok(n as u32)),
+                Err(_) => (0, Err(RequestError::went_away())),
+            }
+        });
+
+        // The only error that `recv` can return is for an invalid state,
+        // which we have already ruled out.
+        let n = result.map_err(|_| NetError::NotConnected)??;
+        count_rx(&mut self.socket_stats[socket_index], n as usize);
+        Ok(n)
+    }
+
+    /// Queues as much of `data` as fits into the transmit buffer of TCP
+    /// socket `socket`, returning the number of bytes queued -- which may be
+    /// zero if the buffer is full.
+    fn tcp_write(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        data: idol_runtime::Leased<idol_runtime::R, [u8]>,
+    ) -> Result<u32, RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let socket = self.get_tcp_socket_mut(socket_index)?;
+
+        if !socket.may_send() {
+            return Err(NetError::NotConnected.into());
+        }
+
+        let result = socket.send(|buf| {
+            let n = usize::min(buf.len(), data.len());
+            match data.read_range(0..n, &mut buf[..n]) {
+                Ok(()) => (n, Ok(n as u32)),
+                Err(_) => (0, Err(RequestError::went_away())),
+            }
+        });
+
+        let n = result.map_err(|_| NetError::NotConnected)??;
+        let stats = &mut self.socket_stats[socket_index];
+        if n > 0 {
+            count_tx(stats, n as usize);
+        } else if data.len() > 0 {
+            stats.tx_overflows = stats.tx_overflows.wrapping_add(1);
+        }
+        Ok(n)
+    }
+
+    /// Closes the connection on TCP socket `socket`, after any queued data
+    /// has been sent.  (If the socket is listening, it simply stops.)
+    fn tcp_close(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        self.get_tcp_socket_mut(socket_index)?.close();
+        Ok(())
+    }
+
+    fn addresses(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<InterfaceAddresses, RequestError<NetError>> {
+        Ok(InterfaceAddresses {
+            ipv6: self.ipv6_addr.into(),
+            ipv4: self.ipv4,
+            dhcp: generated::IPV4_DHCP,
+        })
+    }
+
+    fn mac_addresses(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<MacAddressBlock, RequestError<NetError>> {
+        Ok(self.macs)
+    }
+
+    fn interface_stats(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<InterfaceStats, RequestError<NetError>> {
+        Ok(crate::interface_stats(self.iface().device()))
+    }
+
+    fn socket_stats(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<SocketStats, RequestError<NetError>> {
+        let socket_index = socket as usize;
+        let mut stats = self
+            .socket_stats
+            .get(socket_index)
+            .cloned()
+            .ok_or(RequestError::Fail(ClientError::BadMessageContents))?;
+        self.iface().device().add_stats(socket_index, &mut stats);
+        Ok(stats)
+    }
+
+    fn management_status(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<ManagementStatus, RequestError<NetError>> {
+        self.bsp
+            .management_status()
+            .ok_or_else(|| NetError::NotSupported.into())
+    }
+
+    fn join_multicast_group(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        group: Address,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        self.get_udp_socket_mut(socket_index)?;
+        let group = multicast::group_address(group)?;
+        self.join_group(socket_index, group)?;
+        Ok(())
+    }
+
+    fn leave_multicast_group(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        group: Address,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        self.get_udp_socket_mut(socket_index)?;
+        let group = multicast::group_address(group)?;
+        self.leave_group(socket_index, group);
+        Ok(())
+    }
+
+    /// _smoltcp_ doesn't expose its neighbor cache, but flushes it whenever
+    /// the interface's addresses are updated, so we do a null update (and
+    /// flush our copy of it).
+    fn flush_neighbors(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<(), RequestError<NetError>> {
+        self.iface_mut().update_ip_addrs(|_| ());
+        self.iface_mut().device_mut().neighbors_mut().flush();
+        Ok(())
+    }
+
+    /// Reads our approximation of the neighbor cache (see
+    /// [crate::neighbors]).
+    fn approximate_neighbor(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        index: u32,
+    ) -> Result<NeighborEntry, RequestError<NetError>> {
+        let n = self
+            .iface()
+            .device()
+            .neighbors()
+            .iter()
+            .nth(index as usize)
+            .ok_or(NetError::NoSuchEntry)?;
+        Ok(NeighborEntry {
+            // We only ever fill entries for IPv4 and IPv6 addresses, so this
+            // can't fail.
+            ip: n.ip.try_into().map_err(|_| NetError::NoSuchEntry)?,
+            mac: n.mac.0,
+            expires_in: n.expires_in(now()),
+        })
+    }
+
     fn smi_read(
         &mut self,
         _msg: &userlib::RecvMessage,
@@ -245,7 +1036,7 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
         register: u8,
     ) -> Result<u16, RequestError<NetError>> {
         // TODO: this should not be open to all callers!
-        Ok(self.iface.device().smi_read(phy, register))
+        Ok(self.iface().device().smi_read(phy, register))
     }
 
     fn smi_write(
@@ -256,10 +1047,15 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
         value: u16,
     ) -> Result<(), RequestError<NetError>> {
         // TODO: this should not be open to all callers!
-        Ok(self.iface.device().smi_write(phy, register, value))
+        Ok(self.iface().device().smi_write(phy, register, value))
     }
 }
 
+/// Returns the current time, as _smoltcp_ wants it.
+fn now() -> smoltcp::time::Instant {
+    smoltcp::time::Instant::from_millis(sys_get_timer().now as i64)
+}
+
 impl NotificationHandler for ServerImpl<'_> {
     fn current_notification_mask(&self) -> u32 {
         // We're always listening for our interrupt or the wake (timer) irq
@@ -269,7 +1065,7 @@ impl NotificationHandler for ServerImpl<'_> {
     fn handle_notification(&mut self, bits: u32) {
         // Interrupt dispatch.
         if bits & ETH_IRQ != 0 {
-            self.iface.device().on_interrupt();
+            self.iface().device().on_interrupt();
             userlib::sys_irq_control(ETH_IRQ, true);
         }
         // The wake IRQ is handled in the main `net` loop
@@ -8,20 +8,35 @@
 //! and supports some number of VLANs
 //!
 //! TODO: one IPv6 address per VLAN?
+//!
+//! TCP sockets and IPv4 are not supported with VLANs (which is enforced when
+//! the configuration is loaded), so the TCP operations always fail.
+//!
+//! Sockets may be members of (IPv6) multicast groups, which apply to all
+//! VLANs:  each VLAN's interface joins a group on behalf of its members, with
+//! MLD (see the `multicast` module).
 
 use drv_stm32h7_eth as eth;
 
 use idol_runtime::{ClientError, NotificationHandler, RequestError};
-use smoltcp::iface::{Interface, Neighbor, SocketHandle, SocketStorage};
-use smoltcp::socket::UdpSocket;
+use smoltcp::iface::{Interface, Neighbor, SocketStorage};
+use smoltcp::socket::{Socket, UdpSocket};
 use smoltcp::wire::{
     EthernetAddress, IpAddress, IpCidr, Ipv6Address, Ipv6Cidr,
 };
-use task_net_api::{NetError, SocketName, UdpMetadata};
-use userlib::{sys_post, sys_refresh_task_id};
+use task_net_api::{
+    Address, Endpoint, InterfaceAddresses, InterfaceStats, LoanedPacket,
+    MacAddressBlock, ManagementStatus, NeighborEntry, NetError, SocketName,
+    SocketStats, UdpMetadata,
+};
+use task_net_core::{Sockets, VLanEthernet};
 
-use crate::generated::{self, SOCKET_COUNT, VLAN_COUNT, VLAN_RANGE};
-use crate::{idl, ETH_IRQ, NEIGHBORS, WAKE_IRQ};
+use crate::generated::{self, NEIGHBORS, SOCKET_COUNT, VLAN_COUNT, VLAN_RANGE};
+use crate::multicast::{self, Groups};
+use crate::policy::Policy;
+use crate::{
+    check_owner, count_rx, count_tx, idl, socket_error, ETH_IRQ, WAKE_IRQ,
+};
 
 type NeighborStorage = Option<(IpAddress, Neighbor)>;
 
@@ -38,7 +53,7 @@ impl<'a> ServerStorage<'a> {
     pub fn new(eth: eth::Ethernet) -> Self {
         Self {
             eth,
-            neighbor_cache_storage: Default::default(),
+            neighbor_cache_storage: [[None; NEIGHBORS]; VLAN_COUNT],
             socket_storage: Default::default(),
             ipv6_net: [Ipv6Cidr::default().into(); VLAN_COUNT],
         }
@@ -47,79 +62,31 @@ impl<'a> ServerStorage<'a> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
-pub struct VLanEthernet<'a> {
-    pub eth: &'a eth::Ethernet,
-    pub vid: u16,
-}
-
-impl<'a, 'b> smoltcp::phy::Device<'a> for VLanEthernet<'b> {
-    type RxToken = VLanRxToken<'a>;
-    type TxToken = VLanTxToken<'a>;
-
-    fn receive(&'a mut self) -> Option<(Self::RxToken, Self::TxToken)> {
-        if self.eth.vlan_can_recv(self.vid, VLAN_RANGE) && self.eth.can_send() {
-            Some((
-                VLanRxToken(self.eth, self.vid),
-                VLanTxToken(self.eth, self.vid),
-            ))
-        } else {
-            None
-        }
-    }
-    fn transmit(&'a mut self) -> Option<Self::TxToken> {
-        if self.eth.can_send() {
-            Some(VLanTxToken(self.eth, self.vid))
-        } else {
-            None
-        }
-    }
-    fn capabilities(&self) -> smoltcp::phy::DeviceCapabilities {
-        self.eth.capabilities()
-    }
-}
-
-////////////////////////////////////////////////////////////////////////////////
-
-pub struct VLanRxToken<'a>(&'a eth::Ethernet, u16);
-impl<'a> smoltcp::phy::RxToken for VLanRxToken<'a> {
-    fn consume<R, F>(
-        self,
-        _timestamp: smoltcp::time::Instant,
-        f: F,
-    ) -> smoltcp::Result<R>
-    where
-        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
-    {
-        self.0.vlan_recv(self.1, f)
-    }
-}
-
-pub struct VLanTxToken<'a>(&'a eth::Ethernet, u16);
-impl<'a> smoltcp::phy::TxToken for VLanTxToken<'a> {
-    fn consume<R, F>(
-        self,
-        _timestamp: smoltcp::time::Instant,
-        len: usize,
-        f: F,
-    ) -> smoltcp::Result<R>
-    where
-        F: FnOnce(&mut [u8]) -> smoltcp::Result<R>,
-    {
-        self.0
-            .vlan_try_send(len, self.1, f)
-            .expect("TX token existed without descriptor available")
-    }
-}
-
-////////////////////////////////////////////////////////////////////////////////
+/// The device under each VLAN's interface, which applies our sockets' receive
+/// policies (separately for each VLAN).  With the `pcap` feature, it's also
+/// wrapped in a tap that captures the frames passing through it.
+#[cfg(not(feature = "pcap"))]
+type Device<'a> = Policy<VLanEthernet<'a, eth::Ethernet>>;
+#[cfg(feature = "pcap")]
+type Device<'a> = Policy<crate::pcap::Tap<VLanEthernet<'a, eth::Ethernet>>>;
 
 /// State for the running network server
 pub struct ServerImpl<'a> {
     eth: &'a eth::Ethernet,
 
-    socket_handles: [[SocketHandle; SOCKET_COUNT]; VLAN_COUNT],
-    ifaces: [Interface<'a, VLanEthernet<'a>>; VLAN_COUNT],
+    /// each VLAN's interface, with its copy of our sockets
+    sockets: Sockets<'a, Device<'a>, VLAN_COUNT, SOCKET_COUNT>,
+    socket_stats: [SocketStats; SOCKET_COUNT],
+
+    /// multicast groups that our sockets are members of
+    groups: Groups,
+
     bsp: crate::bsp::Bsp,
+
+    macs: MacAddressBlock,
+
+    /// IPv6 address of the first VLAN
+    ipv6_addr: Ipv6Address,
 }
 
 impl<'a> ServerImpl<'a> {
@@ -129,14 +96,16 @@ impl<'a> ServerImpl<'a> {
     /// Builds a new `ServerImpl`, using the provided storage space.
     pub fn new(
         storage: &'a mut ServerStorage<'a>,
-        mut ipv6_addr: Ipv6Address,
-        mut mac: EthernetAddress,
+        macs: MacAddressBlock,
         bsp: crate::bsp::Bsp,
     ) -> Self {
+        let first_ipv6_addr =
+            crate::link_local_iface_addr(EthernetAddress(macs.base));
+
         // Local storage; this will end up owned by the returned ServerImpl.
         let mut socket_handles = [[Default::default(); generated::SOCKET_COUNT];
             generated::VLAN_COUNT];
-        let mut ifaces: [Option<Interface<'a, VLanEthernet<'a>>>; VLAN_COUNT] =
+        let mut ifaces: [Option<Interface<'a, Device<'a>>>; VLAN_COUNT] =
             Default::default();
 
         // We're iterating over a bunch of things together.  The standard
@@ -153,18 +122,30 @@ impl<'a> ServerImpl<'a> {
         let sockets = generated::construct_sockets();
         assert_eq!(sockets.0.len(), VLAN_COUNT);
 
-        for sockets in sockets.0.into_iter() {
+        for (i, sockets) in sockets.0.into_iter().enumerate() {
+            // Each VLAN has its own MAC address, and a link-local IPv6
+            // address derived from it.
+            let mac = EthernetAddress(macs.nth(i as u16).unwrap());
+            let ipv6_addr = crate::link_local_iface_addr(mac);
+
             let neighbor_cache_storage = neighbor_cache_iter.next().unwrap();
             let neighbor_cache = smoltcp::iface::NeighborCache::new(
                 &mut neighbor_cache_storage[..],
             );
 
+            let vid = vid_iter.next().unwrap();
+            let device = VLanEthernet {
+                mac: &storage.eth,
+                vid,
+                vid_range: VLAN_RANGE,
+            };
+            #[cfg(feature = "pcap")]
+            let device = crate::pcap::Tap::new(device, Some(vid));
+            let device = Policy::new(device, mac, ipv6_addr);
+
             let socket_storage = socket_storage_iter.next().unwrap();
             let builder = smoltcp::iface::InterfaceBuilder::new(
-                VLanEthernet {
-                    eth: &storage.eth,
-                    vid: vid_iter.next().unwrap(),
-                },
+                device,
                 &mut socket_storage[..],
             );
 
@@ -180,9 +161,14 @@ impl<'a> ServerImpl<'a> {
             let socket_handles = socket_handles_iter.next().unwrap();
             assert_eq!(sockets.len(), SOCKET_COUNT);
             for (s, h) in sockets.into_iter().zip(&mut socket_handles[..]) {
-                *h = iface.add_socket(s);
+                *h = match s {
+                    Socket::Udp(s) => iface.add_socket(s),
+                    Socket::Tcp(_) | Socket::Dhcpv4(_) => unreachable!(),
+                };
             }
-            // Bind sockets to their ports.
+            // Bind sockets to their ports, on any address so that they can
+            // receive multicast; our policy drops packets to addresses that
+            // aren't this VLAN's, or groups that it hasn't joined.
             assert_eq!(socket_handles.len(), SOCKET_COUNT);
             assert_eq!(generated::SOCKET_PORTS.len(), SOCKET_COUNT);
             for (&h, &port) in
@@ -190,83 +176,107 @@ impl<'a> ServerImpl<'a> {
             {
                 iface
                     .get_socket::<UdpSocket>(h)
-                    .bind((ipv6_addr, port))
+                    .bind(port)
                     .map_err(|_| ())
                     .unwrap();
             }
             *ifaces_iter.next().unwrap() = Some(iface);
-
-            // Increment the MAC and IP addresses so that each VLAN has
-            // a unique address.
-            ipv6_addr.0[15] += 1;
-            mac.0[5] += 1;
         }
 
         let ifaces = ifaces.map(|e| e.unwrap());
-        Self {
+        let mut server = Self {
             eth: &storage.eth,
-            socket_handles,
-            ifaces,
+            sockets: Sockets::new(
+                ifaces,
+                socket_handles,
+                generated::SOCKET_OWNERS,
+                generated::SOCKET_KINDS,
+                VLAN_RANGE,
+            ),
+            socket_stats: [SocketStats::default(); SOCKET_COUNT],
+            groups: Groups::new(),
             bsp,
+            macs,
+            ipv6_addr: first_ipv6_addr,
+        };
+
+        // Join the multicast groups in our configuration, which has already
+        // been checked to fit into our table (and to be IPv6).
+        for &(socket_index, group) in &generated::SOCKET_MULTICAST {
+            let group = multicast::group_address(group).unwrap();
+            server.join_group(socket_index, group).unwrap();
         }
+
+        server
     }
 
     pub fn poll(&mut self, t: u64) -> smoltcp::Result<bool> {
         let t = smoltcp::time::Instant::from_millis(t as i64);
         let mut any_activity = false;
-        for iface in &mut self.ifaces {
-            any_activity |= iface.poll(t)?;
+        for v in 0..VLAN_COUNT {
+            self.sockets.iface_mut(v).device_mut().send_reports(t);
+            any_activity |= self.sockets.iface_mut(v).poll(t)?;
+
+            // A frame for a full drop-oldest socket stops this VLAN from
+            // receiving until we've made room for it.
+            while let Some(i) = self.sockets.iface(v).device().blocked() {
+                let socket = self.sockets.udp_socket_mut(v, i).unwrap();
+                let made_room = socket.recv().is_ok();
+                let iface = self.sockets.iface_mut(v);
+                iface.device_mut().unblock(made_room);
+                any_activity |= iface.poll(t)?;
+            }
         }
         Ok(any_activity)
     }
 
-    /// Iterate over sockets, waking any that can do work.  A task can do work
-    /// if all of the (internal) VLAN sockets can receive a packet, since
-    /// we don't know which VLAN it will write to.
-    pub fn wake_sockets(&mut self) {
-        for i in 0..SOCKET_COUNT {
-            if (0..VLAN_COUNT)
-                .any(|v| self.get_socket_mut(i, v).unwrap().can_recv())
-            {
-                let (task_id, notification) = generated::SOCKET_OWNERS[i];
-                let task_id = sys_refresh_task_id(task_id);
-                sys_post(task_id, notification);
-            }
-        }
+    /// Returns the time at which `poll` next needs to be called (including
+    /// to send an MLD report), if any.
+    pub fn poll_at(&mut self, t: u64) -> Option<u64> {
+        let t = smoltcp::time::Instant::from_millis(t as i64);
+        self.sockets
+            .ifaces_mut()
+            .iter_mut()
+            .flat_map(|iface| {
+                let mld = iface.device().listener().poll_at();
+                iface.poll_at(t).into_iter().chain(mld)
+            })
+            .min()
+            .map(|t| t.total_millis() as u64)
     }
 
-    pub fn wake(&self) {
-        self.bsp.wake(&self.eth)
+    /// Makes socket `socket_index` a member of multicast group `group`,
+    /// joining the group on each VLAN's interface if it's the first member.
+    fn join_group(
+        &mut self,
+        socket_index: usize,
+        group: IpAddress,
+    ) -> Result<(), NetError> {
+        // IPv4 is not supported with VLANs.
+        let group = match group {
+            IpAddress::Ipv6(group) => group,
+            _ => return Err(NetError::Unaddressable),
+        };
+
+        if self.groups.join(socket_index, group.into())? {
+            let now = now();
+            for iface in self.sockets.ifaces_mut() {
+                iface.device_mut().listener_mut().join(group, now);
+            }
+        }
+        Ok(())
     }
 
-    fn get_handle(
-        &self,
-        index: usize,
-        vlan_index: usize,
-    ) -> Result<SocketHandle, RequestError<NetError>> {
-        self.socket_handles
-            .get(vlan_index)
-            .ok_or(RequestError::Fail(ClientError::BadMessageContents))
-            .and_then(|s| {
-                s.get(index)
-                    .cloned()
-                    .ok_or(RequestError::Fail(ClientError::BadMessageContents))
-            })
+    /// Iterate over sockets, waking any that can do work.  A task can do work
+    /// if any of the (internal) VLAN sockets can receive a packet.
+    pub fn wake_sockets(&mut self) {
+        // All of our sockets are UDP sockets.
+        self.sockets
+            .wake_sockets(&mut crate::Kernel, |_, _, _| unreachable!());
     }
 
-    /// Gets the socket `index`. If `index` is out of range, returns
-    /// `BadMessage`. Panics if `vlan_index` is out of range, which should
-    /// never happen (because messages with invalid VIDs are dropped in
-    /// RxRing).
-    ///
-    /// Sockets are currently assumed to be UDP.
-    fn get_socket_mut(
-        &mut self,
-        index: usize,
-        vlan_index: usize,
-    ) -> Result<&mut UdpSocket<'a>, RequestError<NetError>> {
-        Ok(self.ifaces[vlan_index]
-            .get_socket::<UdpSocket>(self.get_handle(index, vlan_index)?))
+    pub fn wake(&self) {
+        self.bsp.wake(&self.eth)
     }
 }
 
@@ -286,18 +296,14 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
     ) -> Result<UdpMetadata, RequestError<NetError>> {
         let socket_index = socket as usize;
 
-        if generated::SOCKET_OWNERS[socket_index].0.index()
-            != msg.sender.index()
-        {
-            return Err(NetError::NotYours.into());
-        }
-
-        // Iterate over all of the per-VLAN sockets, returning the first
-        // available packet with a bonus `vid` tag attached in the metadata.
-        for (i, vid) in VLAN_RANGE.enumerate() {
-            let socket = self.get_socket_mut(socket_index, i)?;
-            match socket.recv() {
-                Ok((body, endp)) => {
+        // Receive the first available packet on any of the per-VLAN sockets,
+        // with a bonus `vid` tag attached in the metadata.
+        let metadata = self
+            .sockets
+            .recv(
+                msg.sender.index(),
+                socket_index,
+                |body, endp, vid| -> Result<_, RequestError<NetError>> {
                     if payload.len() < body.len() {
                         return Err(RequestError::Fail(ClientError::BadLease));
                     }
@@ -305,23 +311,17 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
                         .write_range(0..body.len(), body)
                         .map_err(|_| RequestError::went_away())?;
 
-                    return Ok(UdpMetadata {
+                    Ok(UdpMetadata {
                         port: endp.port,
                         size: body.len() as u32,
                         addr: endp.addr.try_into().map_err(|_| ()).unwrap(),
                         vid,
-                    });
-                }
-                Err(smoltcp::Error::Exhausted) => {
-                    // Keep iterating
-                }
-                Err(_) => {
-                    // uhhhh TODO
-                    // (keep iterating in the meantime)
-                }
-            }
-        }
-        Err(NetError::QueueEmpty.into())
+                    })
+                },
+            )
+            .map_err(socket_error)??;
+        count_rx(&mut self.socket_stats[socket_index], metadata.size as usize);
+        Ok(metadata)
     }
 
     /// Requests to copy a packet into the tx queue of socket `socket`,
@@ -334,36 +334,221 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
         payload: idol_runtime::Leased<idol_runtime::R, [u8]>,
     ) -> Result<(), RequestError<NetError>> {
         let socket_index = socket as usize;
-        if generated::SOCKET_OWNERS[socket_index].0.index()
-            != msg.sender.index()
-        {
-            return Err(NetError::NotYours.into());
-        }
 
-        // Convert from absolute VID to an index in our VLAN array
-        if !VLAN_RANGE.contains(&metadata.vid) {
-            return Err(NetError::InvalidVLan.into());
-        }
-        let vlan_index = metadata.vid - VLAN_RANGE.start;
-
-        let socket = self.get_socket_mut(socket_index, vlan_index as usize)?;
-        match socket.send(payload.len(), metadata.into()) {
-            Ok(buf) => {
-                payload
-                    .read_range(0..payload.len(), buf)
-                    .map_err(|_| RequestError::went_away())?;
+        let result = self.sockets.send(
+            msg.sender.index(),
+            socket_index,
+            metadata.vid,
+            metadata.into(),
+            payload.len(),
+            |buf| payload.read_range(0..payload.len(), buf),
+        );
+        match result {
+            Ok(Ok(())) => {
+                count_tx(&mut self.socket_stats[socket_index], payload.len());
                 Ok(())
             }
-            Err(smoltcp::Error::Exhausted) => {
-                // TODO this is not quite right
-                Err(NetError::QueueEmpty.into())
+            Ok(Err(_)) => Err(RequestError::went_away()),
+            Err(e @ task_net_core::Error::Send(smoltcp::Error::Exhausted)) => {
+                let stats = &mut self.socket_stats[socket_index];
+                stats.tx_overflows = stats.tx_overflows.wrapping_add(1);
+                Err(socket_error(e))
             }
-            Err(_e) => {
-                // uhhhh TODO
-                // TODO this is not quite right
-                Err(NetError::QueueEmpty.into())
+            Err(e) => Err(socket_error(e)),
+        }
+    }
+
+    /// Loans aren't supported with VLANs (`build_net` rejects them).
+    fn peek_packet(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<LoanedPacket, RequestError<NetError>> {
+        check_owner(msg, socket)?;
+        Err(NetError::NotLoaned.into())
+    }
+
+    fn release_packet(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        _payload: u32,
+    ) -> Result<(), RequestError<NetError>> {
+        check_owner(msg, socket)?;
+        Err(NetError::NotLoaned.into())
+    }
+
+    fn tcp_listen(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+    ) -> Result<(), RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn tcp_accept(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+    ) -> Result<Endpoint, RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn tcp_connect(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+        _remote: Endpoint,
+    ) -> Result<(), RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn tcp_read(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+        _data: idol_runtime::Leased<idol_runtime::W, [u8]>,
+    ) -> Result<u32, RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn tcp_write(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+        _data: idol_runtime::Leased<idol_runtime::R, [u8]>,
+    ) -> Result<u32, RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn tcp_close(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        _socket: SocketName,
+    ) -> Result<(), RequestError<NetError>> {
+        Err(NetError::WrongSocketKind.into())
+    }
+
+    fn addresses(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<InterfaceAddresses, RequestError<NetError>> {
+        Ok(InterfaceAddresses {
+            ipv6: self.ipv6_addr.into(),
+            ipv4: None,
+            dhcp: false,
+        })
+    }
+
+    fn mac_addresses(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<MacAddressBlock, RequestError<NetError>> {
+        Ok(self.macs)
+    }
+
+    fn interface_stats(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<InterfaceStats, RequestError<NetError>> {
+        Ok(crate::interface_stats(self.eth))
+    }
+
+    fn socket_stats(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        socket: SocketName,
+    ) -> Result<SocketStats, RequestError<NetError>> {
+        let socket_index = socket as usize;
+        let mut stats = self
+            .socket_stats
+            .get(socket_index)
+            .cloned()
+            .ok_or(RequestError::Fail(ClientError::BadMessageContents))?;
+        for iface in self.sockets.ifaces() {
+            iface.device().add_stats(socket_index, &mut stats);
+        }
+        Ok(stats)
+    }
+
+    fn management_status(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<ManagementStatus, RequestError<NetError>> {
+        self.bsp
+            .management_status()
+            .ok_or_else(|| NetError::NotSupported.into())
+    }
+
+    fn join_multicast_group(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        group: Address,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let group = multicast::group_address(group)?;
+        self.join_group(socket_index, group)?;
+        Ok(())
+    }
+
+    fn leave_multicast_group(
+        &mut self,
+        msg: &userlib::RecvMessage,
+        socket: SocketName,
+        group: Address,
+    ) -> Result<(), RequestError<NetError>> {
+        let socket_index = check_owner(msg, socket)?;
+        let group = multicast::group_address(group)?;
+
+        if self.groups.leave(socket_index, group) {
+            if let IpAddress::Ipv6(group) = group {
+                let now = now();
+                for iface in self.sockets.ifaces_mut() {
+                    iface.device_mut().listener_mut().leave(group, now);
+                }
             }
         }
+        Ok(())
+    }
+
+    /// _smoltcp_ doesn't expose its neighbor cache, but flushes it whenever
+    /// the interface's addresses are updated, so we do a null update of
+    /// each VLAN's interface (and flush our copy of its cache).
+    fn flush_neighbors(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+    ) -> Result<(), RequestError<NetError>> {
+        for iface in self.sockets.ifaces_mut() {
+            iface.update_ip_addrs(|_| ());
+            iface.device_mut().neighbors_mut().flush();
+        }
+        Ok(())
+    }
+
+    /// Reads our approximations of the VLANs' neighbor caches (see
+    /// [crate::neighbors]), counting through each VLAN's in turn.
+    fn approximate_neighbor(
+        &mut self,
+        _msg: &userlib::RecvMessage,
+        index: u32,
+    ) -> Result<NeighborEntry, RequestError<NetError>> {
+        let now = now();
+        let (vid, n) = VLAN_RANGE
+            .zip(self.sockets.ifaces())
+            .flat_map(|(vid, iface)| {
+                iface.device().neighbors().iter().map(move |n| (vid, n))
+            })
+            .nth(index as usize)
+            .ok_or(NetError::NoSuchEntry)?;
+        Ok(NeighborEntry {
+            // We only ever fill entries for IPv6 addresses, so this can't
+            // fail.
+            ip: n.ip.try_into().map_err(|_| NetError::NoSuchEntry)?,
+            mac: n.mac.0,
+            expires_in: n.expires_in(now),
+            vid,
+        })
     }
 
     fn smi_read(
@@ -388,6 +573,11 @@ impl idl::InOrderNetImpl for ServerImpl<'_> {
     }
 }
 
+/// Returns the current time, as _smoltcp_ wants it.
+fn now() -> smoltcp::time::Instant {
+    smoltcp::time::Instant::from_millis(userlib::sys_get_timer().now as i64)
+}
+
 impl NotificationHandler for ServerImpl<'_> {
     fn current_notification_mask(&self) -> u32 {
         // We're always listening for our interrupt or the wake (timer) irq
@@ -0,0 +1,59 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Reading the vital product data (VPD) in the board's FRU ID EEPROM, via
+//! the `eeprom` task.
+//!
+//! The VPD holds our per-device provisioning:  with the `vpd-mac` feature,
+//! our MAC addresses, and with the `secure` feature, the pre-shared keys of
+//! secure sockets.
+
+use drv_eeprom_api::{Eeprom, EepromError};
+use userlib::task_slot;
+use vpd::{Vpd, VpdError, HEADER_SIZE};
+
+task_slot!(EEPROM, eeprom);
+
+/// Largest VPD that we're willing to read (onto our stack).
+pub const MAX_VPD_SIZE: usize = 512;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    Eeprom(EepromError),
+    Vpd(VpdError),
+    TooLarge(usize),
+}
+
+/// Reads the VPD into `buf`, returning it once it has been validated.
+pub fn read(buf: &mut [u8; MAX_VPD_SIZE]) -> Result<Vpd<'_>, Error> {
+    let eeprom = Eeprom::from(EEPROM.get_task_id());
+
+    // Read the header first, to find out how much we need to read.
+    let len = read_len(&eeprom, buf)?;
+    if len > buf.len() {
+        return Err(Error::TooLarge(len));
+    }
+    eeprom
+        .read(HEADER_SIZE as u16, &mut buf[HEADER_SIZE..len])
+        .map_err(Error::Eeprom)?;
+
+    Vpd::parse(&buf[..len]).map_err(Error::Vpd)
+}
+
+/// Reads the VPD header into the start of `buf`, returning the total length
+/// of the VPD (including the header).
+fn read_len(eeprom: &Eeprom, buf: &mut [u8]) -> Result<usize, Error> {
+    let header = &mut buf[..HEADER_SIZE];
+    eeprom.read(0, header).map_err(Error::Eeprom)?;
+
+    // Parsing the header alone checks the magic; it fails on the length
+    // (unless the VPD is empty), which is what we're after.
+    match Vpd::parse(header) {
+        Ok(_) | Err(VpdError::Truncated) => (),
+        Err(e) => return Err(Error::Vpd(e)),
+    }
+
+    let len = u32::from_le_bytes(header[4..8].try_into().unwrap());
+    Ok(HEADER_SIZE.saturating_add(len as usize))
+}
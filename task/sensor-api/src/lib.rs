@@ -26,6 +26,56 @@ impl From<SensorId> for usize {
     }
 }
 
+/// Number of recent samples retained for each sensor
+pub const SENSOR_HISTORY: usize = 4;
+
+///
+/// Summary statistics for a sensor, as accumulated over all of the readings
+/// that have been posted to it.  If `readings` is zero, `min`, `max` and
+/// `mean` are meaningless.
+///
+#[derive(
+    zerocopy::AsBytes, zerocopy::FromBytes, Copy, Clone, Debug, Default,
+)]
+#[repr(C)]
+pub struct SensorStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    pub readings: u32,
+    pub errors: u32,
+}
+
+///
+/// The thresholds that may be set on a sensor, in the manner of IPMI.  An
+/// upper threshold is crossed when a reading is at or above it, and a lower
+/// threshold when a reading is at or below it; once crossed, a threshold is
+/// only cleared once the reading has moved back past it by more than the
+/// sensor's hysteresis.
+///
+#[derive(zerocopy::AsBytes, Copy, Clone, Debug, FromPrimitive, PartialEq)]
+#[repr(u8)]
+pub enum Threshold {
+    LowerCritical = 0,
+    LowerNonCritical = 1,
+    UpperNonCritical = 2,
+    UpperCritical = 3,
+}
+
+/// Number of distinct thresholds per sensor
+pub const NUM_THRESHOLDS: usize = 4;
+
+impl Threshold {
+    /// Returns the bit that denotes this threshold in an alarm mask
+    pub fn bit(self) -> u8 {
+        1 << (self as u8)
+    }
+
+    pub fn is_upper(self) -> bool {
+        matches!(self, Threshold::UpperNonCritical | Threshold::UpperCritical)
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Reading {
     Absent,
@@ -65,6 +115,10 @@ pub enum SensorError {
     DeviceUnavailable = 5,
     DeviceTimeout = 6,
     DeviceOff = 7,
+    InvalidSample = 8,
+    NoThreshold = 9,
+    InvalidThreshold = 10,
+    SubscriptionsFull = 11,
 }
 
 impl From<NoData> for SensorError {
@@ -3,12 +3,26 @@
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
 //! Sensor management
+//!
+//! In addition to the most recent reading for each sensor, we keep the time
+//! of its last update, summary statistics (minimum, maximum, mean and the
+//! number of readings and errors) and a small ring of its most recent
+//! samples.
+//!
+//! Each sensor may also have upper and lower critical and non-critical
+//! thresholds, set either in the app.toml or via IPC.  When a posted reading
+//! causes a sensor's set of crossed thresholds (its alarms) to change, we
+//! post a notification to any task that has subscribed to that sensor; the
+//! subscriber can then retrieve the sensor's alarms.
 
 #![no_std]
 #![no_main]
 
 use idol_runtime::{NotificationHandler, RequestError};
-use task_sensor_api::{NoData, Reading, SensorError, SensorId};
+use task_sensor_api::{
+    NoData, Reading, SensorError, SensorId, SensorStats, Threshold,
+    NUM_THRESHOLDS, SENSOR_HISTORY,
+};
 use userlib::*;
 
 // This is only included to determine the number of sensors
@@ -17,33 +31,204 @@ include!(concat!(env!("OUT_DIR"), "/i2c_config.rs"));
 use i2c_config::sensors;
 use sensors::NUM_SENSORS;
 
+#[derive(Copy, Clone)]
+struct SensorData {
+    /// most recent reading
+    reading: Reading,
+
+    /// time of most recent reading or error
+    last: u64,
+
+    /// statistics across all readings
+    stats: SensorStats,
+
+    /// ring of recent samples
+    history: [f32; SENSOR_HISTORY],
+
+    /// index of the next sample in `history`
+    next: usize,
+
+    /// thresholds, indexed by `Threshold`
+    thresholds: [Option<f32>; NUM_THRESHOLDS],
+
+    /// hysteresis for clearing a crossed threshold
+    hysteresis: f32,
+
+    /// mask of crossed thresholds
+    alarms: u8,
+}
+
+///
+/// The maximum number of subscriptions, across all tasks and sensors.
+///
+const MAX_SUBSCRIPTIONS: usize = 8;
+
+#[derive(Copy, Clone)]
+struct Subscription {
+    task: TaskId,
+    id: SensorId,
+    notification: u32,
+}
+
+impl SensorData {
+    const fn new() -> Self {
+        Self {
+            reading: Reading::Absent,
+            last: 0,
+            stats: SensorStats {
+                min: 0.0,
+                max: 0.0,
+                mean: 0.0,
+                readings: 0,
+                errors: 0,
+            },
+            history: [0.0; SENSOR_HISTORY],
+            next: 0,
+            thresholds: [None; NUM_THRESHOLDS],
+            hysteresis: 0.0,
+            alarms: 0,
+        }
+    }
+
+    ///
+    /// Posts a new reading, returning true if our alarms have changed as a
+    /// result.
+    ///
+    fn post(&mut self, value: f32, now: u64) -> bool {
+        let stats = &mut self.stats;
+
+        if stats.readings == 0 {
+            stats.min = value;
+            stats.max = value;
+            stats.mean = value;
+        } else {
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+            stats.mean += (value - stats.mean) / (stats.readings + 1) as f32;
+        }
+
+        stats.readings = stats.readings.saturating_add(1);
+
+        self.history[self.next] = value;
+        self.next = (self.next + 1) % SENSOR_HISTORY;
+
+        self.reading = Reading::Value(value);
+        self.last = now;
+
+        self.update_alarms(value)
+    }
+
+    fn update_alarms(&mut self, value: f32) -> bool {
+        let before = self.alarms;
+
+        for (ndx, threshold) in self.thresholds.iter().enumerate() {
+            let t: Threshold = FromPrimitive::from_usize(ndx).unwrap();
+            let bit = t.bit();
+
+            let limit = match threshold {
+                Some(limit) => *limit,
+                None => {
+                    self.alarms &= !bit;
+                    continue;
+                }
+            };
+
+            let (crossed, cleared) = if t.is_upper() {
+                (value >= limit, value < limit - self.hysteresis)
+            } else {
+                (value <= limit, value > limit + self.hysteresis)
+            };
+
+            if crossed {
+                self.alarms |= bit;
+            } else if cleared {
+                self.alarms &= !bit;
+            }
+        }
+
+        self.alarms != before
+    }
+
+    ///
+    /// Re-evaluates our alarms against our most recent reading (if any), as
+    /// when a threshold or the hysteresis has changed, returning true if
+    /// they have changed as a result.
+    ///
+    fn recheck(&mut self) -> bool {
+        match self.reading {
+            Reading::Value(value) => self.update_alarms(value),
+            _ => false,
+        }
+    }
+
+    fn nodata(&mut self, nodata: NoData, now: u64) {
+        self.stats.errors = self.stats.errors.saturating_add(1);
+        self.reading = Reading::NoData(nodata);
+        self.last = now;
+    }
+
+    fn is_absent(&self) -> bool {
+        matches!(self.reading, Reading::Absent)
+    }
+
+    ///
+    /// Returns the `index`th most recent sample, if we have one.
+    ///
+    fn sample(&self, index: usize) -> Option<f32> {
+        let nsamples =
+            core::cmp::min(self.stats.readings as usize, SENSOR_HISTORY);
+
+        if index < nsamples {
+            let ndx = self.next + SENSOR_HISTORY - 1 - index;
+            Some(self.history[ndx % SENSOR_HISTORY])
+        } else {
+            None
+        }
+    }
+}
+
 struct ServerImpl {
-    data: [Reading; NUM_SENSORS],
+    data: [SensorData; NUM_SENSORS],
+    subscriptions: [Option<Subscription>; MAX_SUBSCRIPTIONS],
     deadline: u64,
 }
 
 const TIMER_MASK: u32 = 1 << 0;
 const TIMER_INTERVAL: u64 = 1000;
 
+impl ServerImpl {
+    fn sensor(
+        &mut self,
+        id: SensorId,
+    ) -> Result<&mut SensorData, RequestError<SensorError>> {
+        match self.data.get_mut(id.0) {
+            Some(data) => Ok(data),
+            None => Err(SensorError::InvalidSensor.into()),
+        }
+    }
+
+    fn notify(&self, id: SensorId) {
+        for s in self.subscriptions.iter().flatten() {
+            if s.id == id {
+                sys_post(sys_refresh_task_id(s.task), s.notification);
+            }
+        }
+    }
+}
+
 impl idl::InOrderSensorImpl for ServerImpl {
     fn get(
         &mut self,
         _: &RecvMessage,
         id: SensorId,
     ) -> Result<f32, RequestError<SensorError>> {
-        let index = id.0;
-
-        if index < NUM_SENSORS {
-            match self.data[index] {
-                Reading::Absent => Err(SensorError::NoReading.into()),
-                Reading::NoData(nodata) => {
-                    let err: SensorError = nodata.into();
-                    Err(err.into())
-                }
-                Reading::Value(reading) => Ok(reading),
+        match self.sensor(id)?.reading {
+            Reading::Absent => Err(SensorError::NoReading.into()),
+            Reading::NoData(nodata) => {
+                let err: SensorError = nodata.into();
+                Err(err.into())
             }
-        } else {
-            Err(SensorError::InvalidSensor.into())
+            Reading::Value(reading) => Ok(reading),
         }
     }
 
@@ -53,14 +238,13 @@ impl idl::InOrderSensorImpl for ServerImpl {
         id: SensorId,
         value: f32,
     ) -> Result<(), RequestError<SensorError>> {
-        let index = id.0;
+        let now = sys_get_timer().now;
 
-        if index < NUM_SENSORS {
-            self.data[index] = Reading::Value(value);
-            Ok(())
-        } else {
-            Err(SensorError::InvalidSensor.into())
+        if self.sensor(id)?.post(value, now) {
+            self.notify(id);
         }
+
+        Ok(())
     }
 
     fn nodata(
@@ -69,15 +253,190 @@ impl idl::InOrderSensorImpl for ServerImpl {
         id: SensorId,
         nodata: NoData,
     ) -> Result<(), RequestError<SensorError>> {
-        let index = id.0;
+        let now = sys_get_timer().now;
+        self.sensor(id)?.nodata(nodata, now);
+        Ok(())
+    }
+
+    fn get_timestamp(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+    ) -> Result<u64, RequestError<SensorError>> {
+        let data = self.sensor(id)?;
 
-        if index < NUM_SENSORS {
-            self.data[index] = Reading::NoData(nodata);
-            Ok(())
+        if data.is_absent() {
+            Err(SensorError::NoReading.into())
         } else {
-            Err(SensorError::InvalidSensor.into())
+            Ok(data.last)
         }
     }
+
+    fn get_stats(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+    ) -> Result<SensorStats, RequestError<SensorError>> {
+        let data = self.sensor(id)?;
+
+        if data.is_absent() {
+            Err(SensorError::NoReading.into())
+        } else {
+            Ok(data.stats)
+        }
+    }
+
+    fn get_sample(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+        index: u8,
+    ) -> Result<f32, RequestError<SensorError>> {
+        let data = self.sensor(id)?;
+
+        if index as usize >= SENSOR_HISTORY {
+            return Err(SensorError::InvalidSample.into());
+        }
+
+        match data.sample(index as usize) {
+            Some(sample) => Ok(sample),
+            None => Err(SensorError::NoReading.into()),
+        }
+    }
+
+    fn set_threshold(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+        threshold: Threshold,
+        value: f32,
+    ) -> Result<(), RequestError<SensorError>> {
+        if value.is_nan() {
+            return Err(SensorError::InvalidThreshold.into());
+        }
+
+        let data = self.sensor(id)?;
+        data.thresholds[threshold as usize] = Some(value);
+
+        if data.recheck() {
+            self.notify(id);
+        }
+
+        Ok(())
+    }
+
+    fn clear_threshold(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+        threshold: Threshold,
+    ) -> Result<(), RequestError<SensorError>> {
+        let data = self.sensor(id)?;
+        let alarms = data.alarms;
+
+        data.thresholds[threshold as usize] = None;
+        data.alarms &= !threshold.bit();
+
+        if data.alarms != alarms {
+            self.notify(id);
+        }
+
+        Ok(())
+    }
+
+    fn get_threshold(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+        threshold: Threshold,
+    ) -> Result<f32, RequestError<SensorError>> {
+        match self.sensor(id)?.thresholds[threshold as usize] {
+            Some(value) => Ok(value),
+            None => Err(SensorError::NoThreshold.into()),
+        }
+    }
+
+    fn set_hysteresis(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+        value: f32,
+    ) -> Result<(), RequestError<SensorError>> {
+        if value.is_nan() || value < 0.0 {
+            return Err(SensorError::InvalidThreshold.into());
+        }
+
+        let data = self.sensor(id)?;
+        data.hysteresis = value;
+
+        if data.recheck() {
+            self.notify(id);
+        }
+
+        Ok(())
+    }
+
+    fn get_alarms(
+        &mut self,
+        _: &RecvMessage,
+        id: SensorId,
+    ) -> Result<u8, RequestError<SensorError>> {
+        Ok(self.sensor(id)?.alarms)
+    }
+
+    fn subscribe(
+        &mut self,
+        rm: &RecvMessage,
+        id: SensorId,
+        notification: u32,
+    ) -> Result<(), RequestError<SensorError>> {
+        self.sensor(id)?;
+
+        let task = rm.sender.index();
+        let mut free = None;
+
+        for (ndx, s) in self.subscriptions.iter_mut().enumerate() {
+            match s {
+                Some(s) if s.id == id && s.task.index() == task => {
+                    s.task = rm.sender;
+                    s.notification = notification;
+                    return Ok(());
+                }
+                None if free.is_none() => free = Some(ndx),
+                _ => {}
+            }
+        }
+
+        match free {
+            Some(ndx) => {
+                self.subscriptions[ndx] = Some(Subscription {
+                    task: rm.sender,
+                    id,
+                    notification,
+                });
+                Ok(())
+            }
+            None => Err(SensorError::SubscriptionsFull.into()),
+        }
+    }
+
+    fn unsubscribe(
+        &mut self,
+        rm: &RecvMessage,
+        id: SensorId,
+    ) -> Result<(), RequestError<SensorError>> {
+        self.sensor(id)?;
+
+        for s in self.subscriptions.iter_mut() {
+            if let Some(sub) = s {
+                if sub.id == id && sub.task.index() == rm.sender.index() {
+                    *s = None;
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl NotificationHandler for ServerImpl {
@@ -101,10 +460,19 @@ fn main() -> ! {
     sys_set_timer(Some(deadline), TIMER_MASK);
 
     let mut server = ServerImpl {
-        data: [Reading::Absent; NUM_SENSORS],
+        data: [SensorData::new(); NUM_SENSORS],
+        subscriptions: [None; MAX_SUBSCRIPTIONS],
         deadline,
     };
 
+    for (id, threshold, value) in sensors::SENSOR_THRESHOLDS {
+        server.data[id.0].thresholds[threshold as usize] = Some(value);
+    }
+
+    for (id, value) in sensors::SENSOR_HYSTERESIS {
+        server.data[id.0].hysteresis = value;
+    }
+
     let mut buffer = [0; idl::INCOMING_SIZE];
 
     loop {
@@ -113,7 +481,7 @@ fn main() -> ! {
 }
 
 mod idl {
-    use super::{NoData, SensorError, SensorId};
+    use super::{NoData, SensorError, SensorId, SensorStats, Threshold};
 
     include!(concat!(env!("OUT_DIR"), "/server_stub.rs"));
 }
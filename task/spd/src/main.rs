@@ -17,6 +17,12 @@
 //! use AMD's default of an LTC4306, but only implement two segments, as the
 //! limit of the proxy is 16 total DIMMs.
 //!
+//! With the `ddr5` feature, we instead support DDR5 DIMMs, on which the SPD
+//! is a 1 KB SPD5118 hub that selects its pages via a mode register rather
+//! than via a page address; see `spd5.rs` for details.  (As this doubles the
+//! size of the SPD data, the task needs 32 KiB of RAM rather than 16 KiB; see
+//! `app/gimletlet/app-ddr5.toml`.)
+//!
 
 #![no_std]
 #![no_main]
@@ -34,6 +40,23 @@ task_slot!(I2C, i2c_driver);
 
 mod ltc4306;
 
+#[cfg(feature = "ddr5")]
+mod spd5;
+
+/// Maximum number of DIMMs that we can proxy
+const MAX_DIMMS: usize = 16;
+
+cfg_if::cfg_if! {
+    if #[cfg(feature = "ddr5")] {
+        use spd5::SPD_SIZE;
+
+        static mut HUBS: [spd5::Hub; MAX_DIMMS] =
+            [spd5::Hub::new(); MAX_DIMMS];
+    } else {
+        const SPD_SIZE: usize = spd::MAX_SIZE;
+    }
+}
+
 fn configure_pins(pins: &[I2cPin]) {
     let sys = SYS.get_task_id();
     let sys = Sys::from(sys);
@@ -53,7 +76,7 @@ fn configure_pins(pins: &[I2cPin]) {
 //
 // This is an excellent candidate to put into a non-DTCM memory region
 //
-static mut SPD_DATA: [u8; 8192] = [0; 8192];
+static mut SPD_DATA: [u8; MAX_DIMMS * SPD_SIZE] = [0; MAX_DIMMS * SPD_SIZE];
 
 const LTC4306_ADDRESS: u8 = 0b1001_010;
 type Bank = (Controller, drv_i2c_api::PortIndex, Option<(Mux, Segment)>);
@@ -70,6 +93,8 @@ enum Trace {
     Absent(u8, u8, usize),
     ReadTop(usize),
     ReadBottom(usize),
+    #[cfg(feature = "ddr5")]
+    ReadHub(usize),
     MemInitiate(usize),
     MemSetOffset(usize, u8),
     MuxState(ltc4306::State, ltc4306::State),
@@ -80,6 +105,7 @@ ringbuf!(Trace, 16, Trace::None);
 
 include!(concat!(env!("OUT_DIR"), "/i2c_config.rs"));
 
+#[cfg(not(feature = "ddr5"))]
 fn read_spd_data(
     banks: &[Bank],
     present: &mut [bool],
@@ -236,10 +262,16 @@ fn main() -> ! {
     let mut present = [false; BANKS.len() * spd::MAX_DEVICES as usize];
 
     // Virtual offset, per virtual DIMM
+    #[cfg(not(feature = "ddr5"))]
     let mut voffs = [0u8; BANKS.len() * spd::MAX_DEVICES as usize];
 
     // The actual SPD data itself
     let spd_data = unsafe { &mut SPD_DATA };
+
+    // Our virtual SPD5118 hubs
+    #[cfg(feature = "ddr5")]
+    let hubs = RefCell::new(unsafe { &mut HUBS });
+
     let mut ndelay = 0;
 
     //
@@ -248,8 +280,17 @@ fn main() -> ! {
     // waiting on the sequencer, loop until we have found DIMMs.
     //
     loop {
+        #[cfg(not(feature = "ddr5"))]
         let ndimms = read_spd_data(&BANKS, &mut present, &mut spd_data[..]);
 
+        #[cfg(feature = "ddr5")]
+        let ndimms = spd5::read_spd_data(
+            &BANKS,
+            &mut present,
+            &mut spd_data[..],
+            &mut hubs.borrow_mut()[..],
+        );
+
         ringbuf_entry!(Trace::Found(ndimms));
 
         if ndimms != 0 {
@@ -277,6 +318,7 @@ fn main() -> ! {
     let ltc4306 = Cell::new(ltc4306::State::init());
     let vbank = Cell::new(Some(0u8));
     let page = Cell::new(spd::Page(0));
+    #[cfg(not(feature = "ddr5"))]
     let voffs = RefCell::new(&mut voffs);
 
     //
@@ -287,11 +329,16 @@ fn main() -> ! {
         let rval = if let Some(func) = spd::Function::from_device_code(addr) {
             if let Some(bank) = vbank.get() {
                 match func {
+                    #[cfg(not(feature = "ddr5"))]
                     spd::Function::PageAddress(_) => true,
                     spd::Function::Memory(device) => {
                         let base = (bank * spd::MAX_DEVICES) as usize;
                         let ndx = base + device as usize;
                         ringbuf_entry!(Trace::MemInitiate(ndx));
+
+                        #[cfg(feature = "ddr5")]
+                        hubs.borrow_mut()[ndx].initiate();
+
                         present[ndx]
                     }
                     _ => false,
@@ -346,6 +393,7 @@ fn main() -> ! {
                     page.set(p);
                 }
 
+                #[cfg(not(feature = "ddr5"))]
                 spd::Function::Memory(device) => {
                     //
                     // This is always an offset.
@@ -355,6 +403,13 @@ fn main() -> ! {
                     ringbuf_entry!(Trace::MemSetOffset(ndx, byte));
                     voffs.borrow_mut()[ndx] = byte;
                 }
+
+                #[cfg(feature = "ddr5")]
+                spd::Function::Memory(device) => {
+                    let base = (bank * spd::MAX_DEVICES) as usize;
+                    let ndx = base + device as usize;
+                    hubs.borrow_mut()[ndx].rx(byte);
+                }
                 _ => {}
             }
         }
@@ -372,12 +427,37 @@ fn main() -> ! {
             let bank = vbank.get().unwrap();
 
             match spd::Function::from_device_code(addr).unwrap() {
+                #[cfg(feature = "ddr5")]
+                spd::Function::Memory(device) => {
+                    let base = (bank * spd::MAX_DEVICES) as usize;
+                    let ndx = base + device as usize;
+                    let offs = ndx * SPD_SIZE;
+                    let data = &spd_data[offs..offs + SPD_SIZE];
+
+                    //
+                    // The hub that we're proxying, should we need to read
+                    // its live registers.
+                    //
+                    let (controller, port, mux) = BANKS[bank as usize];
+                    let dev = I2cDevice::new(
+                        I2C.get_task_id(),
+                        controller,
+                        port,
+                        mux,
+                        addr,
+                    );
+                    let hub = drv_i2c_devices::spd5118::Spd5118::new(&dev);
+
+                    Some(hubs.borrow_mut()[ndx].tx(data, &hub))
+                }
+
+                #[cfg(not(feature = "ddr5"))]
                 spd::Function::Memory(device) => {
                     let base = (bank * spd::MAX_DEVICES) as usize;
                     let ndx = base + device as usize;
 
                     let mut voffs = voffs.borrow_mut();
-                    let offs = (ndx * spd::MAX_SIZE) + voffs[ndx] as usize;
+                    let offs = (ndx * SPD_SIZE) + voffs[ndx] as usize;
                     let rbyte = spd_data[offs + page.get().offset()];
 
                     //
@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//
+// DDR5 support.  On DDR5 DIMMs, the SPD EEPROM and temperature sensor are
+// both behind an SPD5118 hub at the DIMM's memory address; there is no page
+// address to write, as the page is instead selected by the hub's MR11.  We
+// read all 1 KB of each hub's memory along with a snapshot of its registers,
+// and then present a virtual hub to the SoC that serves both.  Writes to the
+// virtual hub are ignored, with the exception of the page bits of MR11.
+//
+// Register reads are served from the snapshot, with the exception of the
+// temperature sensor's registers:  these are read from the hub when the SoC
+// reads them, lest it see a temperature that is frozen in time.  (The bus to
+// the SoC is stretched while we do so.)
+//
+
+use super::*;
+use drv_i2c_devices::spd5118::{
+    self, Spd5118, DEVICE_TYPE, MEMORY, NUM_REGISTERS, PAGE_MASK, PAGE_SIZE,
+};
+
+/// Size of the SPD data for each DIMM
+pub const SPD_SIZE: usize = spd5118::NVM_SIZE;
+
+/// MR11, which selects the page of memory
+const LEGACY_MODE_CONFIGURATION: u8 =
+    spd5118::Register::LegacyModeConfiguration as u8;
+
+/// MR49 and MR50, which hold the temperature
+const TS_TEMPERATURE_LOW: u8 = spd5118::Register::TsTemperatureLow as u8;
+const TS_TEMPERATURE_HIGH: u8 = spd5118::Register::TsTemperatureHigh as u8;
+
+#[derive(Copy, Clone)]
+pub struct Hub {
+    /// snapshot of the hub's registers
+    registers: [u8; NUM_REGISTERS],
+
+    /// address byte for the current transaction
+    addr: u8,
+
+    /// true if the next byte we receive is an address byte
+    expect_addr: bool,
+
+    /// true if MR50 was read from the hub along with MR49 for this read,
+    /// and should be served as is
+    temperature_read: bool,
+}
+
+impl Hub {
+    pub const fn new() -> Self {
+        Self {
+            registers: [0; NUM_REGISTERS],
+            addr: 0,
+            expect_addr: false,
+            temperature_read: false,
+        }
+    }
+
+    fn page(&self) -> usize {
+        (self.registers[LEGACY_MODE_CONFIGURATION as usize] & PAGE_MASK)
+            as usize
+    }
+
+    //
+    // The address auto-increments within the register space or within the
+    // current page of memory.
+    //
+    fn advance(&mut self) {
+        self.addr =
+            (self.addr & MEMORY) | (self.addr.wrapping_add(1) & !MEMORY);
+    }
+
+    pub fn initiate(&mut self) {
+        self.expect_addr = true;
+        self.temperature_read = false;
+    }
+
+    ///
+    /// Reads the temperature registers from the hub into our snapshot.  If
+    /// this fails, we serve the last temperature that we read.
+    ///
+    fn read_temperature(&mut self, hub: &Spd5118) {
+        if let Ok(t) = hub.read_temperature_registers() {
+            let low = TS_TEMPERATURE_LOW as usize;
+            self.registers[low..low + t.len()].copy_from_slice(&t);
+        }
+    }
+
+    pub fn rx(&mut self, byte: u8) {
+        if self.expect_addr {
+            self.addr = byte;
+            self.expect_addr = false;
+            return;
+        }
+
+        if self.addr == LEGACY_MODE_CONFIGURATION {
+            let reg = &mut self.registers[self.addr as usize];
+            *reg = (*reg & !PAGE_MASK) | (byte & PAGE_MASK);
+        }
+
+        self.advance();
+    }
+
+    pub fn tx(&mut self, spd_data: &[u8], hub: &Spd5118) -> u8 {
+        //
+        // A read of MR49 reads MR50 along with it, so that a read of both
+        // (as the SoC will do) sees a coherent temperature.
+        //
+        match self.addr {
+            TS_TEMPERATURE_LOW => {
+                self.read_temperature(hub);
+                self.temperature_read = true;
+            }
+            TS_TEMPERATURE_HIGH if !self.temperature_read => {
+                self.read_temperature(hub);
+            }
+            _ => {
+                self.temperature_read = false;
+            }
+        }
+
+        let rval = if self.addr & MEMORY != 0 {
+            let offs = self.page() * PAGE_SIZE + (self.addr & !MEMORY) as usize;
+            spd_data[offs]
+        } else {
+            self.registers[self.addr as usize]
+        };
+
+        self.advance();
+        rval
+    }
+}
+
+pub fn read_spd_data(
+    banks: &[Bank],
+    present: &mut [bool],
+    spd_data: &mut [u8],
+    hubs: &mut [Hub],
+) -> usize {
+    let i2c_task = I2C.get_task_id();
+    let mut npresent = 0;
+
+    for nbank in 0..banks.len() as u8 {
+        let (controller, port, mux) = banks[nbank as usize];
+
+        for i in 0..spd::MAX_DEVICES {
+            let mem = spd::Function::Memory(i).to_device_code().unwrap();
+            let dev = I2cDevice::new(i2c_task, controller, port, mux, mem);
+            let hub = Spd5118::new(&dev);
+            let ndx = (nbank * spd::MAX_DEVICES) as usize + i as usize;
+            let offs = ndx * SPD_SIZE;
+            let registers = &mut hubs[ndx].registers;
+
+            //
+            // If we can't read the registers, we will assume that the device
+            // isn't present.
+            //
+            match hub.read_registers(&mut registers[..]) {
+                Ok(_) if registers[..DEVICE_TYPE.len()] == DEVICE_TYPE => {}
+                _ => {
+                    ringbuf_entry_root!(Trace::Absent(nbank, i, ndx));
+                    continue;
+                }
+            }
+
+            ringbuf_entry_root!(Trace::ReadHub(ndx));
+
+            //
+            // Read all of memory, and then flip back to the first page and
+            // take our snapshot of the registers again, that they might
+            // reflect the state in which we found the hub.
+            //
+            let data = &mut spd_data[offs..offs + SPD_SIZE];
+
+            if hub.read_memory(0, data).is_err()
+                || hub.set_page(0).is_err()
+                || hub.read_registers(&mut registers[..]).is_err()
+            {
+                ringbuf_entry_root!(Trace::Absent(nbank, i, ndx));
+                continue;
+            }
+
+            ringbuf_entry_root!(Trace::Present(nbank, i, ndx));
+            present[ndx] = true;
+            npresent += 1;
+        }
+    }
+
+    npresent
+}
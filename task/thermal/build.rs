@@ -2,9 +2,54 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at https://mozilla.org/MPL/2.0/.
 
+use serde::Deserialize;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// The task's (optional) configuration, which is only present on boards with
+/// a sequencer that we can ask to power off.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ThermalConfig {
+    /// number of consecutive readings at or above a sensor's critical
+    /// threshold after which we power off
+    debounce: u32,
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     build_util::expose_target_board();
-    build_i2c::codegen(build_i2c::Disposition::Sensors)?;
+    build_i2c::codegen(build_i2c::Disposition::Thermal)?;
+
+    let out_dir = env::var("OUT_DIR")?;
+    let dest_path = Path::new(&out_dir).join("thermal_config.rs");
+    let mut file = File::create(&dest_path)?;
+
+    println!("cargo:rerun-if-env-changed=HUBRIS_TASK_CONFIG");
+
+    //
+    // The sequencer's API is only a dependency with the `thermal-shutdown`
+    // feature, which must come with the configuration (and vice versa).
+    //
+    let shutdown = env::var("CARGO_FEATURE_THERMAL_SHUTDOWN").is_ok();
+
+    if env::var("HUBRIS_TASK_CONFIG").is_ok() {
+        if !shutdown {
+            return Err("the thermal task's config needs the \
+                `thermal-shutdown` feature"
+                .into());
+        }
+
+        let config = build_util::task_config::<ThermalConfig>()?;
+
+        println!("cargo:rustc-cfg=thermal_shutdown");
+        writeln!(file, "const DEBOUNCE: u32 = {};", config.debounce)?;
+    } else if shutdown {
+        return Err("the `thermal-shutdown` feature needs the thermal task's \
+            config (with its `debounce`)"
+            .into());
+    }
 
     idol::server::build_server_support(
         "../../idl/thermal.idol",
@@ -8,6 +8,10 @@
 //! sensors and control fan duty cycles to actively manage thermals.  Right now,
 //! though it is merely reading every fan and temp sensor that it can find...
 //!
+//! The sensors and fan controllers are generated from the I2C devices in
+//! app.toml, so the task isn't specific to any one board.  On boards with a
+//! sequencer, it also acts as a backstop (see the `shutdown` module).
+//!
 
 #![no_std]
 #![no_main]
@@ -15,6 +19,7 @@
 use drv_i2c_api::ResponseCode;
 use drv_i2c_devices::max31790::*;
 use drv_i2c_devices::sbtsi::*;
+use drv_i2c_devices::spd5118::*;
 use drv_i2c_devices::tmp117::*;
 use drv_i2c_devices::tmp451::*;
 use drv_i2c_devices::tse2004av::*;
@@ -27,30 +32,35 @@ use userlib::*;
 
 use sensor_api::SensorId;
 
+#[cfg(thermal_shutdown)]
+mod shutdown;
+
 task_slot!(I2C, i2c_driver);
 task_slot!(SENSOR, sensor);
 
 include!(concat!(env!("OUT_DIR"), "/i2c_config.rs"));
-use i2c_config::devices;
-use i2c_config::sensors;
+use i2c_config::thermal;
 
-enum Zone {
-    East,
-    Central,
-    West,
-}
+use thermal::{NUM_FAN_CONTROLLERS, NUM_TEMPERATURE_SENSORS};
 
 enum Device {
-    North(Zone, Tmp117),
-    South(Zone, Tmp117),
-    T6Nic(Tmp451),
+    Tmp117(Tmp117),
+    Tmp451(Tmp451),
     CPU(Sbtsi),
     Dimm(Tse2004Av),
+    Ddr5Dimm(Spd5118),
 }
 
 struct Sensor {
     device: Device,
     id: SensorId,
+    #[cfg(thermal_shutdown)]
+    trip: shutdown::Trip,
+}
+
+struct FanController {
+    device: Max31790,
+    speed_sensors: &'static [SensorId],
 }
 
 fn temp_read<E, T: TempSensor<E>>(
@@ -68,153 +78,66 @@ where
     }
 }
 
+impl From<thermal::TemperatureSensor> for Sensor {
+    fn from(s: thermal::TemperatureSensor) -> Self {
+        use thermal::Driver;
+
+        let device = match s.driver {
+            Driver::Tmp117 => Device::Tmp117(Tmp117::new(&s.device)),
+            Driver::Tmp451 => {
+                Device::Tmp451(Tmp451::new(&s.device, Target::Remote))
+            }
+            Driver::Sbtsi => Device::CPU(Sbtsi::new(&s.device)),
+            Driver::Tse2004Av => Device::Dimm(Tse2004Av::new(&s.device)),
+            Driver::Spd5118 => Device::Ddr5Dimm(Spd5118::new(&s.device)),
+        };
+
+        Self {
+            device,
+            id: s.id,
+            #[cfg(thermal_shutdown)]
+            trip: shutdown::Trip::new(s.critical),
+        }
+    }
+}
+
 impl Sensor {
     fn read_temp(&mut self) -> Result<Celsius, ResponseCode> {
         match &mut self.device {
-            Device::North(_, dev) | Device::South(_, dev) => temp_read(dev),
-            Device::T6Nic(dev) => temp_read(dev),
+            Device::Tmp117(dev) => temp_read(dev),
+            Device::Tmp451(dev) => temp_read(dev),
             Device::CPU(dev) => temp_read(dev),
             Device::Dimm(dev) => temp_read(dev),
+            Device::Ddr5Dimm(dev) => temp_read(dev),
         }
     }
 }
 
-const NUM_TEMPERATURE_SENSORS: usize = sensors::NUM_TMP117_TEMPERATURE_SENSORS
-    + sensors::NUM_TMP451_TEMPERATURE_SENSORS
-    + sensors::NUM_SBTSI_TEMPERATURE_SENSORS
-    + sensors::NUM_TSE2004AV_TEMPERATURE_SENSORS;
-
 fn temperature_sensors() -> [Sensor; NUM_TEMPERATURE_SENSORS] {
-    let task = I2C.get_task_id();
-
-    [
-        // North and south zones are inverted with respect to one another;
-        // see Gimlet issue #1302 for details.
-        Sensor {
-            device: Device::North(
-                Zone::East,
-                Tmp117::new(&devices::tmp117_northeast(task)),
-            ),
-            id: sensors::TMP117_NORTHEAST_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::North(
-                Zone::Central,
-                Tmp117::new(&devices::tmp117_north(task)),
-            ),
-            id: sensors::TMP117_NORTH_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::North(
-                Zone::West,
-                Tmp117::new(&devices::tmp117_northwest(task)),
-            ),
-            id: sensors::TMP117_NORTHWEST_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::South(
-                Zone::East,
-                Tmp117::new(&devices::tmp117_southeast(task)),
-            ),
-            id: sensors::TMP117_SOUTHEAST_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::South(
-                Zone::Central,
-                Tmp117::new(&devices::tmp117_south(task)),
-            ),
-            id: sensors::TMP117_SOUTH_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::South(
-                Zone::West,
-                Tmp117::new(&devices::tmp117_southwest(task)),
-            ),
-            id: sensors::TMP117_SOUTHWEST_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::CPU(Sbtsi::new(&devices::sbtsi(task)[0])),
-            id: sensors::SBTSI_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::T6Nic(Tmp451::new(
-                &devices::tmp451(task)[0],
-                Target::Remote,
-            )),
-            id: sensors::TMP451_TEMPERATURE_SENSOR,
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[0])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[0],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[1])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[1],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[2])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[2],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[3])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[3],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[4])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[4],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[5])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[5],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[6])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[6],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[7])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[7],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[8])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[8],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[9])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[9],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[10])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[10],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[11])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[11],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[12])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[12],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[13])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[13],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[14])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[14],
-        },
-        Sensor {
-            device: Device::Dimm(Tse2004Av::new(&devices::tse2004av(task)[15])),
-            id: sensors::TSE2004AV_TEMPERATURE_SENSORS[15],
-        },
-    ]
+    thermal::temperature_sensors(I2C.get_task_id()).map(Sensor::from)
+}
+
+fn fan_controllers() -> [FanController; NUM_FAN_CONTROLLERS] {
+    thermal::fan_controllers(I2C.get_task_id()).map(|c| {
+        let device = Max31790::new(&c.device);
+        device.initialize().unwrap();
+
+        FanController {
+            device,
+            speed_sensors: c.speed_sensors,
+        }
+    })
 }
 
 struct ServerImpl {
     sensor: sensor_api::Sensor,
     sensors: [Sensor; NUM_TEMPERATURE_SENSORS],
-    fctrl: Max31790,
+    /// fan controllers, whose fans are numbered consecutively: fan `n` of
+    /// controller `c` is fan `c * MAX_FANS + n`
+    fans: [FanController; NUM_FAN_CONTROLLERS],
     deadline: u64,
+    #[cfg(thermal_shutdown)]
+    backstop: shutdown::Backstop,
 }
 
 const TIMER_MASK: u32 = 1 << 0;
@@ -222,19 +145,15 @@ const TIMER_INTERVAL: u64 = 1000;
 
 impl ServerImpl {
     fn read_fans(&self) {
-        let ids = &sensors::MAX31790_SPEED_SENSORS;
-
-        for ndx in 0..MAX_FANS {
-            let fan = Fan::from(ndx);
-
-            match self.fctrl.fan_rpm(fan) {
-                Ok(reading) => {
-                    self.sensor
-                        .post(ids[ndx as usize], reading.0.into())
-                        .unwrap();
-                }
-                Err(e) => {
-                    self.sensor.nodata(ids[ndx as usize], e.into()).unwrap()
+        for c in &self.fans {
+            for (ndx, &id) in c.speed_sensors.iter().enumerate() {
+                let fan = Fan::from(ndx as u8);
+
+                match c.device.fan_rpm(fan) {
+                    Ok(reading) => {
+                        self.sensor.post(id, reading.0.into()).unwrap();
+                    }
+                    Err(e) => self.sensor.nodata(id, e.into()).unwrap(),
                 }
             }
         }
@@ -248,11 +167,15 @@ impl idl::InOrderThermalImpl for ServerImpl {
         index: u8,
         pwm: u8,
     ) -> Result<(), RequestError<ThermalError>> {
-        if index < MAX_FANS {
-            let fan = Fan::from(index);
+        let controller = (index / MAX_FANS) as usize;
+
+        if controller < NUM_FAN_CONTROLLERS {
+            let fan = Fan::from(index % MAX_FANS);
 
             if pwm <= 100 {
-                match self.fctrl.set_pwm(fan, PWMDuty(pwm)) {
+                let device = &self.fans[controller].device;
+
+                match device.set_pwm(fan, PWMDuty(pwm)) {
                     Ok(_) => Ok(()),
                     Err(_) => Err(ThermalError::DeviceError.into()),
                 }
@@ -276,33 +199,31 @@ impl NotificationHandler for ServerImpl {
 
         self.read_fans();
 
+        #[cfg(thermal_shutdown)]
+        let mut tripped = None;
+
         for s in &mut self.sensors {
             match s.read_temp() {
                 Ok(reading) => {
                     self.sensor.post(s.id, reading.0).unwrap();
+
+                    #[cfg(thermal_shutdown)]
+                    if let Some(critical) = s.trip.check(reading) {
+                        tripped = tripped.or(Some((s.id, reading, critical)));
+                    }
                 }
                 Err(e) => self.sensor.nodata(s.id, e.into()).unwrap(),
             };
         }
+
+        #[cfg(thermal_shutdown)]
+        self.backstop.update(tripped);
     }
 }
 
 #[export_name = "main"]
 fn main() -> ! {
-    let task = I2C.get_task_id();
-
-    cfg_if::cfg_if! {
-        if #[cfg(any(
-            target_board = "gimlet-a",
-            target_board = "gimlet-b",
-        ))] {
-            let fctrl = Max31790::new(&devices::max31790(task)[0]);
-        } else {
-            compile_error!("unknown board");
-        }
-    }
-
-    fctrl.initialize().unwrap();
+    let fans = fan_controllers();
 
     let deadline = sys_get_timer().now;
 
@@ -314,8 +235,10 @@ fn main() -> ! {
     let mut server = ServerImpl {
         sensor: sensor_api::Sensor::from(SENSOR.get_task_id()),
         sensors: temperature_sensors(),
-        fctrl: fctrl,
+        fans,
         deadline,
+        #[cfg(thermal_shutdown)]
+        backstop: shutdown::Backstop::new(),
     };
 
     let mut buffer = [0; idl::INCOMING_SIZE];
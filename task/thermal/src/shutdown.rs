@@ -0,0 +1,147 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at https://mozilla.org/MPL/2.0/.
+
+//! Thermal shutdown
+//!
+//! If the task is configured with a `debounce` (which means that the board
+//! has a sequencer for us to talk to), we act as a backstop:  if any
+//! temperature sensor reads at or above its critical threshold for `debounce`
+//! consecutive readings, we ask the sequencer to drop the system to A2.  The
+//! sequencer records the trip in its history, which survives restarts (of the
+//! sequencer, and of the SP); it can be retrieved via the sequencer's
+//! `thermal_trip_history` operation.
+
+use drv_gimlet_seq_api as seq_api;
+use ringbuf::*;
+use task_sensor_api::SensorId;
+use userlib::units::*;
+use userlib::*;
+
+use seq_api::{PowerReason, PowerState, SeqError};
+
+task_slot!(SEQUENCER, gimlet_seq);
+
+include!(concat!(env!("OUT_DIR"), "/thermal_config.rs"));
+
+///
+/// Failures to talk to the sequencer.  (The trips themselves are recorded by
+/// the sequencer.)
+///
+#[derive(Copy, Clone, PartialEq)]
+enum Trace {
+    TripFailed(SensorId, SeqError),
+    StateUnknown(SeqError),
+    PowerOffFailed(PowerState, SeqError),
+    None,
+}
+
+ringbuf!(Trace, 16, Trace::None);
+
+/// The critical threshold of a sensor, and how long it has been exceeded.
+pub struct Trip {
+    critical: Option<Celsius>,
+    overtemp: u32,
+}
+
+impl Trip {
+    pub fn new(critical: Option<f32>) -> Self {
+        Self {
+            critical: critical.map(Celsius),
+            overtemp: 0,
+        }
+    }
+
+    ///
+    /// Checks a reading against our critical threshold (if any), returning
+    /// the threshold if we have been at or above it for `DEBOUNCE`
+    /// consecutive readings.
+    ///
+    pub fn check(&mut self, reading: Celsius) -> Option<Celsius> {
+        let critical = self.critical?;
+
+        if reading.0 >= critical.0 {
+            self.overtemp = self.overtemp.saturating_add(1);
+        } else {
+            self.overtemp = 0;
+        }
+
+        if self.overtemp >= DEBOUNCE {
+            Some(critical)
+        } else {
+            None
+        }
+    }
+}
+
+pub struct Backstop {
+    sequencer: seq_api::Sequencer,
+    /// whether a sensor was critical at our last reading
+    tripped: bool,
+}
+
+impl Backstop {
+    pub fn new() -> Self {
+        Self {
+            sequencer: seq_api::Sequencer::from(SEQUENCER.get_task_id()),
+            tripped: false,
+        }
+    }
+
+    ///
+    /// Acts on the latest readings, given the first sensor (if any) that
+    /// has tripped -- with its reading and critical threshold.
+    ///
+    pub fn update(&mut self, tripped: Option<(SensorId, Celsius, Celsius)>) {
+        match tripped {
+            Some((id, reading, critical)) => {
+                self.shutdown(id, reading, critical)
+            }
+            None => self.tripped = false,
+        }
+    }
+
+    ///
+    /// Drops the system to A2 in response to a sensor having been at or
+    /// above its critical threshold.
+    ///
+    /// We are called every second for as long as the trip lasts.  On the
+    /// transition into it, we ask the sequencer to record the trip and drop
+    /// to A2; after that, we only keep the system in A2 (e.g. if it has been
+    /// powered back on), so that the sequencer records a transition rather
+    /// than another trip, and the original record isn't lost.
+    ///
+    fn shutdown(&mut self, id: SensorId, reading: Celsius, critical: Celsius) {
+        let first = !self.tripped;
+        self.tripped = true;
+
+        if first {
+            if let Err(e) =
+                self.sequencer
+                    .thermal_trip(id.0 as u32, reading.0, critical.0)
+            {
+                ringbuf_entry!(Trace::TripFailed(id, e));
+            }
+            return;
+        }
+
+        let state = match self.sequencer.get_state() {
+            Ok(state) => state,
+            Err(e) => {
+                ringbuf_entry!(Trace::StateUnknown(e));
+                return;
+            }
+        };
+
+        if state == PowerState::A2 {
+            return;
+        }
+
+        if let Err(e) = self
+            .sequencer
+            .set_state_with_reason(PowerState::A2, PowerReason::ThermalTrip)
+        {
+            ringbuf_entry!(Trace::PowerOffFailed(state, e));
+        }
+    }
+}
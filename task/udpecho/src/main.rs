@@ -10,38 +10,58 @@ use userlib::*;
 
 task_slot!(NET, net);
 
+const SOCKET: SocketName = SocketName::echo;
+
 #[export_name = "main"]
 fn main() -> ! {
     let net = NET.get_task_id();
     let net = Net::from(net);
 
-    const SOCKET: SocketName = SocketName::echo;
-
     loop {
-        // Tiiiiiny payload buffer
-        let mut rx_data_buf = [0u8; 64];
-        match net.recv_packet(SOCKET, &mut rx_data_buf) {
-            Ok(meta) => {
-                // A packet! We want to turn it right around. Deserialize the
-                // packet header; unwrap because we trust the server.
+        match echo(&net) {
+            Ok(()) => {
                 UDP_ECHO_COUNT
                     .fetch_add(1, core::sync::atomic::Ordering::Relaxed);
-                // Now we know how many bytes to return.
-                let tx_bytes = &rx_data_buf[..meta.size as usize];
-
-                net.send_packet(SOCKET, meta, tx_bytes).unwrap();
             }
             Err(NetError::QueueEmpty) => {
                 // Our incoming queue is empty. Wait for more packets.
                 sys_recv_closed(&mut [], 1, TaskId::KERNEL).unwrap();
             }
-            Err(NetError::NotYours) => panic!(),
-            Err(NetError::InvalidVLan) => panic!(),
+            Err(_) => panic!(),
         }
 
         // Try again.
     }
 }
 
+/// Receives a packet and turns it right around.
+#[cfg(not(feature = "loan"))]
+fn echo(net: &Net) -> Result<(), NetError> {
+    // Tiiiiiny payload buffer
+    let mut rx_data_buf = [0u8; 64];
+    let meta = net.recv_packet(SOCKET, &mut rx_data_buf)?;
+
+    // Now we know how many bytes to return.
+    let tx_bytes = &rx_data_buf[..meta.size as usize];
+
+    net.send_packet(SOCKET, meta, tx_bytes).unwrap();
+    Ok(())
+}
+
+/// Turns a packet right around without copying it out of the socket's
+/// shared region, releasing it once it has been sent.
+#[cfg(feature = "loan")]
+fn echo(net: &Net) -> Result<(), NetError> {
+    let packet = net.peek_packet(SOCKET)?;
+
+    // Safety: the socket's region is in our `uses`, and we're done with the
+    // payload before we release it.
+    let payload = unsafe { packet.payload() };
+
+    net.send_packet(SOCKET, packet.metadata, payload).unwrap();
+    net.release_packet(SOCKET, packet.payload).unwrap();
+    Ok(())
+}
+
 static UDP_ECHO_COUNT: core::sync::atomic::AtomicU32 =
     core::sync::atomic::AtomicU32::new(0);